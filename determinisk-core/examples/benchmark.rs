@@ -1,8 +1,29 @@
 //! Simple performance benchmark
 
-use determinisk_core::{Scalar, Vec2, Circle, World};
+use determinisk_core::{Scalar, Vec2, Circle, World, SpatialGrid};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::Instant;
 
+/// System allocator that counts allocations, for the broad-phase section
+struct CountingAlloc;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+    
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
 fn main() {
     println!("Performance Benchmark\n");
     
@@ -61,4 +82,28 @@ fn main() {
     
     println!("\nNote: This is without collision detection.");
     println!("Performance will decrease significantly with collisions enabled.");
+    
+    // Broad phase runs every step, so its allocations add up in dense scenes
+    println!("\nBroad-phase allocations per SpatialGrid::build\n");
+    println!("Circles | Cells | Allocations");
+    println!("--------|-------|------------");
+    
+    for &num_circles in &[100, 200, 500] {
+        // Packed so most circles straddle cell edges
+        let circles: Vec<Circle> = (0..num_circles)
+            .map(|i| Circle::new(
+                Vec2::new((i % 25) as f32 * 7.0 + 10.0, (i / 25) as f32 * 7.0 + 10.0),
+                Scalar::from_float(5.0),
+                Scalar::from_float(1.0),
+            ))
+            .collect();
+        let cell_size = SpatialGrid::default_cell_size(&circles);
+        let size = Scalar::from_float(1000.0);
+        
+        let before = ALLOCATIONS.load(Ordering::Relaxed);
+        let grid = SpatialGrid::build(&circles, cell_size, size, size);
+        let allocations = ALLOCATIONS.load(Ordering::Relaxed) - before;
+        
+        println!("{:7} | {:5} | {:11}", num_circles, grid.occupancy().len(), allocations);
+    }
 }
\ No newline at end of file
@@ -50,7 +50,7 @@ fn main() {
                 println!("  Circle {}: pos={}, vel={}",
                     i + 1,
                     circle.position,
-                    circle.velocity,
+                    circle.velocity(),
                 );
             }
             
@@ -83,7 +83,7 @@ fn main() {
         println!("Circle {}: pos={}, vel={}",
             i + 1,
             circle.position,
-            circle.velocity,
+            circle.velocity(),
         );
     }
 }
\ No newline at end of file
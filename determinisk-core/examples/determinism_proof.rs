@@ -1,25 +1,12 @@
 //! Prove deterministic behavior across multiple runs
 
-use determinisk_core::{Scalar, Vec2, Circle, World};
-use sha2::{Sha256, Digest};
-
-fn hash_world_state(world: &World) -> String {
-    let mut hasher = Sha256::new();
-    
-    // Hash all circle positions and velocities
-    for circle in &world.circles {
-        hasher.update(&circle.position.x.to_bits().to_le_bytes());
-        hasher.update(&circle.position.y.to_bits().to_le_bytes());
-        hasher.update(&circle.old_position.x.to_bits().to_le_bytes());
-        hasher.update(&circle.old_position.y.to_bits().to_le_bytes());
-    }
-    
-    // Convert to hex string
-    format!("{:x}", hasher.finalize())
-}
+use determinisk_core::{Scalar, Vec2, Circle, RestitutionRule, World};
+use determinisk_core::determinism::verify_runs;
 
 fn create_complex_world() -> World {
     let mut world = World::new(200.0, 200.0);
+    // Use each ball's own restitution below
+    world.collision_config.restitution_rule = Some(RestitutionRule::GeometricMean);
     
     // Add multiple balls with various initial conditions
     for i in 0..5 {
@@ -47,80 +34,34 @@ fn main() {
     
     // Run the same simulation multiple times
     const NUM_RUNS: usize = 5;
-    const STEPS_PER_RUN: usize = 1000;
+    const STEPS_PER_RUN: u32 = 1000;
     
-    let mut hashes: Vec<Vec<String>> = vec![];
-    
-    for run in 0..NUM_RUNS {
-        println!("Run {}: Simulating {} steps...", run + 1, STEPS_PER_RUN);
-        
-        // Create identical starting conditions
-        let mut world = create_complex_world();
-        let mut run_hashes = vec![];
-        
-        // Record initial state
-        run_hashes.push(hash_world_state(&world));
-        
-        // Run simulation
-        for step in 0..STEPS_PER_RUN {
-            world.step();
-            
-            // Record state every 100 steps
-            if (step + 1) % 100 == 0 {
-                run_hashes.push(hash_world_state(&world));
-            }
-        }
-        
-        hashes.push(run_hashes);
-        
-        // Print final positions for this run
-        println!("  Final positions:");
-        for (i, circle) in world.circles.iter().enumerate() {
-            println!("    Ball {}: ({:.3}, {:.3})", 
-                i + 1,
-                circle.position.x.to_float(),
-                circle.position.y.to_float()
-            );
-        }
-    }
+    println!("Simulating {} runs of {} steps...", NUM_RUNS, STEPS_PER_RUN);
+    let report = verify_runs(create_complex_world, NUM_RUNS, STEPS_PER_RUN);
     
     // Verify all runs produced identical results
     println!("\nVerifying determinism...");
     println!("Step  | Hash (first 16 chars)          | All Match?");
     println!("------|--------------------------------|------------");
     
-    let num_checkpoints = hashes[0].len();
-    let mut all_deterministic = true;
-    
-    for checkpoint in 0..num_checkpoints {
-        let step = checkpoint * 100;
-        let reference_hash = &hashes[0][checkpoint];
-        let hash_preview = &reference_hash[..16];
-        
-        let all_match = hashes.iter()
-            .all(|run| &run[checkpoint] == reference_hash);
+    for checkpoint in &report.checkpoints {
+        let hash_preview: String = checkpoint.reference_hash[..8].iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
         
         println!("{:5} | {} | {}", 
-            step, 
+            checkpoint.step, 
             hash_preview,
-            if all_match { "✓ Yes" } else { "✗ NO!" }
+            if checkpoint.all_match() { "✓ Yes" } else { "✗ NO!" }
         );
         
-        if !all_match {
-            all_deterministic = false;
-            // Show which runs differ
-            for (run_idx, run_hashes) in hashes.iter().enumerate() {
-                if &run_hashes[checkpoint] != reference_hash {
-                    println!("      Run {} differs: {}", 
-                        run_idx + 1, 
-                        &run_hashes[checkpoint][..16]
-                    );
-                }
-            }
+        // Show which runs differ
+        for run_idx in &checkpoint.diverging_runs {
+            println!("      Run {} differs", run_idx + 1);
         }
     }
     
-    println!("\n{}", if all_deterministic {
+    println!("\n{}", if report.is_deterministic() {
         "✓ SUCCESS: All runs produced identical results!"
     } else {
         "✗ FAILURE: Runs produced different results!"
@@ -131,9 +72,7 @@ fn main() {
     println!("Comparing final position bits of Ball 1 across all runs:");
     
     for run in 0..NUM_RUNS {
-        let world = create_complex_world();
-        // Re-run simulation for this check
-        let mut test_world = world;
+        let mut test_world = create_complex_world();
         for _ in 0..STEPS_PER_RUN {
             test_world.step();
         }
@@ -144,4 +83,4 @@ fn main() {
         println!("  Run {}: x_bits = 0x{:08x}, y_bits = 0x{:08x}", 
             run + 1, x_bits, y_bits);
     }
-}
\ No newline at end of file
+}
@@ -7,7 +7,7 @@ fn calculate_total_energy(world: &World) -> f32 {
     
     for circle in &world.circles {
         // Kinetic energy: 0.5 * m * v^2
-        let velocity = circle.velocity(world.timestep);
+        let velocity = circle.velocity();
         let speed_squared = velocity.magnitude_squared().to_float();
         let ke = 0.5 * circle.mass.to_float() * speed_squared;
         
@@ -78,7 +78,7 @@ fn main() {
             
             // Show KE/PE for each ball
             for circle in &world.circles {
-                let velocity = circle.velocity(world.timestep);
+                let velocity = circle.velocity();
                 let speed_squared = velocity.magnitude_squared().to_float();
                 let ke = 0.5 * circle.mass.to_float() * speed_squared;
                 let pe = circle.mass.to_float() * 9.81 * circle.position.y.to_float();
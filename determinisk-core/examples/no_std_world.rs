@@ -0,0 +1,43 @@
+//! Building and stepping a world without `std`
+//!
+//! This is how a zkVM guest or embedded target uses the engine: no printing,
+//! no `f32` trig, just fixed-point stepping and bit-exact outputs. It is built
+//! as a `#![no_std]` library, so it can't lean on `std` even when the core
+//! crate's own `std` feature is on. Check the `std`-free path with:
+//!
+//! ```text
+//! cargo check -p determinisk-core --example no_std_world --no-default-features
+//! ```
+
+#![no_std]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use determinisk_core::{Circle, Scalar, Vec2, World};
+
+/// Final state of a run, as raw bits a guest would commit
+pub struct FinalBits {
+    /// Q16.16 positions, `(x, y)` per circle
+    pub positions: Vec<(i32, i32)>,
+    /// `World::state_hash` after the last step
+    pub state_hash: [u8; 32],
+}
+
+/// Drop two balls onto each other for `steps` steps and return the final bits
+pub fn run(steps: u32) -> FinalBits {
+    let mut world = World::new(20.0, 20.0);
+    world.add_circle(Circle::new(Vec2::new(10.0, 5.0), Scalar::ONE, Scalar::ONE));
+    world.add_circle(Circle::new(Vec2::new(10.2, 12.0), Scalar::ONE, Scalar::from_float(0.5)));
+    
+    for _ in 0..steps {
+        world.step();
+    }
+    
+    FinalBits {
+        positions: world.circles.iter()
+            .map(|c| (c.position.x.to_bits(), c.position.y.to_bits()))
+            .collect(),
+        state_hash: world.state_hash(),
+    }
+}
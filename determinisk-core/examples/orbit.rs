@@ -97,7 +97,7 @@ fn main() {
                 print!("| {:5.1}% ", deviation);
                 
                 // Calculate orbital energy
-                let vel = circle.velocity(world.timestep);
+                let vel = circle.velocity();
                 let speed = vel.magnitude().to_float();
                 let ke = 0.5 * circle.mass.to_float() * speed * speed;
                 let pe = -500.0 * circle.mass.to_float() / dist; // Gravitational PE
@@ -118,7 +118,7 @@ fn main() {
     println!("\nFinal orbital characteristics:");
     for (i, circle) in world.circles.iter().enumerate() {
         let dist = (circle.position - center).magnitude().to_float();
-        let vel = circle.velocity(world.timestep);
+        let vel = circle.velocity();
         let speed = vel.magnitude().to_float();
         
         // Estimate orbital period (T = 2πr/v for circular orbit)
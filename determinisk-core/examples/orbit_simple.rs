@@ -45,7 +45,7 @@ fn main() {
             
             // Apply acceleration using position adjustment
             let dt2 = world.timestep * world.timestep;
-            world.circles[0].position = world.circles[0].position + acceleration * dt2;
+            world.circles[0].position += acceleration * dt2;
         }
         
         world.step();
@@ -57,7 +57,7 @@ fn main() {
         // Print status every 20 steps
         if step % 20 == 0 {
             let dist = (pos - center).magnitude().to_float();
-            let vel = world.circles[0].velocity;
+            let vel = world.circles[0].velocity();
             let speed = vel.magnitude().to_float();
             
             println!("{:4} | {:5.1} | {:5.1} | {:8.2} | {:5.2}", 
@@ -36,25 +36,19 @@ fn main() {
     
     // Simulate for 5 seconds
     for step in 0..300 {
-        // Apply constraint after physics step
-        world.step();
-        
-        // Enforce pendulum length constraint
-        let bob = &mut world.circles[0];
-        let to_bob = bob.position - pivot;
-        let current_length = to_bob.magnitude();
-        
-        if current_length > Scalar::ZERO {
-            // Project position back to correct length
-            bob.position = pivot + to_bob * (Scalar::from_float(length) / current_length);
+        // Enforce pendulum length constraint between integration and
+        // velocity finalization
+        world.step_with(|w| {
+            let bob = &mut w.circles[0];
+            let to_bob = bob.position - pivot;
+            let current_length = to_bob.magnitude();
             
-            // Adjust old_position to maintain velocity tangent to constraint
-            let old_to_pivot = bob.old_position - pivot;
-            let old_length = old_to_pivot.magnitude();
-            if old_length > Scalar::ZERO {
-                bob.old_position = pivot + old_to_pivot * (Scalar::from_float(length) / old_length);
+            if current_length > Scalar::ZERO {
+                // Project position back to correct length
+                bob.position = pivot + to_bob * (Scalar::from_float(length) / current_length);
             }
-        }
+        });
+        let bob = &world.circles[0];
         
         // Calculate and display state every 10 steps
         if step % 10 == 0 {
@@ -71,7 +65,7 @@ fn main() {
             prev_angle = angle;
             
             // Calculate energy (KE + PE)
-            let velocity = bob.velocity(world.timestep);
+            let velocity = bob.velocity();
             let speed = velocity.magnitude().to_float();
             let height = (pivot.y - bob.position.y).to_float() + length;
             let ke = 0.5 * speed * speed;
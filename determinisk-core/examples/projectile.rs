@@ -1,35 +1,18 @@
 //! Projectile motion - balls launched at different angles
 
-use determinisk_core::{Scalar, Vec2, Circle, World};
+use determinisk_core::{scenarios, World};
 
 fn main() {
-    // Create a wider world for projectile motion
-    let mut world = World::new(300.0, 150.0);
-    
     // Launch parameters
     let launch_speed = 30.0;
     let launch_angles = [15.0, 30.0, 45.0, 60.0, 75.0]; // degrees
     
     println!("Launching {} projectiles at different angles...", launch_angles.len());
     
-    // Create projectiles with different launch angles
-    for (_i, &angle) in launch_angles.iter().enumerate() {
-        let mut ball = Circle::new(
-            Vec2::new(10.0, 10.0),  // Start near ground
-            Scalar::from_float(2.0), // Small radius
-            Scalar::from_float(0.5), // Light mass
-        );
-        
-        // Convert angle to radians and set initial velocity
-        let angle_rad = angle * std::f32::consts::PI / 180.0;
-        let vx = launch_speed * angle_rad.cos();
-        let vy = launch_speed * angle_rad.sin();
-        
-        // Set initial velocity using Verlet method
-        ball.set_velocity(Vec2::new(vx, vy), world.timestep);
-        
-        world.add_circle(ball);
-    }
+    // One projectile per angle in a wide world
+    let input = scenarios::projectile_sweep(launch_speed, &launch_angles, [300.0, 150.0]);
+    let start_x: Vec<f32> = input.circles.iter().map(|c| c.position[0]).collect();
+    let mut world = World::from_input(&input);
     
     // Track maximum heights and ranges
     let mut max_heights = vec![10.0; launch_angles.len()];
@@ -56,7 +39,7 @@ fn main() {
             // Check if landed (y ≈ radius)
             if in_flight[i] && y <= circle.radius.to_float() + 0.1 {
                 in_flight[i] = false;
-                ranges[i] = x - 10.0; // Subtract starting x position
+                ranges[i] = x - start_x[i]; // Subtract starting x position
             }
         }
         
@@ -59,8 +59,8 @@ fn main() {
             i,
             circle.position.x.to_float(),
             circle.position.y.to_float(),
-            circle.velocity.x.to_float(),
-            circle.velocity.y.to_float(),
+            circle.velocity().x.to_float(),
+            circle.velocity().y.to_float(),
         );
     }
     
@@ -70,7 +70,7 @@ fn main() {
         println!("\nSaving output to: {}", output_path);
         
         // Create output with metrics
-        let trace = world.run_with_recording(0); // Get final state
+        let _trace = world.run_with_recording(0); // Get final state
         
         match scenarios::to_json_file(&input, output_path) {
             Ok(_) => println!("✓ Output saved successfully"),
@@ -4,7 +4,7 @@ use determinisk_core::{World, Circle, Vec2, Scalar};
 use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
 
-fn run_simulation(seed: u64) -> u64 {
+fn run_simulation(_seed: u64) -> u64 {
     let mut world = World::new(10.0, 10.0);
     
     // Add circles in a specific pattern
@@ -0,0 +1,185 @@
+//! Multi-run determinism verification
+//!
+//! Builds the same world several times, steps each copy, and compares state
+//! hashes at regular checkpoints. Any mismatch means something in the build
+//! or the step is not bit-exact.
+
+use sha2::{Sha256, Digest};
+
+use crate::{CircleConfig, JournalOutput, SimulationInput, SimulationTrace, World};
+
+/// Steps between checkpoint hashes (step 0 and the final step are always checked)
+pub const CHECKPOINT_INTERVAL: u32 = 100;
+
+/// Outcome of comparing all runs at one step
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckpointResult {
+    pub step: u32,
+    /// Hash of the first run, which the others are compared against
+    pub reference_hash: [u8; 32],
+    /// Indices of runs whose hash differs from run 0
+    pub diverging_runs: Vec<usize>,
+}
+
+impl CheckpointResult {
+    /// Whether every run matched the reference at this step
+    pub fn all_match(&self) -> bool {
+        self.diverging_runs.is_empty()
+    }
+}
+
+/// Result of `verify_runs`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeterminismReport {
+    pub runs: usize,
+    pub steps: u32,
+    pub checkpoints: Vec<CheckpointResult>,
+}
+
+impl DeterminismReport {
+    /// Whether all runs matched at every checkpoint
+    pub fn is_deterministic(&self) -> bool {
+        self.checkpoints.iter().all(CheckpointResult::all_match)
+    }
+    
+    /// First checkpoint step and run index that diverged, if any
+    pub fn first_divergence(&self) -> Option<(u32, usize)> {
+        self.checkpoints.iter()
+            .find_map(|c| c.diverging_runs.first().map(|&run| (c.step, run)))
+    }
+}
+
+/// Hash of everything that drives the next step: positions and Verlet history
+pub fn checkpoint_hash(world: &World) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    for circle in &world.circles {
+        hasher.update(circle.position.x.to_bits().to_le_bytes());
+        hasher.update(circle.position.y.to_bits().to_le_bytes());
+        hasher.update(circle.old_position.x.to_bits().to_le_bytes());
+        hasher.update(circle.old_position.y.to_bits().to_le_bytes());
+    }
+    hasher.finalize().into()
+}
+
+/// Build `runs` worlds with `build`, step each `steps` times and compare
+///
+/// Hashes are taken at step 0, every `CHECKPOINT_INTERVAL` steps, and after
+/// the final step.
+pub fn verify_runs(build: impl Fn() -> World, runs: usize, steps: u32) -> DeterminismReport {
+    let is_checkpoint = |step: u32| step.is_multiple_of(CHECKPOINT_INTERVAL) || step == steps;
+    
+    let hashes: Vec<Vec<(u32, [u8; 32])>> = (0..runs)
+        .map(|_| {
+            let mut world = build();
+            let mut run_hashes = vec![(0, checkpoint_hash(&world))];
+            for step in 1..=steps {
+                world.step();
+                if is_checkpoint(step) {
+                    run_hashes.push((step, checkpoint_hash(&world)));
+                }
+            }
+            run_hashes
+        })
+        .collect();
+    
+    let checkpoints = match hashes.first() {
+        Some(reference) => reference.iter().enumerate()
+            .map(|(i, &(step, reference_hash))| CheckpointResult {
+                step,
+                reference_hash,
+                diverging_runs: hashes.iter().enumerate()
+                    .filter(|(_, run)| run[i].1 != reference_hash)
+                    .map(|(run, _)| run)
+                    .collect(),
+            })
+            .collect(),
+        None => Vec::new(),
+    };
+    
+    DeterminismReport { runs, steps, checkpoints }
+}
+
+/// A journal checkpoint that a native replay doesn't reproduce
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CheckpointMismatch {
+    pub step: u32,
+    /// State hash of the native replay at `step`
+    pub native: [u8; 32],
+    /// State hash the guest committed for `step`
+    pub committed: [u8; 32],
+}
+
+/// Replay `input` natively and compare each of `journal`'s checkpoints
+///
+/// Only steps up to the last checkpoint are simulated, so checking a
+/// prefix of a long run is cheap. Checkpoints must be in increasing step
+/// order, as `execute_journal` commits them. Returns how many matched.
+pub fn check_journal_checkpoints(
+    input: &SimulationInput,
+    journal: &JournalOutput,
+) -> Result<usize, CheckpointMismatch> {
+    let mut world = World::from_input(input);
+    let mut step = 0;
+    for &(checkpoint, committed) in &journal.checkpoints {
+        while step < checkpoint {
+            world.step();
+            step += 1;
+        }
+        let native = world.state_hash();
+        if step != checkpoint || native != committed {
+            return Err(CheckpointMismatch { step: checkpoint, native, committed });
+        }
+    }
+    Ok(journal.checkpoints.len())
+}
+
+/// Assert that a trace survives JSON serialization well enough to replay
+///
+/// Runs `input`, round-trips the trace through JSON, rebuilds a world from
+/// the reloaded initial state and checks the replay matches every recorded
+/// state bit for bit. Panics with the first diverging step and circle.
+pub fn assert_trace_roundtrip_stable(input: &SimulationInput) {
+    assert_trace_resume_stable(input, 0);
+}
+
+/// Like `assert_trace_roundtrip_stable`, but resumes from recorded step `from_step`
+///
+/// Recorded states hold `f32` positions and velocities only, so resuming
+/// mid-run drops the Verlet history and any Q16.16 bits `f32` can't hold.
+/// This surfaces that loss; step 0 is exact because it comes from `f32` input.
+///
+/// # Panics
+///
+/// On divergence, or if `from_step` is past `input.num_steps`.
+pub fn assert_trace_resume_stable(input: &SimulationInput, from_step: u32) {
+    assert!(from_step <= input.num_steps, "from_step {} is past the last step {}", from_step, input.num_steps);
+    
+    let trace = World::from_input(input).run_with_recording(input.num_steps);
+    let json = serde_json::to_string(&trace).expect("trace serializes");
+    let reloaded: SimulationTrace = serde_json::from_str(&json).expect("trace deserializes");
+    
+    // Same world settings, circles taken from the reloaded state
+    let mut resumed_input = reloaded.input.clone();
+    resumed_input.circles = reloaded.states[from_step as usize].circles.iter()
+        .map(|c| CircleConfig {
+            position: c.position,
+            velocity: c.velocity,
+            radius: c.radius,
+            mass: c.mass,
+            angular_velocity: 0.0,
+        })
+        .collect();
+    let replay = World::from_input(&resumed_input).run_with_recording(input.num_steps - from_step);
+    
+    let recorded = &trace.states[from_step as usize..];
+    for (expected, actual) in recorded.iter().zip(&replay.states) {
+        let diverged = expected.circles.iter().zip(&actual.circles)
+            .position(|(a, b)| !a.bit_eq(b));
+        if let Some(circle) = diverged {
+            panic!(
+                "trace round-trip diverged at step {} (circle {}): recorded {:?}, replayed {:?}",
+                expected.step, circle, expected.circles[circle], actual.circles[circle],
+            );
+        }
+    }
+}
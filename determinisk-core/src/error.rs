@@ -0,0 +1,114 @@
+//! Typed errors for loading scenarios and running simulations
+
+use std::fmt;
+
+use crate::physics::TooManyCircles;
+use crate::state::{NonFiniteInput, ValidationError};
+
+/// Everything that can go wrong between naming a scenario and proving it
+///
+/// `Validation` keeps the underlying error, so callers that care can
+/// `downcast_ref` it to e.g. `ValidationError`, `NonFiniteInput` or
+/// `TooManyCircles`.
+#[derive(Debug)]
+pub enum DeterminiskError {
+    /// Reading or writing a file failed
+    Io(std::io::Error),
+    /// A file isn't valid TOML/JSON for a `SimulationInput` (or bincode for a trace)
+    Parse(String),
+    /// The input parsed but can't be simulated as given
+    Validation(Box<dyn std::error::Error + Send + Sync>),
+    /// No built-in scenario has this name
+    UnknownScenario(String),
+    /// Proof generation failed
+    Proof(String),
+    /// The requested file format or mode isn't available
+    Unsupported(String),
+}
+
+impl DeterminiskError {
+    /// A `Validation` error with just a message
+    pub fn validation(message: impl Into<String>) -> Self {
+        DeterminiskError::Validation(message.into().into())
+    }
+}
+
+impl fmt::Display for DeterminiskError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DeterminiskError::Io(err) => write!(f, "I/O error: {}", err),
+            DeterminiskError::Parse(message) => write!(f, "parse error: {}", message),
+            DeterminiskError::Validation(err) => write!(f, "{}", err),
+            DeterminiskError::UnknownScenario(name) => write!(f, "Unknown scenario: {}", name),
+            DeterminiskError::Proof(message) => write!(f, "proof failed: {}", message),
+            DeterminiskError::Unsupported(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for DeterminiskError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            DeterminiskError::Io(err) => Some(err),
+            DeterminiskError::Validation(err) => Some(err.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for DeterminiskError {
+    fn from(err: std::io::Error) -> Self {
+        DeterminiskError::Io(err)
+    }
+}
+
+impl From<NonFiniteInput> for DeterminiskError {
+    fn from(err: NonFiniteInput) -> Self {
+        DeterminiskError::Validation(Box::new(err))
+    }
+}
+
+/// Non-finite values keep their own `NonFiniteInput` type
+impl From<ValidationError> for DeterminiskError {
+    fn from(err: ValidationError) -> Self {
+        match err {
+            ValidationError::NonFinite(err) => err.into(),
+            ValidationError::TooManyCircles(err) => err.into(),
+            err => DeterminiskError::Validation(Box::new(err)),
+        }
+    }
+}
+
+impl From<TooManyCircles> for DeterminiskError {
+    fn from(err: TooManyCircles) -> Self {
+        DeterminiskError::Validation(Box::new(err))
+    }
+}
+
+#[cfg(feature = "toml")]
+impl From<toml::de::Error> for DeterminiskError {
+    fn from(err: toml::de::Error) -> Self {
+        DeterminiskError::Parse(err.to_string())
+    }
+}
+
+#[cfg(feature = "toml")]
+impl From<toml::ser::Error> for DeterminiskError {
+    fn from(err: toml::ser::Error) -> Self {
+        DeterminiskError::Parse(err.to_string())
+    }
+}
+
+#[cfg(feature = "serde_json")]
+impl From<serde_json::Error> for DeterminiskError {
+    fn from(err: serde_json::Error) -> Self {
+        DeterminiskError::Parse(err.to_string())
+    }
+}
+
+#[cfg(feature = "bincode")]
+impl From<bincode::Error> for DeterminiskError {
+    fn from(err: bincode::Error) -> Self {
+        DeterminiskError::Parse(err.to_string())
+    }
+}
@@ -16,15 +16,25 @@ pub mod state;
 #[cfg(feature = "std")]
 pub mod scenarios;
 
+#[cfg(feature = "std")]
+pub mod determinism;
+
+#[cfg(feature = "std")]
+pub mod error;
+
 #[cfg(test)]
 mod tests;
 
-pub use math::{Scalar, Vec2};
-pub use physics::{Circle, World, CollisionConfig, resolve_all_collisions};
-pub use spatial::{SpatialGrid, Collision, BoundaryCollision};
+pub use math::{Aabb, Scalar, Vec2};
+pub use physics::{Circle, CircleHandle, Constraint, RayHit, Rect, MAX_SAFE_SPEED, SLEEP_SPEED, World, WorldCheckpoint, CollisionFilter, GravityZone, Integrator, MAX_CIRCLES, TooManyCircles, CollisionConfig, CorrectionOrder, CollisionEvent, RestitutionRule, StepContacts, BroadPhaseStats, Schedule, ParamChange, resolve_all_collisions};
+pub use spatial::{SpatialGrid, GridCell, GridCells, InvalidGridCells, Collision, SweptCollision, BoundaryCollision, ContactMode};
 pub use state::{
     SimulationState, CircleState, 
-    SimulationInput, CircleConfig,
+    SimulationInput, CircleConfig, NonFiniteInput, ValidationError, CostEstimate,
     SimulationOutput, SimulationMetrics,
-    SimulationTrace,
-};
\ No newline at end of file
+    SimulationTrace, BitExact, JournalOutput, ConservationBits, StateIterator,
+    RECOMMENDED_SEGMENT_PO2,
+};
+
+#[cfg(feature = "std")]
+pub use error::DeterminiskError;
\ No newline at end of file
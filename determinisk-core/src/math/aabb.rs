@@ -0,0 +1,28 @@
+//! Axis-aligned bounding boxes
+
+use serde::{Serialize, Deserialize};
+
+use crate::math::Vec2;
+
+/// Axis-aligned box spanning `min` to `max`, edges inclusive
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Aabb {
+    pub min: Vec2,
+    pub max: Vec2,
+}
+
+impl Aabb {
+    /// Box between two opposite corners, in any order
+    pub fn new(a: Vec2, b: Vec2) -> Self {
+        Aabb {
+            min: Vec2::from_scalars(a.x.min(b.x), a.y.min(b.y)),
+            max: Vec2::from_scalars(a.x.max(b.x), a.y.max(b.y)),
+        }
+    }
+    
+    /// Whether `point` lies inside the box or on its edge
+    pub fn contains(&self, point: Vec2) -> bool {
+        point.x >= self.min.x && point.x <= self.max.x
+            && point.y >= self.min.y && point.y <= self.max.y
+    }
+}
@@ -1,7 +1,9 @@
 //! Fixed-point mathematics for deterministic physics
 
+mod aabb;
 mod scalar;
 mod vec2;
 
+pub use aabb::Aabb;
 pub use scalar::Scalar;
 pub use vec2::Vec2;
\ No newline at end of file
@@ -8,6 +8,53 @@ use core::ops::{Add, Sub, Mul, Div, Neg};
 use fixed::types::I16F16;
 use serde::{Serialize, Deserialize};
 
+/// 2^16, the value of one integer step in raw Q16.16 bits
+const FRAC_SCALE: f32 = 65536.0;
+
+/// Floor of an already-scaled float without `f32::floor` (unavailable in no_std)
+///
+/// Casting truncates toward zero; step down for negative non-integers. Floats
+/// beyond 2^24 are integral, so the comparison is exact.
+fn floor_scaled(scaled: f32) -> i64 {
+    let truncated = scaled as i64;
+    if (truncated as f32) > scaled {
+        truncated - 1
+    } else {
+        truncated
+    }
+}
+
+/// Fractional bits of the angles and coordinates CORDIC works in
+///
+/// 14 bits more than Q16.16, so rounding error from the iterations stays
+/// below the output's last bit.
+const CORDIC_FRAC_BITS: u32 = 30;
+
+/// CORDIC iterations; each adds about one bit of precision
+const CORDIC_ITERATIONS: usize = 24;
+
+/// atan(2^-i) with `CORDIC_FRAC_BITS` fractional bits
+const CORDIC_ATAN: [i64; CORDIC_ITERATIONS] = [
+    843314857, 497837829, 263043837, 133525159, 67021687, 33543516,
+    16775851, 8388437, 4194283, 2097149, 1048576, 524288,
+    262144, 131072, 65536, 32768, 16384, 8192,
+    4096, 2048, 1024, 512, 256, 128,
+];
+
+/// 1 / (CORDIC gain) after `CORDIC_ITERATIONS` rotations, with `CORDIC_FRAC_BITS`
+const CORDIC_INV_GAIN: i64 = 652032874;
+
+/// π, π/2 and τ with `CORDIC_FRAC_BITS` fractional bits
+const CORDIC_PI: i64 = 3373259426;
+const CORDIC_FRAC_PI_2: i64 = 1686629713;
+const CORDIC_TAU: i64 = 6746518852;
+
+/// Round a `CORDIC_FRAC_BITS` value to Q16.16, ties up
+fn cordic_to_scalar(value: i64) -> Scalar {
+    let shift = CORDIC_FRAC_BITS - 16;
+    Scalar::from_bits(((value + (1 << (shift - 1))) >> shift) as i32)
+}
+
 /// Q16.16 fixed-point scalar for deterministic physics calculations
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct Scalar(pub I16F16);
@@ -25,11 +72,85 @@ impl Scalar {
     /// Half value
     pub const HALF: Self = Scalar(I16F16::from_bits(0x00008000));
     
+    /// π, nearest Q16.16 value
+    pub const PI: Self = Scalar(I16F16::from_bits(0x0003243F));
+    
+    /// τ = 2π, nearest Q16.16 value
+    pub const TAU: Self = Scalar(I16F16::from_bits(0x0006487F));
+    
+    /// π/2, nearest Q16.16 value
+    pub const FRAC_PI_2: Self = Scalar(I16F16::from_bits(0x00019220));
+    
+    /// Euler's number e, nearest Q16.16 value
+    pub const E: Self = Scalar(I16F16::from_bits(0x0002B7E1));
+    
     /// Create from floating-point value
+    ///
+    /// Rounds to the nearest multiple of 2^-16, with ties rounded to even.
+    /// Use `from_float_round`, `from_float_floor` or `from_float_ceil` when
+    /// the rounding direction at the f32 boundary must be explicit.
+    ///
+    /// # Panics
+    ///
+    /// If `f` is NaN or infinite. Use `try_from_float` for untrusted values.
     pub fn from_float(f: f32) -> Self {
+        assert!(f.is_finite(), "Scalar::from_float: non-finite input {}", f);
         Scalar(I16F16::from_num(f))
     }
     
+    /// Like `from_float`, but `None` for NaN, infinities and values outside
+    /// the Q16.16 range instead of panicking or wrapping
+    pub fn try_from_float(f: f32) -> Option<Self> {
+        I16F16::checked_from_num(f).map(Scalar)
+    }
+    
+    /// Create from floating-point value, rounding to nearest with ties away from zero
+    ///
+    /// # Panics
+    ///
+    /// Like `from_float`, if `f` is NaN or infinite, and if it is outside the
+    /// Q16.16 range.
+    pub fn from_float_round(f: f32) -> Self {
+        assert!(f.is_finite(), "Scalar::from_float_round: non-finite input {}", f);
+        let scaled = f * FRAC_SCALE;
+        let floor = floor_scaled(scaled);
+        let frac = scaled - floor as f32;
+        let bits = if frac > 0.5 || (frac == 0.5 && scaled > 0.0) {
+            floor + 1
+        } else {
+            floor
+        };
+        Self::from_scaled_bits(bits)
+    }
+    
+    /// Create from floating-point value, rounding toward negative infinity
+    ///
+    /// # Panics
+    ///
+    /// As `from_float_round`.
+    pub fn from_float_floor(f: f32) -> Self {
+        assert!(f.is_finite(), "Scalar::from_float_floor: non-finite input {}", f);
+        Self::from_scaled_bits(floor_scaled(f * FRAC_SCALE))
+    }
+    
+    /// Create from floating-point value, rounding toward positive infinity
+    ///
+    /// # Panics
+    ///
+    /// As `from_float_round`.
+    pub fn from_float_ceil(f: f32) -> Self {
+        assert!(f.is_finite(), "Scalar::from_float_ceil: non-finite input {}", f);
+        let scaled = f * FRAC_SCALE;
+        let floor = floor_scaled(scaled);
+        let bits = if (floor as f32) < scaled { floor + 1 } else { floor };
+        Self::from_scaled_bits(bits)
+    }
+    
+    fn from_scaled_bits(bits: i64) -> Self {
+        let bits = i32::try_from(bits).expect("value out of Q16.16 range");
+        Scalar::from_bits(bits)
+    }
+    
     /// Convert to floating-point value (for debugging/display only)
     pub fn to_float(&self) -> f32 {
         self.0.to_num()
@@ -50,11 +171,66 @@ impl Scalar {
         Scalar(self.0.abs())
     }
     
+    /// The smaller of `self` and `other`
+    ///
+    /// These take `self` by value so they, rather than the `Ord` methods of
+    /// the same name, are what `a.min(b)` calls.
+    pub fn min(self, other: Self) -> Self {
+        Scalar(self.0.min(other.0))
+    }
+    
+    /// The larger of `self` and `other`
+    pub fn max(self, other: Self) -> Self {
+        Scalar(self.0.max(other.0))
+    }
+    
+    /// `self` limited to `[lo, hi]`
+    ///
+    /// Debug builds assert `lo <= hi`; release builds return `hi` for values
+    /// above it and `lo` for values below it, checked in that order.
+    pub fn clamp(self, lo: Self, hi: Self) -> Self {
+        debug_assert!(lo <= hi, "clamp range is empty: {} > {}", lo, hi);
+        if self.0 > hi.0 {
+            hi
+        } else if self.0 < lo.0 {
+            lo
+        } else {
+            self
+        }
+    }
+    
+    /// `ONE`, `ZERO` or `-ONE` by the sign of `self`
+    pub fn signum(&self) -> Self {
+        Scalar(self.0.signum())
+    }
+    
     /// Convert to integer
     pub fn to_int(&self) -> i32 {
         self.0.to_num()
     }
     
+    /// Integer square root of the raw bits, rounded down
+    ///
+    /// Works purely on the `i32` representation with integer Newton steps, so
+    /// there's no fractional rounding. Since the bits are `value * 2^16`, the
+    /// result is `sqrt(value)` scaled by 2^8. Non-positive values give 0.
+    pub fn isqrt_bits(&self) -> i32 {
+        let n = self.to_bits();
+        if n <= 0 {
+            return 0;
+        }
+        let n = n as u32;
+        
+        // Start above the root; Newton then decreases monotonically to floor(sqrt(n))
+        let mut x = n;
+        let mut y = x.div_ceil(2);
+        while y < x {
+            x = y;
+            y = (x + n / x) / 2;
+        }
+        x as i32
+    }
+    
     /// Square root using Newton-Raphson method
     pub fn sqrt(&self) -> Self {
         if self.0 <= I16F16::ZERO {
@@ -78,6 +254,79 @@ impl Scalar {
         
         guess
     }
+    
+    /// Sine and cosine of an angle in radians, computed together
+    ///
+    /// Uses integer CORDIC only, so results are bit-identical on every
+    /// platform and in no_std guests. Within 0.001 of the exact values for
+    /// any angle; the error is usually a few units in the last place.
+    pub fn sin_cos(&self) -> (Self, Self) {
+        // Reduce to [-π, π], then fold into [-π/2, π/2] where CORDIC converges
+        let mut angle = (i64::from(self.to_bits()) << (CORDIC_FRAC_BITS - 16)).rem_euclid(CORDIC_TAU);
+        if angle > CORDIC_PI {
+            angle -= CORDIC_TAU;
+        }
+        let flip = angle.abs() > CORDIC_FRAC_PI_2;
+        if flip {
+            angle -= CORDIC_PI * angle.signum();
+        }
+        
+        // Rotate (1/gain, 0) by `angle`, one arctangent step at a time
+        let (mut x, mut y, mut z) = (CORDIC_INV_GAIN, 0i64, angle);
+        for (i, &step) in CORDIC_ATAN.iter().enumerate() {
+            let (dx, dy) = (y >> i, x >> i);
+            if z >= 0 {
+                (x, y, z) = (x - dx, y + dy, z - step);
+            } else {
+                (x, y, z) = (x + dx, y - dy, z + step);
+            }
+        }
+        
+        if flip {
+            (x, y) = (-x, -y);
+        }
+        (cordic_to_scalar(y), cordic_to_scalar(x))
+    }
+    
+    /// Sine of an angle in radians; see `sin_cos`
+    pub fn sin(&self) -> Self {
+        self.sin_cos().0
+    }
+    
+    /// Cosine of an angle in radians; see `sin_cos`
+    pub fn cos(&self) -> Self {
+        self.sin_cos().1
+    }
+    
+    /// Four-quadrant arctangent of `self / x`, in [-π, π]
+    ///
+    /// `self` is the y coordinate, as with `f32::atan2`. Integer CORDIC like
+    /// `sin_cos`, within 0.001 of the exact angle. `atan2(0, 0)` is 0.
+    pub fn atan2(&self, x: Self) -> Self {
+        let (mut x, mut y) = (i64::from(x.to_bits()) << 16, i64::from(self.to_bits()) << 16);
+        if x == 0 && y == 0 {
+            return Scalar::ZERO;
+        }
+        
+        // Rotate the left half-plane by π so CORDIC only sees x >= 0
+        let mut z = 0i64;
+        if x < 0 {
+            z = if y >= 0 { CORDIC_PI } else { -CORDIC_PI };
+            (x, y) = (-x, -y);
+        }
+        
+        // Rotate the vector onto the x axis, summing the angles turned
+        for (i, &step) in CORDIC_ATAN.iter().enumerate() {
+            let (dx, dy) = (y >> i, x >> i);
+            if y < 0 {
+                (x, y, z) = (x - dx, y + dy, z - step);
+            } else {
+                (x, y, z) = (x + dx, y - dy, z + step);
+            }
+        }
+        
+        cordic_to_scalar(z)
+    }
 }
 
 impl fmt::Display for Scalar {
@@ -152,6 +401,59 @@ mod tests {
         assert_eq!(div_result.to_bits(), expected.to_bits());
     }
     
+    #[test]
+    fn test_from_float_rounding_modes_at_tie() {
+        // Exactly halfway between raw bits 65538 and 65539
+        let tie = 1.0 + 2.5 / 65536.0;
+        assert_eq!(Scalar::from_float(tie).to_bits(), 65538); // ties to even
+        assert_eq!(Scalar::from_float_round(tie).to_bits(), 65539); // ties away from zero
+        assert_eq!(Scalar::from_float_floor(tie).to_bits(), 65538);
+        assert_eq!(Scalar::from_float_ceil(tie).to_bits(), 65539);
+        
+        // Negative ties round away from zero too; floor/ceil keep their direction
+        assert_eq!(Scalar::from_float_round(-tie).to_bits(), -65539);
+        assert_eq!(Scalar::from_float_floor(-tie).to_bits(), -65539);
+        assert_eq!(Scalar::from_float_ceil(-tie).to_bits(), -65538);
+        
+        // Representable values are unchanged by every mode
+        for v in [0.0, 1.5, -3.25, 100.0] {
+            let exact = Scalar::from_float(v);
+            assert_eq!(Scalar::from_float_round(v), exact);
+            assert_eq!(Scalar::from_float_floor(v), exact);
+            assert_eq!(Scalar::from_float_ceil(v), exact);
+        }
+    }
+    
+    #[test]
+    fn test_isqrt_bits_perfect_squares() {
+        for n in [0i32, 1, 2, 3, 255, 256, 1000, 46340] {
+            assert_eq!(Scalar::from_bits(n * n).isqrt_bits(), n);
+        }
+        
+        // Rounds down between squares
+        assert_eq!(Scalar::from_bits(15).isqrt_bits(), 3);
+        assert_eq!(Scalar::from_bits(i32::MAX).isqrt_bits(), 46340);
+        assert_eq!(Scalar::from_bits(-4).isqrt_bits(), 0);
+        
+        // 4.0 is 4 << 16, whose root is 2 << 8
+        assert_eq!(Scalar::from_float(4.0).isqrt_bits(), 2 << 8);
+    }
+    
+    #[test]
+    fn test_math_constants_within_precision() {
+        let ulp = 1.0 / 65536.0;
+        for (constant, exact) in [
+            (Scalar::PI, core::f32::consts::PI),
+            (Scalar::TAU, core::f32::consts::TAU),
+            (Scalar::FRAC_PI_2, core::f32::consts::FRAC_PI_2),
+            (Scalar::E, core::f32::consts::E),
+        ] {
+            // Nearest representable value: within half a step
+            assert!((constant.to_float() - exact).abs() <= ulp / 2.0, "{} vs {}", constant, exact);
+            assert_eq!(constant, Scalar::from_float(exact));
+        }
+    }
+    
     #[test]
     fn test_scalar_sqrt() {
         let values = [0.0, 1.0, 4.0, 9.0, 16.0, 25.0];
@@ -177,4 +479,115 @@ mod tests {
         
         assert_eq!(result1.to_bits(), result2.to_bits());
     }
-}
\ No newline at end of file
+    
+    #[test]
+    fn test_try_from_float_rejects_non_finite() {
+        assert_eq!(Scalar::try_from_float(1.5), Some(Scalar::from_float(1.5)));
+        assert_eq!(Scalar::try_from_float(f32::NAN), None);
+        assert_eq!(Scalar::try_from_float(f32::INFINITY), None);
+        assert_eq!(Scalar::try_from_float(f32::NEG_INFINITY), None);
+        assert_eq!(Scalar::try_from_float(40000.0), None);
+    }
+    
+    #[test]
+    #[should_panic(expected = "non-finite input NaN")]
+    fn test_from_float_panics_on_nan() {
+        Scalar::from_float(f32::NAN);
+    }
+    
+    #[test]
+    fn test_rounding_constructors_reject_non_finite() {
+        type Constructor = fn(f32) -> Scalar;
+        let constructors: [(&str, Constructor); 3] = [
+            ("from_float_round", Scalar::from_float_round),
+            ("from_float_floor", Scalar::from_float_floor),
+            ("from_float_ceil", Scalar::from_float_ceil),
+        ];
+        
+        for (name, constructor) in constructors {
+            for input in [f32::NAN, f32::INFINITY, f32::NEG_INFINITY] {
+                let message = std::panic::catch_unwind(|| constructor(input))
+                    .expect_err("non-finite input was accepted");
+                let message = message.downcast_ref::<String>().unwrap();
+                assert_eq!(*message, format!("Scalar::{}: non-finite input {}", name, input));
+            }
+        }
+    }
+    
+    #[test]
+    fn test_sin_cos_within_tolerance() {
+        // -10 to 10 radians in steps of 0.01, covering every quadrant and wrap
+        for i in -1000..=1000 {
+            let angle = Scalar::from_float(i as f32 * 0.01);
+            let exact = angle.to_float();
+            let (sin, cos) = angle.sin_cos();
+            
+            assert!((sin.to_float() - exact.sin()).abs() < 0.001, "sin({}) = {}", exact, sin);
+            assert!((cos.to_float() - exact.cos()).abs() < 0.001, "cos({}) = {}", exact, cos);
+            assert_eq!((angle.sin(), angle.cos()), (sin, cos));
+        }
+        
+        assert_eq!(Scalar::ZERO.sin(), Scalar::ZERO);
+    }
+    
+    #[test]
+    fn test_atan2_within_tolerance() {
+        for i in 0..360 {
+            let theta = (i as f32).to_radians();
+            for radius in [0.01, 1.0, 250.0] {
+                let y = Scalar::from_float(radius * theta.sin());
+                let x = Scalar::from_float(radius * theta.cos());
+                let exact = y.to_float().atan2(x.to_float());
+                
+                let angle = y.atan2(x);
+                assert!((angle.to_float() - exact).abs() < 0.001, "atan2({}, {}) = {}", y, x, angle);
+            }
+        }
+        
+        assert_eq!(Scalar::ZERO.atan2(Scalar::ZERO), Scalar::ZERO);
+        assert_eq!(Scalar::ZERO.atan2(-Scalar::ONE), Scalar::PI);
+    }
+    
+    #[test]
+    fn test_trig_determinism() {
+        for bits in [0x0001_0000, -0x0003_1234, 0x0006_487F, 0x0000_0001, i32::MAX, i32::MIN] {
+            let a = Scalar::from_bits(bits);
+            assert_eq!(a.sin().to_bits(), a.sin().to_bits());
+            assert_eq!(a.cos().to_bits(), a.cos().to_bits());
+            assert_eq!(a.atan2(Scalar::ONE).to_bits(), a.atan2(Scalar::ONE).to_bits());
+            assert_eq!(Scalar::ONE.atan2(a).to_bits(), Scalar::ONE.atan2(a).to_bits());
+        }
+    }
+    
+    #[test]
+    fn test_min_max_clamp_signum() {
+        let a = Scalar::from_bits(-0x0002_8000);
+        let b = Scalar::from_bits(0x0000_0001);
+        assert_eq!(a.min(b).to_bits(), -0x0002_8000);
+        assert_eq!(a.max(b).to_bits(), 0x0000_0001);
+        assert_eq!(b.min(b).to_bits(), b.to_bits());
+        
+        // Negative range
+        let (lo, hi) = (Scalar::from_float(-3.0), Scalar::from_float(-1.5));
+        assert_eq!(Scalar::ZERO.clamp(lo, hi).to_bits(), hi.to_bits());
+        assert_eq!(Scalar::from_float(-7.25).clamp(lo, hi).to_bits(), lo.to_bits());
+        assert_eq!(a.clamp(lo, hi).to_bits(), a.to_bits());
+        
+        // Degenerate range pins everything to the single value
+        let point = Scalar::from_bits(-0x0000_1234);
+        for value in [Scalar::from_bits(i32::MIN), point, Scalar::ZERO, Scalar::from_bits(i32::MAX)] {
+            assert_eq!(value.clamp(point, point).to_bits(), point.to_bits());
+        }
+        
+        assert_eq!(a.signum().to_bits(), (-Scalar::ONE).to_bits());
+        assert_eq!(b.signum().to_bits(), Scalar::ONE.to_bits());
+        assert_eq!(Scalar::ZERO.signum().to_bits(), 0);
+    }
+    
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "clamp range is empty")]
+    fn test_clamp_rejects_inverted_range() {
+        Scalar::ZERO.clamp(Scalar::ONE, -Scalar::ONE);
+    }
+}
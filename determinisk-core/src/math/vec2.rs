@@ -65,6 +65,26 @@ impl Vec2 {
         self.magnitude_squared().sqrt()
     }
     
+    /// This vector, shortened to at most `max` long if needed
+    ///
+    /// Safe for vectors whose squared magnitude would overflow Q16.16: the
+    /// length is measured after scaling by the largest component.
+    pub fn magnitude_clamped(&self, max: Scalar) -> Self {
+        let largest = self.x.abs().max(self.y.abs());
+        if largest <= Scalar::ZERO {
+            return *self;
+        }
+        // Components of `unit_ish` are in [-1, 1], so its magnitude is in [1, √2]
+        let unit_ish = *self / largest;
+        // Rounded up a few bits so fixed-point rounding can't overshoot `max`
+        let scaled_magnitude = unit_ish.magnitude() + Scalar::from_bits(4);
+        if largest <= max / scaled_magnitude {
+            *self
+        } else {
+            unit_ish * (max / scaled_magnitude)
+        }
+    }
+    
     /// Normalize the vector
     pub fn normalized(&self) -> Self {
         let mag = self.magnitude();
@@ -87,6 +107,23 @@ impl Vec2 {
     pub fn lerp(&self, other: &Vec2, t: Scalar) -> Self {
         *self + (*other - *self) * t
     }
+    
+    /// Rotate counter-clockwise by `angle` radians
+    ///
+    /// Fixed-point throughout (see `Scalar::sin_cos`), so rotations are
+    /// deterministic, though not exactly length-preserving.
+    pub fn rotate(&self, angle: Scalar) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        Vec2 {
+            x: self.x * cos - self.y * sin,
+            y: self.x * sin + self.y * cos,
+        }
+    }
+    
+    /// Angle from the positive x axis in radians, in [-π, π]
+    pub fn angle(&self) -> Scalar {
+        self.y.atan2(self.x)
+    }
 }
 
 impl fmt::Display for Vec2 {
@@ -210,4 +247,31 @@ mod tests {
         let dot = a.dot(&b);
         assert_eq!(dot.to_float(), 23.0); // 2*4 + 3*5 = 8 + 15 = 23
     }
-}
\ No newline at end of file
+    
+    #[test]
+    fn test_vec2_rotate_quarter_turn() {
+        let rotated = Vec2::UNIT_X.rotate(Scalar::FRAC_PI_2);
+        assert!((rotated - Vec2::UNIT_Y).magnitude() < Scalar::from_float(0.001), "{}", rotated);
+        assert!((rotated.angle() - Scalar::FRAC_PI_2).abs() < Scalar::from_float(0.001));
+        // A quarter turn of the axes happens to be exact
+        assert_eq!(rotated, Vec2::UNIT_Y);
+    }
+    
+    #[test]
+    fn test_vec2_rotate_twice_matches_doubled_angle() {
+        // Exact where the sines and cosines are: quarter turns. (Doubling
+        // FRAC_PI_2 overshoots PI by one unit, so compare with PI itself.)
+        let half_turn = Vec2::UNIT_X.rotate(Scalar::FRAC_PI_2).rotate(Scalar::FRAC_PI_2);
+        assert_eq!(half_turn, Vec2::UNIT_X.rotate(Scalar::PI));
+        
+        // Otherwise rounding differs by a few units in the last place
+        let v = Vec2::new(3.0, -2.0);
+        for i in 0..20 {
+            let angle = Scalar::from_float(i as f32 * 0.37);
+            let twice = v.rotate(angle).rotate(angle);
+            let doubled = v.rotate(angle + angle);
+            assert!((twice - doubled).magnitude() < Scalar::from_float(0.001), "{}: {} vs {}", angle, twice, doubled);
+            assert_eq!(twice, v.rotate(angle).rotate(angle));
+        }
+    }
+}
@@ -1,18 +1,52 @@
 //! Circle entity for physics simulation
 
 use crate::math::{Scalar, Vec2};
+use fixed::types::I16F16;
 use serde::{Serialize, Deserialize};
 
+/// Largest speed `set_velocity_clamped` lets through
+///
+/// Keeps `velocity.magnitude_squared()` (and so kinetic energy per unit mass)
+/// at half the Q16.16 range, so the first step and energy metrics can't overflow.
+pub const MAX_SAFE_SPEED: Scalar = Scalar(I16F16::from_bits(0x00800000));
+
+/// Speed below which a circle counts as sleeping (0.05 m/s)
+///
+/// There is no sleep system that skips integration yet; this only classifies
+/// bodies for `World::num_sleeping` and friends.
+pub const SLEEP_SPEED: Scalar = Scalar(I16F16::from_bits(0x00000CCD));
+
 /// A physics circle with position, velocity, and properties
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct Circle {
     pub position: Vec2,
     pub old_position: Vec2,  // For Verlet integration
-    pub velocity: Vec2,       // Cached velocity for collision calculations
+    /// Cached velocity for collision calculations
+    ///
+    /// Read it with `velocity()` and change it with `set_velocity`, which
+    /// keeps `old_position` in step. Under the default position Verlet
+    /// integrator, `World::step` leaves it equal to `verlet_velocity`.
+    pub(crate) velocity: Vec2,
     pub radius: Scalar,
     pub mass: Scalar,
     pub restitution: Scalar,
     pub friction: Scalar,
+    /// Linear drag coefficient (retarding force = -drag * velocity)
+    #[serde(default)]
+    pub drag: Scalar,
+    /// Orientation in radians, counterclockwise, kept in [-π, π)
+    #[serde(default)]
+    pub angle: Scalar,
+    /// Spin in radians per second, counterclockwise
+    ///
+    /// Only advances `angle` for now; contacts neither exert nor feel torque.
+    #[serde(default)]
+    pub angular_velocity: Scalar,
+    /// Pinned in place: never integrated, and infinitely massive in contacts
+    ///
+    /// Dynamic bodies still bounce off (and are pushed out of) a static one.
+    #[serde(default)]
+    pub is_static: bool,
 }
 
 impl Circle {
@@ -26,16 +60,143 @@ impl Circle {
             mass,
             restitution: Scalar::from_float(0.5),
             friction: Scalar::from_float(0.1),
+            drag: Scalar::ZERO,
+            angle: Scalar::ZERO,
+            angular_velocity: Scalar::ZERO,
+            is_static: false,
         }
     }
     
+    /// Create an immovable circle, e.g. an anchor or a round obstacle
+    ///
+    /// `mass` is irrelevant to contacts and left at one.
+    pub fn new_static(position: Vec2, radius: Scalar) -> Self {
+        Circle {
+            is_static: true,
+            ..Circle::new(position, radius, Scalar::ONE)
+        }
+    }
+    
+    /// Create a circle already moving at `velocity`
+    ///
+    /// Equivalent to `new` followed by `set_velocity`, so the Verlet history
+    /// can't be invalidated by moving the circle in between.
+    pub fn with_velocity(position: Vec2, velocity: Vec2, radius: Scalar, mass: Scalar, dt: Scalar) -> Self {
+        let mut circle = Circle::new(position, radius, mass);
+        circle.set_velocity(velocity, dt);
+        circle
+    }
+    
+    /// Rebuild a circle exactly from raw Q16.16 bits (e.g. a committed journal)
+    ///
+    /// Material properties take the `new` defaults.
+    pub fn from_bits(
+        position: [i32; 2],
+        old_position: [i32; 2],
+        velocity: [i32; 2],
+        radius: i32,
+        mass: i32,
+    ) -> Self {
+        let vec = |bits: [i32; 2]| Vec2::from_scalars(Scalar::from_bits(bits[0]), Scalar::from_bits(bits[1]));
+        Circle {
+            old_position: vec(old_position),
+            velocity: vec(velocity),
+            ..Circle::new(vec(position), Scalar::from_bits(radius), Scalar::from_bits(mass))
+        }
+    }
+    
+    /// Advance `angle` by one step of `angular_velocity`, wrapping into [-π, π)
+    ///
+    /// Wraps at most once, so spins up to a full turn per step stay exact.
+    pub fn advance_angle(&mut self, dt: Scalar) {
+        let mut angle = self.angle + self.angular_velocity * dt;
+        if angle >= Scalar::PI {
+            angle = angle - Scalar::TAU;
+        } else if angle < -Scalar::PI {
+            angle = angle + Scalar::TAU;
+        }
+        self.angle = angle;
+    }
+    
+    /// `1 / mass`, or zero for a static circle
+    pub fn inv_mass(&self) -> Scalar {
+        if self.is_static {
+            Scalar::ZERO
+        } else {
+            Scalar::ONE / self.mass
+        }
+    }
+    
+    /// Kinetic energy `0.5 * m * v^2`, with `v` taken from the position history
+    ///
+    /// That is position Verlet's velocity; `World::calculate_kinetic_energy`
+    /// uses the synchronized `velocity()` under velocity Verlet instead.
+    pub fn kinetic_energy(&self, timestep: Scalar) -> Scalar {
+        let velocity = (self.position - self.old_position) / timestep;
+        self.mass * velocity.magnitude_squared() * Scalar::HALF
+    }
+    
+    /// Linear momentum `m * v`, with `v` taken from the position history
+    ///
+    /// See `kinetic_energy` for velocity Verlet.
+    pub fn momentum(&self, timestep: Scalar) -> Vec2 {
+        (self.position - self.old_position) / timestep * self.mass
+    }
+    
+    /// The cached velocity, as of the end of the last step
+    pub fn velocity(&self) -> Vec2 {
+        self.velocity
+    }
+    
+    /// Velocity derived from the position history, `(position - old_position) / dt`
+    ///
+    /// This is what the next Verlet step will actually carry forward. After
+    /// a position Verlet `World::step` it equals `velocity()` exactly;
+    /// `set_velocity` or moving `position` by hand can leave them a few bits
+    /// apart until the next step. Velocity Verlet keeps its own `velocity`,
+    /// so there the two differ by design.
+    pub fn verlet_velocity(&self, timestep: Scalar) -> Vec2 {
+        (self.position - self.old_position) / timestep
+    }
+    
     /// Update velocity from position history
     pub fn update_velocity(&mut self, dt: Scalar) {
-        self.velocity = (self.position - self.old_position) / dt;
+        self.velocity = self.verlet_velocity(dt);
     }
     
     /// Set velocity by adjusting old_position
+    ///
+    /// Also updates the cached `velocity` so momentum/energy are correct
+    /// before the first step.
     pub fn set_velocity(&mut self, velocity: Vec2, dt: Scalar) {
         self.old_position = self.position - velocity * dt;
+        self.velocity = velocity;
+    }
+    
+    /// Like `set_velocity`, but first clamps the speed to `MAX_SAFE_SPEED`
+    ///
+    /// For untrusted input: an absurd velocity keeps its direction but can
+    /// no longer overflow Q16.16 on the first step.
+    pub fn set_velocity_clamped(&mut self, velocity: Vec2, dt: Scalar) {
+        self.set_velocity(velocity.magnitude_clamped(MAX_SAFE_SPEED), dt);
+    }
+    
+    /// Whether the velocity encoded in `old_position` matches the cached `velocity`
+    ///
+    /// Moving `position` after `set_velocity` silently changes the encoded
+    /// velocity; this catches that. Allows two bits of Q16.16 rounding.
+    pub fn velocity_consistent(&self, dt: Scalar) -> bool {
+        let encoded = self.position - self.velocity * dt;
+        let tolerance = Scalar::from_bits(2);
+        (encoded.x - self.old_position.x).abs() <= tolerance
+            && (encoded.y - self.old_position.y).abs() <= tolerance
+    }
+    
+    /// Whether the cached velocity is below `SLEEP_SPEED`
+    pub fn is_sleeping(&self) -> bool {
+        let (vx, vy) = (self.velocity.x.abs(), self.velocity.y.abs());
+        // Component check first so fast bodies can't overflow the square
+        vx < SLEEP_SPEED && vy < SLEEP_SPEED
+            && self.velocity.magnitude_squared() < SLEEP_SPEED * SLEEP_SPEED
     }
-}
\ No newline at end of file
+}
@@ -4,33 +4,147 @@
 //! but adapted for discrete impulse-based collision response.
 
 #[cfg(not(feature = "std"))]
-use alloc::{vec, vec::Vec};
+use alloc::{borrow::Cow, vec, vec::Vec};
+#[cfg(feature = "std")]
+use std::borrow::Cow;
 
-use crate::math::{Scalar, Vec2};
-use crate::physics::Circle;
-use crate::spatial::{Collision, BoundaryCollision, Boundary};
+use crate::math::{Aabb, Scalar, Vec2};
+use crate::physics::{Circle, CollisionFilter, Rect};
+use crate::spatial::{Collision, BoundaryCollision, Boundary, ContactMode, GridCells};
+use serde::{Serialize, Deserialize};
+
+/// Rule for combining two circles' restitution coefficients
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RestitutionRule {
+    /// Use the smaller coefficient (the less bouncy body dominates)
+    Min,
+    /// Use the larger coefficient (the bouncier body dominates)
+    Max,
+    /// Square root of the product
+    GeometricMean,
+    /// Arithmetic mean
+    Average,
+}
+
+impl RestitutionRule {
+    /// Combine two restitution coefficients according to this rule
+    pub fn combine(&self, a: Scalar, b: Scalar) -> Scalar {
+        match self {
+            RestitutionRule::Min => a.min(b),
+            RestitutionRule::Max => a.max(b),
+            RestitutionRule::GeometricMean => (a * b).sqrt(),
+            RestitutionRule::Average => (a + b) * Scalar::HALF,
+        }
+    }
+}
+
+/// When circle-circle position correction is applied relative to velocity resolution
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CorrectionOrder {
+    /// Each approaching pair's velocity impulse and overlap correction are
+    /// emitted together and applied in one pass (the historical order);
+    /// separating pairs are left overlapping
+    #[default]
+    Combined,
+    /// Apply all velocity impulses first, then correct every overlapping
+    /// pair, separating or not, in a second accumulation pass
+    ///
+    /// Contact chains and stacks settle more evenly, at the cost of a second
+    /// pass over the circles.
+    Split,
+}
 
 /// Collision response configuration
-#[derive(Debug, Clone)]
+///
+/// Missing fields deserialize to their defaults.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct CollisionConfig {
     /// Coefficient of restitution (0 = perfectly inelastic, 1 = perfectly elastic)
     pub restitution: Scalar,
+    /// How collisions use per-circle restitution (`None` uses the global
+    /// `restitution` for every contact)
+    ///
+    /// Circle pairs combine both circles' coefficients with the rule; a
+    /// circle bouncing off the boundary or a rectangle uses its own.
+    pub restitution_rule: Option<RestitutionRule>,
     /// Position correction factor (0.2-0.8 typical)
     pub position_correction: Scalar,
     /// Minimum separation velocity to apply restitution
     pub velocity_threshold: Scalar,
+    /// Coulomb friction coefficient for sliding contacts (0 = frictionless)
+    ///
+    /// Each contact's tangential impulse is at most `friction` times its
+    /// normal impulse.
+    pub friction: Scalar,
+    /// Where reported contact points are placed
+    pub contact_mode: ContactMode,
+    /// Cell shape of the broad-phase grid
+    pub grid_cells: GridCells,
+    /// Whether position correction shares the velocity pass
+    pub correction_order: CorrectionOrder,
+    /// Upper bound on each contact's normal impulse (`None` = unbounded)
+    ///
+    /// `World::step` also caps the velocity change a body picks up from
+    /// position correction at `max_impulse / mass`, so deep spawn overlaps
+    /// separate at a bounded speed instead of launching the bodies apart.
+    pub max_impulse: Option<Scalar>,
+    /// Sweep fast circle pairs over each step so they can't tunnel through
+    /// each other (see `detect_swept_collisions`)
+    ///
+    /// Pairs that touched mid-step are moved back to the time of impact and
+    /// resolved there. Checks every pair, so it is off by default.
+    pub continuous: bool,
 }
 
 impl Default for CollisionConfig {
     fn default() -> Self {
         Self {
             restitution: Scalar::from_float(0.8),          // 80% elastic
+            restitution_rule: None,                          // Global restitution
             position_correction: Scalar::from_float(0.4),   // 40% position correction
             velocity_threshold: Scalar::from_float(0.01),   // Minimum velocity for bounce
+            friction: Scalar::ZERO,                          // Frictionless contacts
+            contact_mode: ContactMode::SurfaceA,             // Contact on A's surface
+            grid_cells: GridCells::Square,                   // 2x max radius cells
+            correction_order: CorrectionOrder::Combined,     // Correct with velocity
+            max_impulse: None,                               // Unbounded impulses
+            continuous: false,                               // Discrete detection only
         }
     }
 }
 
+impl CollisionConfig {
+    /// Named collision presets
+    ///
+    /// - `pool`: nearly elastic ball-on-ball contacts (restitution 0.95)
+    /// - `bouncy`: perfectly elastic (restitution 1.0)
+    /// - `sticky`: almost no bounce (restitution 0.1)
+    /// - `realistic`: moderate energy loss (restitution 0.7)
+    ///
+    /// All presets use the 0.8 position correction of the bundled scenarios.
+    pub fn preset(name: &str) -> Option<Self> {
+        let restitution = match name {
+            "pool" => 0.95,
+            "bouncy" => 1.0,
+            "sticky" => 0.1,
+            "realistic" => 0.7,
+            _ => return None,
+        };
+        
+        Some(Self {
+            restitution: Scalar::from_float(restitution),
+            position_correction: Scalar::from_float(0.8),
+            ..Self::default()
+        })
+    }
+    
+    /// Names accepted by [`CollisionConfig::preset`]
+    pub fn preset_names() -> &'static [&'static str] {
+        &["pool", "bouncy", "sticky", "realistic"]
+    }
+}
+
 /// Impulse to apply to a circle
 #[derive(Debug, Clone)]
 pub struct Impulse {
@@ -42,6 +156,27 @@ pub struct Impulse {
     pub delta_pos: Vec2,
 }
 
+/// A resolved circle-circle contact
+///
+/// Carries the impulse strength so games can scale sounds or haptics by
+/// impact.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CollisionEvent {
+    /// Index of first circle
+    pub idx_a: usize,
+    /// Index of second circle
+    pub idx_b: usize,
+    /// Contact point (in world space)
+    pub contact: Vec2,
+    /// Magnitude of the normal impulse applied to each circle
+    pub impulse: Scalar,
+    /// Closing speed along the collision normal before resolution
+    pub relative_speed: Scalar,
+    /// Step the contact was resolved in (`World::steps_taken` before that
+    /// step); zero when resolving outside a `World`
+    pub step: u32,
+}
+
 /// Resolve circle-circle collisions using impulse method
 /// Returns impulses to apply to circles (functional approach)
 pub fn resolve_collisions(
@@ -49,15 +184,28 @@ pub fn resolve_collisions(
     collisions: &[Collision],
     config: &CollisionConfig,
 ) -> Vec<Impulse> {
+    resolve_collisions_with_events(circles, collisions, config).0
+}
+
+/// Resolve circle-circle collisions, also reporting one event per resolved contact
+///
+/// Separating pairs produce neither impulses nor events.
+pub fn resolve_collisions_with_events(
+    circles: &[Circle],
+    collisions: &[Collision],
+    config: &CollisionConfig,
+) -> (Vec<Impulse>, Vec<CollisionEvent>) {
     let mut impulses = Vec::new();
+    let mut events = Vec::new();
     
     for collision in collisions {
-        let circle_a = &circles[collision.idx_a];
-        let circle_b = &circles[collision.idx_b];
+        let (idx_a, idx_b, normal) = oriented(collision, circles);
+        let circle_a = &circles[idx_a];
+        let circle_b = &circles[idx_b];
         
         // Calculate relative velocity
         let relative_velocity = circle_b.velocity - circle_a.velocity;
-        let velocity_along_normal = relative_velocity.dot(&collision.normal);
+        let velocity_along_normal = relative_velocity.dot(&normal);
         
         // Don't resolve if velocities are separating
         if velocity_along_normal > Scalar::ZERO {
@@ -66,40 +214,165 @@ pub fn resolve_collisions(
         
         // Calculate restitution based on velocity
         let e = if velocity_along_normal.abs() > config.velocity_threshold {
-            config.restitution
+            match config.restitution_rule {
+                Some(rule) => rule.combine(circle_a.restitution, circle_b.restitution),
+                None => config.restitution,
+            }
         } else {
             Scalar::ZERO // No bounce for very slow collisions
         };
         
-        // Calculate impulse scalar
-        let mass_a = circle_a.mass;
-        let mass_b = circle_b.mass;
-        let impulse_scalar = -(Scalar::ONE + e) * velocity_along_normal 
-            / (Scalar::ONE / mass_a + Scalar::ONE / mass_b);
+        // Calculate impulse scalar; static circles have infinite mass
+        let inv_mass_sum = circle_a.inv_mass() + circle_b.inv_mass();
+        if inv_mass_sum == Scalar::ZERO {
+            continue;
+        }
+        let mut impulse_scalar = -(Scalar::ONE + e) * velocity_along_normal / inv_mass_sum;
+        if let Some(max_impulse) = config.max_impulse {
+            impulse_scalar = impulse_scalar.min(max_impulse);
+        }
         
-        // Calculate impulse vector
-        let impulse = collision.normal * impulse_scalar;
+        // Calculate impulse vector, with friction against any sliding
+        let impulse = normal * impulse_scalar
+            + friction_impulse(relative_velocity, normal, impulse_scalar, inv_mass_sum, config.friction);
         
         // Apply to velocities (using inverse mass)
-        let delta_v_a = -impulse / mass_a;
-        let delta_v_b = impulse / mass_b;
+        let delta_v_a = if circle_a.is_static { Vec2::ZERO } else { -impulse / circle_a.mass };
+        let delta_v_b = if circle_b.is_static { Vec2::ZERO } else { impulse / circle_b.mass };
         
-        // Position correction to resolve overlap
-        let total_correction = collision.depth * config.position_correction;
-        let mass_sum = mass_a + mass_b;
-        let correction_a = collision.normal * (total_correction * mass_b / mass_sum);
-        let correction_b = -collision.normal * (total_correction * mass_a / mass_sum);
+        // Position correction to resolve overlap, unless it gets its own pass
+        let (delta_pos_a, delta_pos_b) = match config.correction_order {
+            CorrectionOrder::Combined => overlap_correction(normal, collision.depth, circle_a, circle_b, config),
+            CorrectionOrder::Split => (Vec2::ZERO, Vec2::ZERO),
+        };
         
         impulses.push(Impulse {
-            idx: collision.idx_a,
+            idx: idx_a,
             delta_v: delta_v_a,
-            delta_pos: -correction_a,
+            delta_pos: delta_pos_a,
         });
         
         impulses.push(Impulse {
-            idx: collision.idx_b,
+            idx: idx_b,
             delta_v: delta_v_b,
-            delta_pos: -correction_b,
+            delta_pos: delta_pos_b,
+        });
+        
+        events.push(CollisionEvent {
+            idx_a: collision.idx_a,
+            idx_b: collision.idx_b,
+            contact: collision.contact,
+            impulse: impulse_scalar,
+            relative_speed: -velocity_along_normal,
+            step: 0,
+        });
+    }
+    
+    (impulses, events)
+}
+
+/// Coulomb friction impulse on B for a contact with normal impulse `normal_impulse`
+///
+/// `relative_velocity` is B's velocity relative to A and `normal` points from
+/// A to B; A receives the negation. Opposes the tangential part of the
+/// relative velocity: just enough to stop the sliding, but no more than
+/// `friction * normal_impulse`.
+fn friction_impulse(
+    relative_velocity: Vec2,
+    normal: Vec2,
+    normal_impulse: Scalar,
+    inv_mass_sum: Scalar,
+    friction: Scalar,
+) -> Vec2 {
+    if friction == Scalar::ZERO {
+        return Vec2::ZERO;
+    }
+    let tangent_velocity = relative_velocity - normal * relative_velocity.dot(&normal);
+    let tangent_speed = tangent_velocity.magnitude();
+    if tangent_speed == Scalar::ZERO {
+        return Vec2::ZERO;
+    }
+    
+    let magnitude = (tangent_speed / inv_mass_sum).min(friction * normal_impulse);
+    -(tangent_velocity / tangent_speed) * magnitude
+}
+
+/// `collision`'s bodies and normal (from the first to the second), ordered
+/// by position rather than by index
+///
+/// Q16.16 multiplication floors, so `(-n) * s` and `-(n * s)` can differ in
+/// the last bit: resolving a pair as (a, b) or as (b, a) gives slightly
+/// different results. Ordering by position keeps the outcome independent of
+/// where the bodies sit in the circle list.
+///
+/// The narrow phase skips exactly coincident circles (there is no normal to
+/// push along), so equal positions shouldn't reach here. If they do, e.g.
+/// with a hand-built `Collision`, ties are broken by velocity, then radius
+/// and mass. Only bodies equal in all of these fall back to index order, and
+/// swapping two such bodies in the list yields the same world.
+fn oriented(collision: &Collision, circles: &[Circle]) -> (usize, usize, Vec2) {
+    let key = |idx: usize| {
+        let circle = &circles[idx];
+        (
+            [circle.position.x, circle.position.y, circle.velocity.x, circle.velocity.y].map(|s| s.to_bits()),
+            [circle.radius, circle.mass].map(|s| s.to_bits()),
+        )
+    };
+    if key(collision.idx_b) < key(collision.idx_a) {
+        (collision.idx_b, collision.idx_a, -collision.normal)
+    } else {
+        (collision.idx_a, collision.idx_b, collision.normal)
+    }
+}
+
+/// Position changes for A and B that remove `position_correction` of the overlap,
+/// split by inverse mass (`normal` points from A to B)
+///
+/// A static circle doesn't move; the other one takes the whole correction.
+fn overlap_correction(normal: Vec2, depth: Scalar, a: &Circle, b: &Circle, config: &CollisionConfig) -> (Vec2, Vec2) {
+    let total_correction = depth * config.position_correction;
+    match (a.is_static, b.is_static) {
+        (true, true) => return (Vec2::ZERO, Vec2::ZERO),
+        (true, false) => return (Vec2::ZERO, normal * total_correction),
+        (false, true) => return (-normal * total_correction, Vec2::ZERO),
+        (false, false) => {}
+    }
+    let (mass_a, mass_b) = (a.mass, b.mass);
+    let mass_sum = mass_a + mass_b;
+    let correction_a = normal * (total_correction * mass_b / mass_sum);
+    let correction_b = -normal * (total_correction * mass_a / mass_sum);
+    (-correction_a, -correction_b)
+}
+
+/// Position-only impulses correcting every overlapping pair in `collisions`
+///
+/// The second pass of `CorrectionOrder::Split`; unlike the combined order,
+/// pairs already separating are corrected too.
+pub fn resolve_position_corrections(
+    circles: &[Circle],
+    collisions: &[Collision],
+    config: &CollisionConfig,
+) -> Vec<Impulse> {
+    let mut impulses = Vec::new();
+    
+    for collision in collisions {
+        let (idx_a, idx_b, normal) = oriented(collision, circles);
+        let (delta_pos_a, delta_pos_b) = overlap_correction(
+            normal,
+            collision.depth,
+            &circles[idx_a],
+            &circles[idx_b],
+            config,
+        );
+        impulses.push(Impulse {
+            idx: idx_a,
+            delta_v: Vec2::ZERO,
+            delta_pos: delta_pos_a,
+        });
+        impulses.push(Impulse {
+            idx: idx_b,
+            delta_v: Vec2::ZERO,
+            delta_pos: delta_pos_b,
         });
     }
     
@@ -107,6 +380,17 @@ pub fn resolve_collisions(
 }
 
 /// Resolve boundary collisions
+///
+/// A circle in a corner gets one contact per wall, and they are resolved
+/// jointly: every wall's normal impulse is applied first, then each wall's
+/// friction acts on the velocity that results. The walls are perpendicular,
+/// so the normal impulses and position pushes stay on their own axes and
+/// neither axis is corrected twice; friction, though, acts along the other
+/// wall's normal, and must oppose the velocity after that wall's bounce
+/// rather than before it.
+///
+/// `collisions` must list each circle's contacts together, as
+/// `detect_boundary_collisions` does.
 pub fn resolve_boundary_collisions(
     circles: &[Circle],
     collisions: &[BoundaryCollision],
@@ -114,54 +398,131 @@ pub fn resolve_boundary_collisions(
 ) -> Vec<Impulse> {
     let mut impulses = Vec::new();
     
-    for collision in collisions {
-        let circle = &circles[collision.idx];
-        
-        // Determine normal based on boundary
-        let normal = match collision.boundary {
-            Boundary::Left => Vec2::from_scalars(Scalar::ONE, Scalar::ZERO),
-            Boundary::Right => Vec2::from_scalars(-Scalar::ONE, Scalar::ZERO),
-            Boundary::Bottom => Vec2::from_scalars(Scalar::ZERO, Scalar::ONE),
-            Boundary::Top => Vec2::from_scalars(Scalar::ZERO, -Scalar::ONE),
-        };
+    for contacts in collisions.chunk_by(|a, b| a.idx == b.idx) {
+        let idx = contacts[0].idx;
+        let circle = &circles[idx];
+        let normals = || contacts.iter().filter_map(|collision| {
+            let normal = boundary_normal(collision.boundary);
+            static_contact_normal(circle, normal, collision.depth, config)
+                .map(|(impulse_scalar, delta_pos)| (normal, impulse_scalar, delta_pos))
+        });
         
-        // Calculate velocity along normal
-        let velocity_along_normal = circle.velocity.dot(&normal);
+        // Velocity after all of this circle's bounces, before friction
+        let bounced = normals().fold(circle.velocity, |velocity, (normal, impulse_scalar, _)| {
+            velocity + normal * impulse_scalar / circle.mass
+        });
         
-        // Don't resolve if velocity is away from boundary
-        if velocity_along_normal > Scalar::ZERO {
-            continue;
+        for (normal, impulse_scalar, delta_pos) in normals() {
+            let impulse = normal * impulse_scalar
+                + friction_impulse(bounced, normal, impulse_scalar, Scalar::ONE / circle.mass, config.friction);
+            impulses.push(Impulse {
+                idx,
+                delta_v: impulse / circle.mass,
+                delta_pos,
+            });
         }
-        
-        // Apply restitution
-        let e = if velocity_along_normal.abs() > config.velocity_threshold {
-            config.restitution
-        } else {
-            Scalar::ZERO
-        };
-        
-        // Calculate impulse (boundary has infinite mass)
-        let impulse_scalar = -(Scalar::ONE + e) * velocity_along_normal;
-        let impulse = normal * impulse_scalar;
-        
-        // Velocity change
-        let delta_v = impulse / circle.mass;
-        
-        // Position correction to push circle back inside bounds
-        let delta_pos = normal * (collision.depth * config.position_correction);
-        
-        impulses.push(Impulse {
-            idx: collision.idx,
-            delta_v,
-            delta_pos,
-        });
     }
     
     impulses
 }
 
+/// Outward normal of a world boundary, pointing into the world
+fn boundary_normal(boundary: Boundary) -> Vec2 {
+    match boundary {
+        Boundary::Left => Vec2::from_scalars(Scalar::ONE, Scalar::ZERO),
+        Boundary::Right => Vec2::from_scalars(-Scalar::ONE, Scalar::ZERO),
+        Boundary::Bottom => Vec2::from_scalars(Scalar::ZERO, Scalar::ONE),
+        Boundary::Top => Vec2::from_scalars(Scalar::ZERO, -Scalar::ONE),
+    }
+}
+
+/// Resolve circle contacts with rectangle obstacles
+///
+/// Rectangles are treated like the world boundary: infinitely massive, with
+/// the penetration reflected along with the velocity. `collisions` come from
+/// `detect_rect_collisions`.
+pub fn resolve_rect_collisions(
+    circles: &[Circle],
+    collisions: &[Collision],
+    config: &CollisionConfig,
+) -> Vec<Impulse> {
+    collisions.iter()
+        .filter_map(|collision| static_contact_impulse(circles, collision.idx_a, -collision.normal, collision.depth, config))
+        .collect()
+}
+
+/// Impulse bouncing circle `idx` off an immovable surface
+///
+/// `normal` points out of the surface towards the circle. `None` if the
+/// circle is static or already moving away.
+fn static_contact_impulse(
+    circles: &[Circle],
+    idx: usize,
+    normal: Vec2,
+    depth: Scalar,
+    config: &CollisionConfig,
+) -> Option<Impulse> {
+    let circle = &circles[idx];
+    let (impulse_scalar, delta_pos) = static_contact_normal(circle, normal, depth, config)?;
+    let impulse = normal * impulse_scalar
+        + friction_impulse(circle.velocity, normal, impulse_scalar, Scalar::ONE / circle.mass, config.friction);
+    
+    Some(Impulse {
+        idx,
+        delta_v: impulse / circle.mass,
+        delta_pos,
+    })
+}
+
+/// Normal part of bouncing `circle` off an immovable surface: the impulse
+/// magnitude along `normal` and the position push
+///
+/// `None` if the circle is static or already moving away.
+fn static_contact_normal(
+    circle: &Circle,
+    normal: Vec2,
+    depth: Scalar,
+    config: &CollisionConfig,
+) -> Option<(Scalar, Vec2)> {
+    if circle.is_static {
+        return None;
+    }
+    
+    // Calculate velocity along normal
+    let velocity_along_normal = circle.velocity.dot(&normal);
+    
+    // Don't resolve if velocity is away from the surface
+    if velocity_along_normal > Scalar::ZERO {
+        return None;
+    }
+    
+    // Apply restitution; surfaces have none of their own to combine
+    let e = if velocity_along_normal.abs() > config.velocity_threshold {
+        match config.restitution_rule {
+            Some(_) => circle.restitution,
+            None => config.restitution,
+        }
+    } else {
+        Scalar::ZERO
+    };
+    
+    // Calculate impulse (the surface has infinite mass, so only this
+    // circle's mass enters)
+    let impulse_scalar = -(Scalar::ONE + e) * velocity_along_normal * circle.mass;
+    
+    // Reflect the penetration the same way the velocity was reflected:
+    // the circle ends `e * depth` clear of the wall, as if it had bounced
+    // at the surface mid-step. A partial `position_correction` push here
+    // would leave it inside and lose path length (and, under gravity,
+    // height) on every bounce.
+    let delta_pos = normal * (depth * (Scalar::ONE + e));
+    
+    Some((impulse_scalar, delta_pos))
+}
+
 /// Apply impulses to circles (functional update)
-/// Returns new circle states after applying impulses
+/// Returns new circle states after applying impulses; static circles are
+/// left as they are
 pub fn apply_impulses(circles: &[Circle], impulses: &[Impulse]) -> Vec<Circle> {
     // Create a map of accumulated impulses per circle
     let mut impulse_map: Vec<(Vec2, Vec2)> = vec![(Vec2::ZERO, Vec2::ZERO); circles.len()];
@@ -174,6 +535,9 @@ pub fn apply_impulses(circles: &[Circle], impulses: &[Impulse]) -> Vec<Circle> {
     
     // Apply accumulated impulses to create new circle states
     circles.iter().enumerate().map(|(idx, circle)| {
+        if circle.is_static {
+            return *circle;
+        }
         let (delta_v, delta_pos) = impulse_map[idx];
         Circle {
             position: circle.position + delta_pos,
@@ -184,39 +548,171 @@ pub fn apply_impulses(circles: &[Circle], impulses: &[Impulse]) -> Vec<Circle> {
     }).collect()
 }
 
+/// Contacts found by one pass of `resolve_all_collisions_with_contacts`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StepContacts {
+    /// Overlapping circle pairs `(idx_a, idx_b)`, resolved or separating
+    pub pairs: Vec<(usize, usize)>,
+    /// Number of circle/boundary and circle/rectangle contacts
+    pub boundary_hits: u32,
+    /// One event per resolved (approaching) circle pair
+    pub events: Vec<CollisionEvent>,
+}
+
+/// Broad-phase efficiency for one pass of `resolve_all_collisions_with_contacts`
+///
+/// `candidate_pairs` far above `actual_collisions` means the grid cells are
+/// too coarse for the scene (or the circles too clustered).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BroadPhaseStats {
+    /// Pairs sharing a grid cell and passing `should_collide`, i.e. sent to narrow phase
+    pub candidate_pairs: usize,
+    /// Candidate pairs that actually overlapped
+    pub actual_collisions: usize,
+    /// Grid cells holding at least one circle
+    pub occupied_cells: usize,
+}
+
 /// Complete collision resolution pipeline (functional)
-/// Takes circles and returns updated circles after collision resolution
+/// Takes circles (and static rectangle obstacles) and returns updated circles
+/// after collision resolution
 pub fn resolve_all_collisions(
     circles: &[Circle],
+    rects: &[Rect],
     world_width: Scalar,
     world_height: Scalar,
     config: &CollisionConfig,
 ) -> Vec<Circle> {
-    use crate::spatial::{SpatialGrid, detect_collisions, detect_boundary_collisions};
+    resolve_all_collisions_with_contacts(circles, rects, world_width, world_height, config, None).0.into_owned()
+}
+
+/// Collision resolution pipeline that also reports the contacts it found
+///
+/// Lets callers (e.g. metrics) reuse this pass's broad and narrow phase
+/// instead of building another spatial grid, and reports how well the broad
+/// phase pruned pairs. Pairs rejected by
+/// `should_collide` are dropped before narrow phase.
+///
+/// When nothing touches (common in sparse scenes) the circles come back
+/// borrowed, unchanged, so the caller can skip copying them.
+pub fn resolve_all_collisions_with_contacts<'a>(
+    circles: &'a [Circle],
+    rects: &[Rect],
+    world_width: Scalar,
+    world_height: Scalar,
+    config: &CollisionConfig,
+    should_collide: Option<&CollisionFilter>,
+) -> (Cow<'a, [Circle]>, StepContacts, BroadPhaseStats) {
+    use crate::spatial::{SpatialGrid, detect_collisions_with_mode, detect_boundary_collisions, detect_rect_collisions};
     
-    // Build spatial grid (cell size = 2 * max radius)
-    let max_radius = circles.iter()
-        .map(|c| c.radius)
-        .max()
-        .unwrap_or(Scalar::from_float(1.0));
-    let cell_size = max_radius * Scalar::from_float(2.0);
+    // Move circles that tunneled through each other back to where they met
+    let original = circles;
+    let (cell_width, cell_height) = config.grid_cells.dimensions(circles);
+    let swept = if config.continuous {
+        sweep(circles, cell_width, cell_height, world_width, world_height, should_collide)
+    } else {
+        None
+    };
+    let (circles, swept_collisions) = match &swept {
+        Some((rewound, collisions)) => (rewound.as_slice(), collisions.as_slice()),
+        None => (circles, &[][..]),
+    };
     
-    let grid = SpatialGrid::build(circles, cell_size, world_width, world_height);
+    // Build spatial grid (square cells of 2 * max radius by default)
+    let grid = SpatialGrid::build_rect(circles, cell_width, cell_height, world_width, world_height);
     
-    // Get potential collision pairs from spatial grid
-    let pairs = grid.get_collision_pairs();
+    // Get potential collision pairs from spatial grid, minus filtered ones
+    let mut pairs = grid.get_collision_pairs();
+    if let Some(filter) = should_collide {
+        pairs.retain(|&(a, b)| filter.allows(a, b));
+    }
     
     // Detect actual collisions
-    let circle_collisions = detect_collisions(circles, &pairs);
+    let mut circle_collisions = detect_collisions_with_mode(circles, &pairs, config.contact_mode);
     let boundary_collisions = detect_boundary_collisions(circles, world_width, world_height);
+    let rect_collisions = detect_rect_collisions(circles, rects);
+    let stats = BroadPhaseStats {
+        candidate_pairs: pairs.len(),
+        actual_collisions: circle_collisions.len(),
+        occupied_cells: grid.num_occupied_cells(),
+    };
+    circle_collisions.extend(swept_collisions.iter().cloned());
+    
+    // No contacts means no impulses; applying none is the identity (and
+    // nothing was rewound, or there would be swept contacts)
+    if circle_collisions.is_empty() && boundary_collisions.is_empty() && rect_collisions.is_empty() {
+        return (Cow::Borrowed(original), StepContacts::default(), stats);
+    }
     
     // Resolve collisions to get impulses
-    let mut all_impulses = resolve_collisions(circles, &circle_collisions, config);
+    let (mut all_impulses, events) = resolve_collisions_with_events(circles, &circle_collisions, config);
     let boundary_impulses = resolve_boundary_collisions(circles, &boundary_collisions, config);
     all_impulses.extend(boundary_impulses);
+    all_impulses.extend(resolve_rect_collisions(circles, &rect_collisions, config));
+    
+    let contacts = StepContacts {
+        pairs: circle_collisions.iter().map(|c| (c.idx_a, c.idx_b)).collect(),
+        boundary_hits: (boundary_collisions.len() + rect_collisions.len()) as u32,
+        events,
+    };
     
-    // Apply impulses to circles
-    apply_impulses(circles, &all_impulses)
+    // Apply impulses to circles, then any deferred position corrections
+    let mut resolved = apply_impulses(circles, &all_impulses);
+    if config.correction_order == CorrectionOrder::Split {
+        let corrections = resolve_position_corrections(&resolved, &circle_collisions, config);
+        resolved = apply_impulses(&resolved, &corrections);
+    }
+    (Cow::Owned(resolved), contacts, stats)
+}
+
+/// Rewind circles that tunneled through another this step to their earliest
+/// time of impact
+///
+/// Returns the rewound circles and the contacts there, or `None` when
+/// nothing tunneled. The bounds each circle swept go through the same
+/// `SpatialGrid` broad phase as the step's contacts, so only pairs whose
+/// bounds share a cell (and that `should_collide` allows) are swept; pairs
+/// whose bounds don't overlap can't have met.
+fn sweep(
+    circles: &[Circle],
+    cell_width: Scalar,
+    cell_height: Scalar,
+    world_width: Scalar,
+    world_height: Scalar,
+    should_collide: Option<&CollisionFilter>,
+) -> Option<(Vec<Circle>, Vec<Collision>)> {
+    use crate::spatial::{SpatialGrid, detect_swept_collisions};
+    
+    let bounds: Vec<Aabb> = circles.iter()
+        .map(|circle| {
+            let path = Aabb::new(circle.old_position, circle.position);
+            let extent = Vec2::from_scalars(circle.radius, circle.radius);
+            Aabb { min: path.min - extent, max: path.max + extent }
+        })
+        .collect();
+    let mut pairs = SpatialGrid::build_aabbs(&bounds, cell_width, cell_height, world_width, world_height)
+        .get_collision_pairs();
+    if let Some(filter) = should_collide {
+        pairs.retain(|&(a, b)| filter.allows(a, b));
+    }
+    // Index order, so contacts come out as they would from every pair
+    pairs.sort_unstable();
+    let hits = detect_swept_collisions(circles, &pairs);
+    if hits.is_empty() {
+        return None;
+    }
+    
+    let mut toi = vec![Scalar::ONE; circles.len()];
+    for hit in &hits {
+        for idx in [hit.collision.idx_a, hit.collision.idx_b] {
+            toi[idx] = toi[idx].min(hit.toi);
+        }
+    }
+    let rewound = circles.iter().zip(toi).map(|(circle, toi)| Circle {
+        position: circle.old_position + (circle.position - circle.old_position) * toi,
+        ..*circle
+    });
+    Some((rewound.collect(), hits.into_iter().map(|hit| hit.collision).collect()))
 }
 
 #[cfg(test)]
@@ -264,11 +760,156 @@ mod tests {
         let new_circles = apply_impulses(&circles, &impulses);
         
         // Velocities should be reversed and scaled by restitution
-        let expected_v = Scalar::from_float(0.8); // restitution * 1.0
+        let _expected_v = Scalar::from_float(0.8); // restitution * 1.0
         assert!(new_circles[0].velocity.x < Scalar::ZERO); // Moving left now
         assert!(new_circles[1].velocity.x > Scalar::ZERO); // Moving right now
     }
     
+    /// Closed-form 1D elastic collision: post-collision velocities of two bodies
+    fn elastic_1d(m1: f32, v1: f32, m2: f32, v2: f32) -> (f32, f32) {
+        let total = m1 + m2;
+        (
+            ((m1 - m2) * v1 + 2.0 * m2 * v2) / total,
+            ((m2 - m1) * v2 + 2.0 * m1 * v1) / total,
+        )
+    }
+    
+    #[test]
+    fn test_elastic_head_on_matches_analytic() {
+        let config = CollisionConfig {
+            restitution: Scalar::ONE,
+            ..CollisionConfig::default()
+        };
+        // A few Q16.16 ulps of slack for the divisions by mass
+        let tolerance = 1e-3;
+        
+        for &(m1, v1, m2, v2) in &[
+            (1.0, 1.0, 1.0, -1.0),
+            (1.0, 3.0, 1.0, 0.0),
+            (2.0, 1.5, 0.5, -2.0),
+            (0.3, 4.0, 3.0, -0.5),
+        ] {
+            let mut circles = vec![
+                Circle::new(Vec2::new(10.0, 10.0), Scalar::ONE, Scalar::from_float(m1)),
+                Circle::new(Vec2::new(12.0, 10.0), Scalar::ONE, Scalar::from_float(m2)),
+            ];
+            circles[0].velocity = Vec2::new(v1, 0.0);
+            circles[1].velocity = Vec2::new(v2, 0.0);
+            
+            let collision = Collision {
+                idx_a: 0,
+                idx_b: 1,
+                normal: Vec2::new(1.0, 0.0),
+                depth: Scalar::ZERO,
+                contact: Vec2::new(11.0, 10.0),
+            };
+            
+            let impulses = resolve_collisions(&circles, &[collision], &config);
+            let after = apply_impulses(&circles, &impulses);
+            let (expected_a, expected_b) = elastic_1d(m1, v1, m2, v2);
+            
+            let error_a = (after[0].velocity.x.to_float() - expected_a).abs();
+            let error_b = (after[1].velocity.x.to_float() - expected_b).abs();
+            assert!(
+                error_a < tolerance && error_b < tolerance,
+                "masses ({}, {}), velocities ({}, {}): error ({}, {})",
+                m1, m2, v1, v2, error_a, error_b
+            );
+            
+            // Equal masses simply exchange velocities
+            if m1 == m2 {
+                assert_eq!(after[0].velocity.x, Scalar::from_float(v2));
+                assert_eq!(after[1].velocity.x, Scalar::from_float(v1));
+            }
+            assert_eq!(after[0].velocity.y, Scalar::ZERO);
+        }
+    }
+    
+    #[test]
+    fn test_harder_collision_reports_larger_impulse() {
+        let impulse_at = |speed: f32| {
+            let mut circles = vec![
+                Circle::new(Vec2::new(10.0, 10.0), Scalar::ONE, Scalar::ONE),
+                Circle::new(Vec2::new(12.0, 10.0), Scalar::ONE, Scalar::ONE),
+            ];
+            circles[0].velocity = Vec2::new(speed, 0.0);
+            circles[1].velocity = Vec2::new(-speed, 0.0);
+            
+            let collision = Collision {
+                idx_a: 0,
+                idx_b: 1,
+                normal: Vec2::new(1.0, 0.0),
+                depth: Scalar::ZERO,
+                contact: Vec2::new(11.0, 10.0),
+            };
+            
+            let (_, events) = resolve_collisions_with_events(
+                &circles,
+                &[collision],
+                &CollisionConfig::default(),
+            );
+            assert_eq!(events.len(), 1);
+            assert_eq!(events[0].relative_speed, Scalar::from_float(2.0 * speed));
+            events[0].impulse
+        };
+        
+        let gentle = impulse_at(0.5);
+        let hard = impulse_at(5.0);
+        
+        assert!(gentle > Scalar::ZERO);
+        assert!(hard > gentle);
+    }
+    
+    #[test]
+    fn test_restitution_rule_min_vs_max() {
+        // A bouncy ball hitting a dull one head-on
+        let mut circles = vec![
+            Circle::new(Vec2::new(10.0, 10.0), Scalar::ONE, Scalar::ONE),
+            Circle::new(Vec2::new(12.0, 10.0), Scalar::ONE, Scalar::ONE),
+        ];
+        circles[0].velocity = Vec2::new(1.0, 0.0);
+        circles[0].restitution = Scalar::from_float(0.9);
+        circles[1].velocity = Vec2::new(-1.0, 0.0);
+        circles[1].restitution = Scalar::from_float(0.3);
+        
+        let collision = Collision {
+            idx_a: 0,
+            idx_b: 1,
+            normal: Vec2::new(1.0, 0.0),
+            depth: Scalar::ZERO,
+            contact: Vec2::new(11.0, 10.0),
+        };
+        
+        let speed_after = |rule| {
+            let config = CollisionConfig {
+                restitution_rule: Some(rule),
+                ..CollisionConfig::default()
+            };
+            let impulses = resolve_collisions(&circles, std::slice::from_ref(&collision), &config);
+            apply_impulses(&circles, &impulses)[1].velocity.x
+        };
+        
+        let min_speed = speed_after(RestitutionRule::Min);
+        let max_speed = speed_after(RestitutionRule::Max);
+        
+        // Equal masses: each ball rebounds at e * 1.0
+        assert_eq!(min_speed, Scalar::from_float(0.3));
+        assert_eq!(max_speed, Scalar::from_float(0.9));
+        assert!(min_speed < max_speed);
+    }
+    
+    #[test]
+    fn test_presets() {
+        for name in CollisionConfig::preset_names() {
+            assert!(CollisionConfig::preset(name).is_some(), "missing preset {}", name);
+        }
+        assert!(CollisionConfig::preset("unknown").is_none());
+        
+        let bouncy = CollisionConfig::preset("bouncy").unwrap();
+        let sticky = CollisionConfig::preset("sticky").unwrap();
+        assert!(bouncy.restitution > sticky.restitution);
+    }
+    
     #[test]
     fn test_boundary_bounce() {
         // Circle hitting bottom boundary
@@ -302,4 +943,154 @@ mod tests {
         // Position should be corrected
         assert!(new_circles[0].position.y > circles[0].position.y);
     }
-}
\ No newline at end of file
+    
+    #[test]
+    fn test_corner_contacts_correct_each_axis_once() {
+        let dt = Scalar::from_float(1.0 / 60.0);
+        let mut circle = Circle::new(Vec2::new(0.75, 0.5), Scalar::ONE, Scalar::ONE);
+        circle.set_velocity(Vec2::new(-3.0, -2.0), dt);
+        let circles = vec![circle];
+        
+        let width = Scalar::from_float(20.0);
+        let contacts = crate::spatial::detect_boundary_collisions(&circles, width, width);
+        let walls: Vec<Boundary> = contacts.iter().map(|c| c.boundary).collect();
+        assert_eq!(walls, vec![Boundary::Left, Boundary::Bottom]);
+        
+        let config = CollisionConfig::default();
+        let impulses = resolve_boundary_collisions(&circles, &contacts, &config);
+        let total_pos = impulses.iter().fold(Vec2::ZERO, |sum, i| sum + i.delta_pos);
+        let total_v = impulses.iter().fold(Vec2::ZERO, |sum, i| sum + i.delta_v);
+        
+        // Each axis is pushed out by its own depth only, as for a single wall
+        let one_plus_e = Scalar::ONE + config.restitution;
+        assert_eq!(total_pos, Vec2::new(0.25, 0.5) * one_plus_e);
+        assert_eq!(total_v, Vec2::new(3.0, 2.0) * one_plus_e);
+        
+        // With friction, each wall's friction opposes the velocity after
+        // the other wall's bounce, slowing the rebound rather than feeding it
+        let config = CollisionConfig { friction: Scalar::from_float(0.2), ..config };
+        let impulses = resolve_boundary_collisions(&circles, &contacts, &config);
+        let bounced = apply_impulses(&circles, &impulses)[0].velocity;
+        let rebound = Vec2::new(3.0, 2.0) * config.restitution;
+        let drag = Vec2::new(2.0, 3.0) * (config.friction * one_plus_e);
+        let expected = rebound - drag;
+        let tolerance = Scalar::from_float(0.001);
+        assert!((bounced.x - expected.x).abs() <= tolerance && (bounced.y - expected.y).abs() <= tolerance,
+            "{:?} != {:?}", bounced, expected);
+    }
+    
+    #[test]
+    fn test_split_correction_separates_contact_chain() {
+        // A and B at rest and overlapping; C overlaps B but is already
+        // drifting away, so the combined order never corrects B-C
+        let chain = || {
+            let mut circles: Vec<Circle> = [10.0, 11.8, 13.6].iter()
+                .map(|&x| Circle::new(Vec2::new(x, 10.0), Scalar::ONE, Scalar::ONE))
+                .collect();
+            circles[2].velocity = Vec2::new(0.5, 0.0);
+            circles
+        };
+        let max_overlap = |circles: &[Circle]| {
+            circles.windows(2)
+                .map(|pair| (pair[0].radius + pair[1].radius) - (pair[1].position - pair[0].position).magnitude())
+                .max()
+                .unwrap()
+        };
+        let resolve = |order| {
+            let config = CollisionConfig {
+                correction_order: order,
+                ..CollisionConfig::default()
+            };
+            resolve_all_collisions(&chain(), &[], Scalar::from_float(100.0), Scalar::from_float(100.0), &config)
+        };
+        
+        assert_eq!(CollisionConfig::default().correction_order, CorrectionOrder::Combined);
+        let combined = resolve(CorrectionOrder::Combined);
+        let split = resolve(CorrectionOrder::Split);
+        
+        // Velocities are resolved identically either way
+        for (a, b) in combined.iter().zip(&split) {
+            assert_eq!(a.velocity, b.velocity);
+        }
+        // Combined pushes B further into C (0.2 -> 0.24); split shrinks it
+        assert!(max_overlap(&combined) > Scalar::from_float(0.23), "{:?}", max_overlap(&combined));
+        assert!(max_overlap(&split) < Scalar::from_float(0.17), "{:?}", max_overlap(&split));
+    }
+    
+    #[test]
+    fn test_max_impulse_clamps_contact_impulse() {
+        let mut circles = vec![
+            Circle::new(Vec2::new(10.0, 10.0), Scalar::ONE, Scalar::ONE),
+            Circle::new(Vec2::new(11.5, 10.0), Scalar::ONE, Scalar::ONE),
+        ];
+        circles[0].velocity = Vec2::new(20.0, 0.0);
+        circles[1].velocity = Vec2::new(-20.0, 0.0);
+        let collisions = crate::spatial::detect_collisions(&circles, &[(0, 1)]);
+        
+        let config = CollisionConfig {
+            max_impulse: Some(Scalar::TWO),
+            ..CollisionConfig::default()
+        };
+        let (impulses, events) = resolve_collisions_with_events(&circles, &collisions, &config);
+        assert_eq!(events[0].impulse, Scalar::TWO);
+        // Unit masses: each side's velocity changes by exactly the clamp
+        assert_eq!(impulses[0].delta_v.magnitude(), Scalar::TWO);
+        assert_eq!(impulses[1].delta_v.magnitude(), Scalar::TWO);
+    }
+    
+    #[test]
+    fn test_friction_impulse_is_coulomb_clamped() {
+        // B closes on A along x while sliding past it along y
+        let mut circles = vec![
+            Circle::new(Vec2::new(10.0, 10.0), Scalar::ONE, Scalar::ONE),
+            Circle::new(Vec2::new(11.5, 10.0), Scalar::ONE, Scalar::ONE),
+        ];
+        circles[1].velocity = Vec2::new(-1.0, 8.0);
+        let collisions = crate::spatial::detect_collisions(&circles, &[(0, 1)]);
+        let frictionless = resolve_collisions(&circles, &collisions, &CollisionConfig::default());
+        
+        for friction in [0.0, 0.1, 10.0] {
+            let config = CollisionConfig {
+                friction: Scalar::from_float(friction),
+                ..CollisionConfig::default()
+            };
+            let (impulses, events) = resolve_collisions_with_events(&circles, &collisions, &config);
+            let normal_impulse = events[0].impulse;
+            let tangential = impulses[1].delta_v.y.abs();
+            
+            // The normal response is unchanged by friction
+            assert_eq!(impulses[1].delta_v.x, frictionless[1].delta_v.x);
+            assert!(tangential <= config.friction * normal_impulse, "friction {}", friction);
+            if friction == 0.1 {
+                assert!(tangential > Scalar::ZERO);
+            }
+            if friction == 10.0 {
+                // Unclamped: the sliding stops, shared equally by equal masses
+                assert_eq!(impulses[1].delta_v.y, Scalar::from_float(-4.0));
+                assert_eq!(impulses[0].delta_v.y, Scalar::from_float(4.0));
+            }
+        }
+    }
+    
+    #[test]
+    fn test_oriented_breaks_position_ties_by_state() {
+        let at = Vec2::new(5.0, 5.0);
+        let mut slow = Circle::new(at, Scalar::ONE, Scalar::ONE);
+        slow.velocity = Vec2::new(-1.0, 0.0);
+        let mut fast = slow;
+        fast.velocity = Vec2::new(2.0, 0.0);
+        let collision = |idx_a, idx_b| Collision {
+            idx_a,
+            idx_b,
+            normal: Vec2::new(1.0, 0.0),
+            depth: Scalar::ONE,
+            contact: at,
+        };
+        
+        // Same body first whichever index it has
+        let (first, _, normal) = oriented(&collision(0, 1), &[slow, fast]);
+        assert_eq!((first, normal), (0, Vec2::new(1.0, 0.0)));
+        let (first, _, normal) = oriented(&collision(0, 1), &[fast, slow]);
+        assert_eq!((first, normal), (1, Vec2::new(-1.0, 0.0)));
+    }
+}
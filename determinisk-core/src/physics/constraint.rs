@@ -0,0 +1,80 @@
+//! Positional constraints between circles
+
+use crate::math::Scalar;
+use crate::physics::Circle;
+use serde::{Serialize, Deserialize};
+
+/// A constraint `World::step` enforces after resolving collisions
+///
+/// Solved as a position correction (position-based dynamics), so the
+/// Verlet history turns the correction into velocity and everything stays
+/// in fixed point. Circles are referred to by index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Constraint {
+    /// Keep circles `a` and `b` `rest_length` apart (center to center)
+    ///
+    /// Each step closes `stiffness` of the gap to the rest length, split by
+    /// inverse mass: 1 is a rigid rod, smaller values a softer spring.
+    DistanceConstraint {
+        a: usize,
+        b: usize,
+        rest_length: Scalar,
+        stiffness: Scalar,
+    },
+}
+
+impl Constraint {
+    /// Fix up indices after circle `removed` is taken out of the world
+    ///
+    /// Returns `false` if the constraint involved that circle and should be
+    /// dropped.
+    pub(crate) fn reindex_after_removal(&mut self, removed: usize) -> bool {
+        match self {
+            Constraint::DistanceConstraint { a, b, .. } => {
+                if *a == removed || *b == removed {
+                    return false;
+                }
+                for index in [a, b] {
+                    if *index > removed {
+                        *index -= 1;
+                    }
+                }
+                true
+            }
+        }
+    }
+}
+
+/// Apply each constraint once, in order
+///
+/// Constraints naming a missing circle, or the same circle twice, are
+/// skipped, as are pairs of static circles and pairs at exactly the same
+/// position (no direction to correct along).
+pub(crate) fn solve_constraints(circles: &mut [Circle], constraints: &[Constraint]) {
+    for constraint in constraints {
+        match *constraint {
+            Constraint::DistanceConstraint { a, b, rest_length, stiffness } => {
+                if a == b || a >= circles.len() || b >= circles.len() {
+                    continue;
+                }
+                let delta = circles[b].position - circles[a].position;
+                let distance = delta.magnitude();
+                if distance == Scalar::ZERO {
+                    continue;
+                }
+
+                // Move each end along the axis, the lighter one further
+                // (a static end not at all)
+                let inv_mass_a = circles[a].inv_mass();
+                let inv_mass_b = circles[b].inv_mass();
+                let inv_mass_sum = inv_mass_a + inv_mass_b;
+                if inv_mass_sum == Scalar::ZERO {
+                    continue;
+                }
+                let correction = delta / distance * ((distance - rest_length) * stiffness);
+                circles[a].position += correction * (inv_mass_a / inv_mass_sum);
+                circles[b].position = circles[b].position - correction * (inv_mass_b / inv_mass_sum);
+            }
+        }
+    }
+}
@@ -0,0 +1,130 @@
+//! Stable circle handles that survive removals
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::vec::Vec;
+
+use serde::{Serialize, Deserialize};
+
+/// Stable reference to a circle in a `World`, returned by `add_circle`
+///
+/// Unlike an index it keeps pointing at the same circle when others are
+/// removed. Packs a slot (low 32 bits) and that slot's generation (high 32
+/// bits), so a handle to a removed circle never resolves to the circle
+/// that later reuses its slot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct CircleHandle(pub u64);
+
+impl CircleHandle {
+    fn new(slot: u32, generation: u32) -> Self {
+        CircleHandle((u64::from(generation) << 32) | u64::from(slot))
+    }
+    
+    fn slot(self) -> usize {
+        self.0 as u32 as usize
+    }
+    
+    fn generation(self) -> u32 {
+        (self.0 >> 32) as u32
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+struct Slot {
+    /// Bumped each time the slot is freed
+    generation: u32,
+    /// Index into `World::circles` while occupied
+    index: Option<usize>,
+}
+
+/// Generational slot map from handles to indices in `World::circles`
+///
+/// The circles themselves stay in a dense `Vec` in insertion order, which
+/// the step pipeline and `capture_state` iterate; this only tracks where
+/// each handle's circle currently sits.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub(crate) struct CircleSlots {
+    slots: Vec<Slot>,
+    /// Freed slots, reused most recent first
+    free: Vec<u32>,
+    /// Handle of each circle, parallel to `World::circles`
+    handles: Vec<CircleHandle>,
+}
+
+impl CircleSlots {
+    /// Issue a handle for a circle about to be pushed at index `count`
+    ///
+    /// Circles pushed straight onto `World::circles` are given handles first,
+    /// so indices stay in step.
+    pub(crate) fn insert(&mut self, count: usize) -> CircleHandle {
+        self.sync(count);
+        let index = self.handles.len();
+        let handle = match self.free.pop() {
+            Some(slot) => {
+                let entry = &mut self.slots[slot as usize];
+                entry.index = Some(index);
+                CircleHandle::new(slot, entry.generation)
+            }
+            None => {
+                let slot = self.slots.len() as u32;
+                self.slots.push(Slot { generation: 0, index: Some(index) });
+                CircleHandle::new(slot, 0)
+            }
+        };
+        self.handles.push(handle);
+        handle
+    }
+    
+    /// Index of `handle`'s circle, if it hasn't been removed
+    pub(crate) fn index(&self, handle: CircleHandle) -> Option<usize> {
+        self.slots.get(handle.slot())
+            .filter(|slot| slot.generation == handle.generation())
+            .and_then(|slot| slot.index)
+    }
+    
+    /// Forget `handle`, returning the index its circle must be removed from
+    ///
+    /// Later circles shift down one index, matching `Vec::remove`.
+    pub(crate) fn remove(&mut self, handle: CircleHandle, count: usize) -> Option<usize> {
+        self.sync(count);
+        let index = self.index(handle)?;
+        self.handles.remove(index);
+        self.free_slot(handle);
+        for later in &self.handles[index..] {
+            if let Some(moved) = &mut self.slots[later.slot()].index {
+                *moved -= 1;
+            }
+        }
+        Some(index)
+    }
+    
+    /// Match `handles` to a `circles` vector of length `count` edited directly
+    fn sync(&mut self, count: usize) {
+        while self.handles.len() > count {
+            let handle = self.handles.pop().expect("len > count >= 0");
+            self.free_slot(handle);
+        }
+        while self.handles.len() < count {
+            let count = self.handles.len();
+            self.insert(count);
+        }
+    }
+    
+    fn free_slot(&mut self, handle: CircleHandle) {
+        let slot = &mut self.slots[handle.slot()];
+        slot.index = None;
+        slot.generation = slot.generation.wrapping_add(1);
+        self.free.push(handle.slot() as u32);
+    }
+    
+    /// Follow a reordering of the circles: new index `i` holds old `order[i]`
+    #[cfg(test)]
+    pub(crate) fn reorder(&mut self, order: &[usize]) {
+        self.sync(order.len());
+        self.handles = order.iter().map(|&old| self.handles[old]).collect();
+        for (index, handle) in self.handles.iter().enumerate() {
+            self.slots[handle.slot()].index = Some(index);
+        }
+    }
+}
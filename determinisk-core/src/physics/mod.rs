@@ -1,9 +1,19 @@
 //! Physics simulation components
 
 mod circle;
+mod constraint;
+mod handle;
+mod raycast;
+mod rect;
 mod world;
+mod schedule;
 pub mod collision;
 
-pub use circle::Circle;
-pub use world::World;
-pub use collision::{CollisionConfig, resolve_all_collisions};
\ No newline at end of file
+pub use circle::{Circle, MAX_SAFE_SPEED, SLEEP_SPEED};
+pub use constraint::Constraint;
+pub use handle::CircleHandle;
+pub use raycast::{RayHit, raycast};
+pub use rect::Rect;
+pub use world::{World, WorldCheckpoint, CollisionFilter, GravityZone, Integrator, MAX_CIRCLES, TooManyCircles};
+pub use schedule::{Schedule, ParamChange};
+pub use collision::{BroadPhaseStats, CollisionConfig, CorrectionOrder, CollisionEvent, RestitutionRule, StepContacts, resolve_all_collisions};
\ No newline at end of file
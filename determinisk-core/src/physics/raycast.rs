@@ -0,0 +1,148 @@
+//! Ray queries against circles, for picking and line-of-sight checks
+
+use crate::math::{Scalar, Vec2};
+use crate::physics::Circle;
+
+/// Where a ray first hits a circle
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RayHit {
+    /// Index of the circle hit
+    pub idx: usize,
+    /// Point on the circle's surface where the ray enters
+    pub point: Vec2,
+    /// Distance from the ray origin to `point`
+    pub distance: Scalar,
+    /// Outward surface normal at `point`
+    pub normal: Vec2,
+}
+
+/// Nearest circle hit by the ray from `origin` along `dir`, within `max_dist`
+///
+/// Solves the ray-circle intersection analytically in fixed point. `dir` need
+/// not be normalized; a zero `dir` hits nothing. A ray that only grazes a
+/// circle counts as a hit. Circles containing `origin` are skipped, so a beam
+/// cast from inside a body (e.g. its own emitter) sees past it. Equally near
+/// hits go to the lowest index.
+pub fn raycast(circles: &[Circle], origin: Vec2, dir: Vec2, max_dist: Scalar) -> Option<RayHit> {
+    if dir == Vec2::ZERO {
+        return None;
+    }
+    let dir = dir.normalized();
+    
+    let mut nearest: Option<RayHit> = None;
+    for (idx, circle) in circles.iter().enumerate() {
+        let to_center = circle.position - origin;
+        let radius = circle.radius;
+        
+        // Closest approach along the ray. Distances across a large world
+        // overflow Q16.16 when squared (past ~181), so circles out of range
+        // are rejected first, and only quantities bounded by the radius are
+        // squared below.
+        let along = to_center.dot(&dir);
+        if along < Scalar::ZERO || along - radius > max_dist {
+            continue;
+        }
+        let miss = to_center - dir * along;
+        if miss.x.abs() > radius || miss.y.abs() > radius {
+            continue;
+        }
+        let radius_sq = radius * radius;
+        let miss_sq = miss.length_squared();
+        if miss_sq > radius_sq {
+            continue;
+        }
+        
+        // Origin inside the circle
+        if along < radius && along * along + miss_sq < radius_sq {
+            continue;
+        }
+        
+        let distance = along - (radius_sq - miss_sq).sqrt();
+        if distance > max_dist || nearest.is_some_and(|hit| hit.distance <= distance) {
+            continue;
+        }
+        let point = origin + dir * distance;
+        nearest = Some(RayHit {
+            idx,
+            point,
+            distance,
+            normal: (point - circle.position) / circle.radius,
+        });
+    }
+    
+    nearest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::World;
+    
+    fn circles() -> Vec<Circle> {
+        vec![
+            Circle::new(Vec2::new(10.0, 5.0), Scalar::ONE, Scalar::ONE),
+            Circle::new(Vec2::new(6.0, 5.0), Scalar::HALF, Scalar::ONE),
+            Circle::new(Vec2::new(6.0, 9.0), Scalar::ONE, Scalar::ONE),
+        ]
+    }
+    
+    #[test]
+    fn test_raycast_hits_nearest_circle() {
+        let hit = raycast(&circles(), Vec2::new(1.0, 5.0), Vec2::new(2.0, 0.0), Scalar::from_float(20.0)).unwrap();
+        
+        assert_eq!(hit.idx, 1);
+        assert_eq!(hit.distance, Scalar::from_float(4.5));
+        assert_eq!(hit.point, Vec2::new(5.5, 5.0));
+        assert_eq!(hit.normal, Vec2::new(-1.0, 0.0));
+        
+        // Out of range
+        assert!(raycast(&circles(), Vec2::new(1.0, 5.0), Vec2::UNIT_X, Scalar::from_float(4.0)).is_none());
+    }
+    
+    #[test]
+    fn test_raycast_grazing_ray_hits() {
+        // Just touches the top of circle 2
+        let hit = raycast(&circles(), Vec2::new(0.0, 10.0), Vec2::UNIT_X, Scalar::from_float(20.0)).unwrap();
+        
+        assert_eq!(hit.idx, 2);
+        assert_eq!(hit.point, Vec2::new(6.0, 10.0));
+        assert_eq!(hit.normal, Vec2::UNIT_Y);
+    }
+    
+    #[test]
+    fn test_raycast_miss() {
+        let circles = circles();
+        // Passes between the circles, then points away from them
+        assert!(raycast(&circles, Vec2::new(0.0, 7.0), Vec2::UNIT_X, Scalar::from_float(20.0)).is_none());
+        assert!(raycast(&circles, Vec2::new(1.0, 5.0), -Vec2::UNIT_X, Scalar::from_float(20.0)).is_none());
+        assert!(raycast(&circles, Vec2::new(1.0, 5.0), Vec2::ZERO, Scalar::from_float(20.0)).is_none());
+    }
+    
+    #[test]
+    fn test_raycast_skips_circle_containing_origin() {
+        // From inside circle 1, the ray sees circle 0 beyond it
+        let hit = raycast(&circles(), Vec2::new(6.0, 5.0), Vec2::UNIT_X, Scalar::from_float(20.0)).unwrap();
+        
+        assert_eq!(hit.idx, 0);
+        assert_eq!(hit.distance, Scalar::from_float(3.0));
+    }
+    
+    #[test]
+    fn test_raycast_across_large_world() {
+        // Distances here square past the Q16.16 range
+        let mut world = World::new(300.0, 150.0);
+        world.add_circle(Circle::new(Vec2::new(260.0, 75.0), Scalar::ONE, Scalar::ONE));
+        world.add_circle(Circle::new(Vec2::new(20.0, 5.0), Scalar::ONE, Scalar::ONE));
+        world.add_circle(Circle::new(Vec2::new(280.0, 140.0), Scalar::ONE, Scalar::ONE));
+        let origin = Vec2::new(20.0, 75.0);
+        
+        let hit = world.raycast(origin, Vec2::UNIT_X, Scalar::from_float(300.0)).unwrap();
+        assert_eq!(hit.idx, 0);
+        assert_eq!(hit.distance, Scalar::from_float(239.0));
+        assert_eq!(hit.point, Vec2::new(259.0, 75.0));
+        
+        // Out of range, and a ray past every circle
+        assert!(world.raycast(origin, Vec2::UNIT_X, Scalar::from_float(100.0)).is_none());
+        assert!(world.raycast(origin, Vec2::UNIT_Y, Scalar::from_float(300.0)).is_none());
+    }
+}
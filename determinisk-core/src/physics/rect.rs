@@ -0,0 +1,20 @@
+//! Static axis-aligned rectangle obstacles (walls, platforms)
+
+use crate::math::{Aabb, Vec2};
+use serde::{Serialize, Deserialize};
+
+/// An immovable axis-aligned box that circles collide with
+///
+/// Behaves like the world boundary: infinitely massive, bounced off with the
+/// config's restitution and friction. See `World::add_rect`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Rect {
+    pub bounds: Aabb,
+}
+
+impl Rect {
+    /// Rectangle between two opposite corners, in any order
+    pub fn new(a: Vec2, b: Vec2) -> Self {
+        Rect { bounds: Aabb::new(a, b) }
+    }
+}
@@ -0,0 +1,60 @@
+//! Parameter changes keyed by step index
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::vec::Vec;
+
+use crate::math::{Scalar, Vec2};
+use serde::{Serialize, Deserialize};
+
+/// A world parameter change applied by a `Schedule`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ParamChange {
+    /// New gravity, in the engine's y-up frame
+    Gravity(Vec2),
+    /// New global restitution
+    Restitution(Scalar),
+}
+
+/// Parameter changes applied at the start of given steps
+///
+/// Keyed by step index rather than time, so a scheduled run is exactly as
+/// deterministic as an unscheduled one. Events for the same step apply in
+/// insertion order.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Schedule {
+    events: Vec<(u32, ParamChange)>,
+}
+
+impl Schedule {
+    /// Empty schedule
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Add `change` before step `step` (0 = the first step), builder style
+    pub fn at(mut self, step: u32, change: ParamChange) -> Self {
+        self.push(step, change);
+        self
+    }
+    
+    /// Add `change` before step `step`, keeping events sorted by step
+    pub fn push(&mut self, step: u32, change: ParamChange) {
+        let index = self.events.partition_point(|&(s, _)| s <= step);
+        self.events.insert(index, (step, change));
+    }
+    
+    /// Changes due before step `step`, in application order
+    pub fn changes_at(&self, step: u32) -> impl Iterator<Item = &ParamChange> + '_ {
+        let start = self.events.partition_point(|&(s, _)| s < step);
+        self.events[start..].iter()
+            .take_while(move |&&(s, _)| s == step)
+            .map(|(_, change)| change)
+    }
+    
+    /// Whether no changes are scheduled
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+}
@@ -1,15 +1,101 @@
 //! Physics world container and simulation
 
 #[cfg(not(feature = "std"))]
-use alloc::vec::Vec;
+use alloc::{borrow::Cow, sync::Arc, vec::Vec};
 #[cfg(feature = "std")]
-use std::vec::Vec;
+use std::{borrow::Cow, sync::Arc, vec::Vec};
 
-use crate::math::{Scalar, Vec2};
-use crate::physics::{Circle, CollisionConfig};
+use crate::math::{Aabb, Scalar, Vec2};
+use crate::physics::constraint::{solve_constraints, Constraint};
+use crate::physics::handle::{CircleHandle, CircleSlots};
+use crate::physics::{BroadPhaseStats, Circle, CollisionEvent, Rect, RayHit, MAX_SAFE_SPEED, CollisionConfig, ParamChange, Schedule, StepContacts};
+use crate::spatial::{GridCells, InvalidGridCells};
 use crate::state::SimulationInput;
 use serde::{Serialize, Deserialize};
 
+/// Upper bound on circles per world, so guest memory use is predictable
+pub const MAX_CIRCLES: usize = 256;
+
+/// An input was rejected for having more than `MAX_CIRCLES` circles
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TooManyCircles {
+    pub count: usize,
+}
+
+impl core::fmt::Display for TooManyCircles {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "input has {} circles, at most {} are supported", self.count, MAX_CIRCLES)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for TooManyCircles {}
+
+/// How `World::integrate` advances positions and velocities
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Integrator {
+    /// Position Verlet: velocity is implied by `old_position` and derived
+    /// after each step, half a step behind the position (the historical
+    /// integrator)
+    #[default]
+    Verlet,
+    /// Velocity Verlet: explicit `velocity` kept in sync with `position`,
+    /// exact for constant acceleration. `old_position` is still updated, as
+    /// the previous position, for code that reads the history.
+    VelocityVerlet,
+}
+
+/// A region with its own gravity
+///
+/// Zones use the engine's y-up coordinates, like `World::gravity`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GravityZone {
+    pub aabb: Aabb,
+    pub gravity: Vec2,
+}
+
+/// Gravity at `point`: the first zone containing it, else `global`
+fn gravity_at(zones: &[GravityZone], global: Vec2, point: Vec2) -> Vec2 {
+    zones.iter()
+        .find(|zone| zone.aabb.contains(point))
+        .map_or(global, |zone| zone.gravity)
+}
+
+/// Dynamic predicate deciding whether two circles (by index) may collide
+///
+/// Cloning a world shares the filter. The predicate must be `Send + Sync`,
+/// so worlds can still move between threads. Not serialized; a
+/// deserialized world has no filter.
+#[derive(Clone)]
+pub struct CollisionFilter(Arc<dyn Fn(usize, usize) -> bool + Send + Sync>);
+
+impl CollisionFilter {
+    /// Wrap a predicate; it is called with the lower index first
+    pub fn new(filter: impl Fn(usize, usize) -> bool + Send + Sync + 'static) -> Self {
+        CollisionFilter(Arc::new(filter))
+    }
+    
+    /// Whether circles `a` and `b` should collide
+    pub fn allows(&self, a: usize, b: usize) -> bool {
+        (self.0)(a, b)
+    }
+}
+
+impl core::fmt::Debug for CollisionFilter {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("CollisionFilter(..)")
+    }
+}
+
+/// Everything needed to rewind a `World`, see `World::checkpoint`
+///
+/// Serializable, so a checkpoint can be saved and restored in another
+/// process; the per-step contact records only survive in memory.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorldCheckpoint {
+    world: World,
+}
+
 /// The physics world containing all entities
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct World {
@@ -17,8 +103,53 @@ pub struct World {
     pub gravity: Vec2,
     pub timestep: Scalar,
     pub circles: Vec<Circle>,
-    #[serde(skip)]
+    /// Static rectangle obstacles, see `add_rect`
+    #[serde(default)]
+    pub rects: Vec<Rect>,
+    /// Handles for `circles`, see `add_circle`
+    #[serde(default)]
+    circle_slots: CircleSlots,
+    #[serde(default)]
     pub collision_config: CollisionConfig,
+    /// Report recorded states in y-down screen coordinates
+    ///
+    /// The world always simulates y-up; this only affects conversion at the
+    /// input/output boundary (see `SimulationInput::y_down`).
+    #[serde(default)]
+    pub y_down: bool,
+    /// Regions overriding `gravity` for circles whose center is inside;
+    /// the first matching zone wins
+    #[serde(default)]
+    pub gravity_zones: Vec<GravityZone>,
+    /// Parameter changes applied at the start of matching steps
+    #[serde(default)]
+    pub schedule: Schedule,
+    /// Number of `step` calls so far; the index `schedule` is keyed on
+    #[serde(default)]
+    pub steps_taken: u32,
+    /// Integration scheme used by `integrate`
+    #[serde(default)]
+    pub integrator: Integrator,
+    /// Constraints solved after collisions each step, in order
+    #[serde(default)]
+    pub constraints: Vec<Constraint>,
+    /// Optional pair filter consulted after broad phase; `None` collides everything
+    #[serde(skip)]
+    pub should_collide: Option<CollisionFilter>,
+    /// Accumulate every step's collision events for `take_events`
+    ///
+    /// Off by default, so headless and zkVM runs don't keep them around.
+    #[serde(default)]
+    pub record_events: bool,
+    /// Events recorded since the last `take_events`
+    #[serde(skip)]
+    events: Vec<CollisionEvent>,
+    /// Contacts resolved by the most recent `step`, reused for metrics
+    #[serde(skip)]
+    last_contacts: StepContacts,
+    /// Broad-phase statistics from the most recent `step`
+    #[serde(skip)]
+    last_broad_phase: BroadPhaseStats,
 }
 
 impl World {
@@ -29,70 +160,622 @@ impl World {
             gravity: Vec2::new(0.0, -9.81),
             timestep: Scalar::from_float(1.0 / 60.0),
             circles: Vec::new(),
+            rects: Vec::new(),
+            circle_slots: CircleSlots::default(),
             collision_config: CollisionConfig::default(),
+            y_down: false,
+            gravity_zones: Vec::new(),
+            schedule: Schedule::new(),
+            steps_taken: 0,
+            integrator: Integrator::default(),
+            constraints: Vec::new(),
+            should_collide: None,
+            record_events: false,
+            events: Vec::new(),
+            last_contacts: StepContacts::default(),
+            last_broad_phase: BroadPhaseStats::default(),
+        }
+    }
+    
+    /// Create a new world with an explicit collision config
+    pub fn with_config(width: f32, height: f32, config: CollisionConfig) -> Self {
+        let mut world = World::new(width, height);
+        world.collision_config = config;
+        world
+    }
+    
+    /// Replace the collision config used by subsequent steps
+    ///
+    /// Takes effect on the next `step`; positions and velocities are kept.
+    /// Like the other live setters below, changing physics mid-run is still
+    /// deterministic for a given schedule of changes, but the result is no
+    /// longer comparable bit-for-bit with a run that used a constant config.
+    pub fn set_collision_config(&mut self, config: CollisionConfig) {
+        self.collision_config = config;
+    }
+    
+    /// Only let pairs for which `filter(a, b)` is true collide, from the next step on
+    ///
+    /// Boundary collisions are unaffected. The filter must itself be
+    /// deterministic (e.g. keyed on indices and world state) to keep runs
+    /// reproducible.
+    pub fn set_collision_filter(&mut self, filter: impl Fn(usize, usize) -> bool + Send + Sync + 'static) {
+        self.should_collide = Some(CollisionFilter::new(filter));
+    }
+    
+    /// Set the global restitution used from the next step on
+    pub fn set_restitution(&mut self, restitution: f32) {
+        self.collision_config.restitution = Scalar::from_float(restitution);
+    }
+    
+    /// Use `cells` for the broad-phase grid from the next step on
+    ///
+    /// Every shape finds the same pairs, but may resolve them in a different
+    /// order, so runs are only bit-comparable with the same cells. Rejects
+    /// cells with a zero or negative side, leaving the current ones.
+    pub fn set_grid_cells(&mut self, cells: GridCells) -> Result<(), InvalidGridCells> {
+        if !cells.is_valid() {
+            return Err(InvalidGridCells { cells });
+        }
+        self.collision_config.grid_cells = cells;
+        Ok(())
+    }
+    
+    /// Set the contact friction coefficient used from the next step on
+    ///
+    /// Writes `CollisionConfig::friction`, which is what the solver applies;
+    /// the per-circle `Circle::friction` is not used by contacts.
+    pub fn set_friction(&mut self, friction: f32) {
+        self.collision_config.friction = Scalar::from_float(friction);
+    }
+    
+    /// Set the gravity vector used from the next step on
+    pub fn set_gravity(&mut self, gravity: Vec2) {
+        self.gravity = gravity;
+    }
+    
+    /// Add a region with its own gravity, checked after earlier zones
+    pub fn add_gravity_zone(&mut self, aabb: Aabb, gravity: Vec2) {
+        self.gravity_zones.push(GravityZone { aabb, gravity });
+    }
+    
+    /// Gravity applied to a circle centered at `point`
+    pub fn gravity_at(&self, point: Vec2) -> Vec2 {
+        gravity_at(&self.gravity_zones, self.gravity, point)
+    }
+    
+    /// Builder-style timestep override
+    ///
+    /// Existing circles keep their velocity (it is re-encoded for the new
+    /// timestep). Check `tunneling_risk` afterwards if the step may be large
+    /// enough for the fastest body to skip past the smallest one.
+    pub fn with_timestep(mut self, timestep: f32) -> Self {
+        self.timestep = Scalar::from_float(timestep);
+        for circle in &mut self.circles {
+            circle.set_velocity(circle.velocity, self.timestep);
         }
+        self
+    }
+    
+    /// Whether the fastest circle moves further than the smallest radius in one step
+    pub fn tunneling_risk(&self) -> bool {
+        let max_speed = self.circles.iter()
+            .map(|c| c.velocity.magnitude())
+            .max()
+            .unwrap_or(Scalar::ZERO);
+        let min_radius = self.circles.iter()
+            .map(|c| c.radius)
+            .min()
+            .unwrap_or(Scalar::ZERO);
+        
+        max_speed * self.timestep > min_radius
+    }
+    
+    /// Create world from declarative input, rejecting more than `MAX_CIRCLES` circles
+    pub fn try_from_input(input: &SimulationInput) -> Result<Self, TooManyCircles> {
+        if input.circles.len() > MAX_CIRCLES {
+            return Err(TooManyCircles { count: input.circles.len() });
+        }
+        Ok(World::from_input(input))
     }
     
     /// Create world from declarative input
+    ///
+    /// Does not enforce `MAX_CIRCLES`; use `try_from_input` for untrusted input.
     pub fn from_input(input: &SimulationInput) -> Self {
         let mut world = World::new(input.world_width, input.world_height);
-        world.gravity = Vec2::new(input.gravity[0], input.gravity[1]);
-        world.timestep = Scalar::from_float(input.timestep);
-        world.collision_config.restitution = Scalar::from_float(input.restitution);
-        world.collision_config.position_correction = Scalar::from_float(input.position_correction);
+        world.reset_with(input);
+        world
+    }
+    
+    /// Rebuild this world from `input` in place, reusing the circle buffer
+    ///
+    /// Bit-identical to `from_input`: everything else (config, schedule,
+    /// zones, filter, step count, contacts) is reset to `World::new`'s
+    /// defaults first. Lets batch runs reuse one world's capacity. Like
+    /// `from_input`, does not enforce `MAX_CIRCLES`.
+    pub fn reset_with(&mut self, input: &SimulationInput) {
+        let mut circles = core::mem::take(&mut self.circles);
+        circles.clear();
+        *self = World::new(input.world_width, input.world_height);
+        self.circles = circles;
+        self.y_down = input.y_down;
+        
+        // Screen coordinates are mirrored into the engine's y-up frame
+        let height = self.bounds.y;
+        let to_engine_position = |p: [f32; 2]| if input.y_down {
+            Vec2::from_scalars(Scalar::from_float(p[0]), height - Scalar::from_float(p[1]))
+        } else {
+            Vec2::new(p[0], p[1])
+        };
+        let to_engine_vector = |v: [f32; 2]| if input.y_down {
+            Vec2::new(v[0], -v[1])
+        } else {
+            Vec2::new(v[0], v[1])
+        };
+        // Mirroring y also flips the sense of rotation
+        let to_engine_spin = |w: f32| if input.y_down {
+            -Scalar::from_float(w)
+        } else {
+            Scalar::from_float(w)
+        };
+        
+        self.gravity = to_engine_vector(input.gravity);
+        self.timestep = Scalar::from_float(input.timestep);
+        self.collision_config.restitution = Scalar::from_float(input.restitution);
+        self.collision_config.position_correction = Scalar::from_float(input.position_correction);
+        if let Some(config) = input.collision_preset.as_deref().and_then(CollisionConfig::preset) {
+            self.collision_config = config;
+        }
         
         for circle_cfg in &input.circles {
-            let mut circle = Circle::new(
-                Vec2::new(circle_cfg.position[0], circle_cfg.position[1]),
+            let mut circle = Circle::with_velocity(
+                to_engine_position(circle_cfg.position),
+                to_engine_vector(circle_cfg.velocity),
                 Scalar::from_float(circle_cfg.radius),
                 Scalar::from_float(circle_cfg.mass),
+                self.timestep,
             );
-            circle.set_velocity(
-                Vec2::new(circle_cfg.velocity[0], circle_cfg.velocity[1]),
-                world.timestep,
-            );
-            world.add_circle(circle);
+            circle.angular_velocity = to_engine_spin(circle_cfg.angular_velocity);
+            self.add_circle(circle);
+        }
+    }
+    
+    /// Panic with the first field whose bits differ from `other`
+    ///
+    /// Compares everything that affects future steps: world parameters,
+    /// the collision config, gravity zones, the schedule, the step count,
+    /// rects, constraints and every circle's full state, bit for bit. Fields
+    /// added to `World`, `CollisionConfig` or `Circle` that change how a
+    /// world steps belong here too.
+    ///
+    /// Not compared: the `should_collide` closure (not comparable), handle
+    /// bookkeeping, and the diagnostics from past steps (`record_events`,
+    /// recorded events, last contacts).
+    pub fn assert_bit_equal(&self, other: &World) {
+        fn check(field: &dyn core::fmt::Display, a: Scalar, b: Scalar) {
+            if a.to_bits() != b.to_bits() {
+                panic!("{} differs: {} ({:#010x}) vs {} ({:#010x})", field, a, a.to_bits(), b, b.to_bits());
+            }
+        }
+        fn check_vec(field: &dyn core::fmt::Display, a: Vec2, b: Vec2) {
+            check(&format_args!("{}.x", field), a.x, b.x);
+            check(&format_args!("{}.y", field), a.y, b.y);
         }
         
-        world
+        check_vec(&"bounds", self.bounds, other.bounds);
+        check_vec(&"gravity", self.gravity, other.gravity);
+        check(&"timestep", self.timestep, other.timestep);
+        if self.y_down != other.y_down {
+            panic!("y_down differs: {} vs {}", self.y_down, other.y_down);
+        }
+        if self.gravity_zones != other.gravity_zones {
+            panic!("gravity_zones differ: {:?} vs {:?}", self.gravity_zones, other.gravity_zones);
+        }
+        if self.schedule != other.schedule {
+            panic!("schedule differs: {:?} vs {:?}", self.schedule, other.schedule);
+        }
+        
+        let (a, b) = (&self.collision_config, &other.collision_config);
+        check(&"collision_config.restitution", a.restitution, b.restitution);
+        check(&"collision_config.position_correction", a.position_correction, b.position_correction);
+        check(&"collision_config.velocity_threshold", a.velocity_threshold, b.velocity_threshold);
+        check(&"collision_config.friction", a.friction, b.friction);
+        if self.integrator != other.integrator {
+            panic!("integrator differs: {:?} vs {:?}", self.integrator, other.integrator);
+        }
+        if a.restitution_rule != b.restitution_rule {
+            panic!("collision_config.restitution_rule differs: {:?} vs {:?}", a.restitution_rule, b.restitution_rule);
+        }
+        if a.continuous != b.continuous {
+            panic!("collision_config.continuous differs: {} vs {}", a.continuous, b.continuous);
+        }
+        if a.max_impulse != b.max_impulse {
+            panic!("collision_config.max_impulse differs: {:?} vs {:?}", a.max_impulse, b.max_impulse);
+        }
+        if a.correction_order != b.correction_order {
+            panic!("collision_config.correction_order differs: {:?} vs {:?}", a.correction_order, b.correction_order);
+        }
+        if a.contact_mode != b.contact_mode {
+            panic!("collision_config.contact_mode differs: {:?} vs {:?}", a.contact_mode, b.contact_mode);
+        }
+        // Cell shape changes the order pairs are resolved in
+        if a.grid_cells != b.grid_cells {
+            panic!("collision_config.grid_cells differs: {:?} vs {:?}", a.grid_cells, b.grid_cells);
+        }
+        if self.rects != other.rects {
+            panic!("rects differ: {:?} vs {:?}", self.rects, other.rects);
+        }
+        if self.constraints != other.constraints {
+            panic!("constraints differ: {:?} vs {:?}", self.constraints, other.constraints);
+        }
+        
+        if self.circles.len() != other.circles.len() {
+            panic!("circle count differs: {} vs {}", self.circles.len(), other.circles.len());
+        }
+        for (i, (a, b)) in self.circles.iter().zip(&other.circles).enumerate() {
+            check_vec(&format_args!("circle {} position", i), a.position, b.position);
+            check_vec(&format_args!("circle {} old_position", i), a.old_position, b.old_position);
+            check_vec(&format_args!("circle {} velocity", i), a.velocity, b.velocity);
+            check(&format_args!("circle {} radius", i), a.radius, b.radius);
+            check(&format_args!("circle {} mass", i), a.mass, b.mass);
+            check(&format_args!("circle {} restitution", i), a.restitution, b.restitution);
+            check(&format_args!("circle {} friction", i), a.friction, b.friction);
+            check(&format_args!("circle {} drag", i), a.drag, b.drag);
+            check(&format_args!("circle {} angle", i), a.angle, b.angle);
+            check(&format_args!("circle {} angular_velocity", i), a.angular_velocity, b.angular_velocity);
+            if a.is_static != b.is_static {
+                panic!("circle {} is_static differs: {} vs {}", i, a.is_static, b.is_static);
+            }
+        }
+        
+        // Last, so diverged runs report the body that moved first
+        if self.steps_taken != other.steps_taken {
+            panic!("steps_taken differs: {} vs {}", self.steps_taken, other.steps_taken);
+        }
     }
     
     /// Add a circle to the world
-    pub fn add_circle(&mut self, circle: Circle) {
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, under `Integrator::Verlet`, if the circle's
+    /// Verlet-encoded velocity disagrees with its cached `velocity` (see
+    /// `Circle::velocity_consistent`; e.g. `position` moved after
+    /// `set_velocity`). Velocity Verlet keeps the two apart by design.
+    pub fn add_circle(&mut self, circle: Circle) -> CircleHandle {
+        debug_assert!(
+            self.integrator != Integrator::Verlet || circle.velocity_consistent(self.timestep),
+            "circle at ({}, {}) has velocity ({}, {}) but its old_position encodes ({}, {}); \
+             call set_velocity after moving it",
+            circle.position.x, circle.position.y,
+            circle.velocity.x, circle.velocity.y,
+            (circle.position.x - circle.old_position.x) / self.timestep,
+            (circle.position.y - circle.old_position.y) / self.timestep,
+        );
+        let handle = self.circle_slots.insert(self.circles.len());
         self.circles.push(circle);
+        handle
+    }
+    
+    /// The circle behind `handle`, unless it has been removed
+    pub fn get_circle(&self, handle: CircleHandle) -> Option<&Circle> {
+        self.circle_slots.index(handle).and_then(|index| self.circles.get(index))
+    }
+    
+    /// Mutable access to the circle behind `handle`
+    pub fn get_circle_mut(&mut self, handle: CircleHandle) -> Option<&mut Circle> {
+        self.circle_slots.index(handle).and_then(|index| self.circles.get_mut(index))
+    }
+    
+    /// Remove the circle behind `handle`, returning it
+    ///
+    /// The remaining circles keep their order, so stepping stays
+    /// deterministic, but later ones move down an index: handles are
+    /// unaffected, while index-keyed state such as a `should_collide` filter
+    /// sees the new indices. Constraints are renumbered to match, and those
+    /// involving the removed circle are dropped. `None` if the circle was
+    /// already removed.
+    pub fn remove_circle(&mut self, handle: CircleHandle) -> Option<Circle> {
+        let index = self.circle_slots.remove(handle, self.circles.len())?;
+        // Recorded contacts refer to the old indices
+        self.last_contacts = StepContacts::default();
+        self.constraints.retain_mut(|constraint| constraint.reindex_after_removal(index));
+        Some(self.circles.remove(index))
+    }
+    
+    /// Add a static rectangle obstacle, e.g. a wall or platform
+    ///
+    /// Circles bounce off it like off the world boundary.
+    pub fn add_rect(&mut self, rect: Rect) {
+        self.rects.push(rect);
+    }
+    
+    /// Add a constraint, solved after collisions from the next step on
+    ///
+    /// Circles are named by index; constraints are solved in the order added.
+    pub fn add_constraint(&mut self, constraint: Constraint) {
+        self.constraints.push(constraint);
     }
     
     /// Perform one physics step with collision detection
+    ///
+    /// Exactly `integrate` followed by `resolve`.
     pub fn step(&mut self) {
-        // Step 1: Apply forces and integrate positions (Verlet)
-        for circle in &mut self.circles {
+        self.integrate();
+        self.resolve();
+    }
+    
+    /// Perform one physics step, running `hook` between `integrate` and
+    /// `resolve`
+    ///
+    /// The hook sees freshly integrated positions and may move them (e.g. to
+    /// project a length constraint). Velocities are then derived from the
+    /// corrected positions, so the correction carries into the next step
+    /// instead of being undone by stale Verlet history.
+    pub fn step_with(&mut self, mut hook: impl FnMut(&mut World)) {
+        self.integrate();
+        hook(self);
+        self.resolve();
+    }
+    
+    /// Pure counterpart of `step`: return the next world, leaving `self` as is
+    ///
+    /// Bit-identical to cloning and then stepping, which is exactly what it
+    /// does; handy for pure pipelines and undo stacks.
+    pub fn stepped(&self) -> World {
+        let mut next = self.clone();
+        next.step();
+        next
+    }
+    
+    /// First half of `step`: apply any scheduled changes, then forces, and
+    /// integrate positions with `integrator`
+    ///
+    /// Leaves bodies possibly overlapping and `velocity` stale until
+    /// `resolve` runs; call the two in order, once each per step, to keep
+    /// `steps_taken` and the schedule in sync.
+    pub fn integrate(&mut self) {
+        for change in self.schedule.changes_at(self.steps_taken) {
+            match *change {
+                ParamChange::Gravity(gravity) => self.gravity = gravity,
+                ParamChange::Restitution(restitution) => self.collision_config.restitution = restitution,
+            }
+        }
+        
+        let (dt, zones, gravity) = (self.timestep, &self.gravity_zones, self.gravity);
+        // Gravity (or the zone's) plus drag, which opposes motion:
+        // a = -drag * v / m, so lighter bodies slow faster
+        let acceleration_at = |circle: &Circle, position: Vec2, velocity: Vec2| {
+            let acceleration = gravity_at(zones, gravity, position);
+            if circle.drag != Scalar::ZERO {
+                acceleration - velocity * circle.drag / circle.mass
+            } else {
+                acceleration
+            }
+        };
+        
+        for circle in self.circles.iter_mut().filter(|circle| !circle.is_static) {
             let current = circle.position;
             
-            // Calculate acceleration
-            let acceleration = self.gravity;
-            
-            // Verlet integration
-            circle.position = current * Scalar::TWO - circle.old_position 
-                + acceleration * self.timestep * self.timestep;
-            
-            // Update velocity for collision calculations
-            circle.velocity = (circle.position - circle.old_position) / self.timestep;
+            match self.integrator {
+                Integrator::Verlet => {
+                    // Only drag needs the (implied) velocity
+                    let velocity = if circle.drag != Scalar::ZERO {
+                        (current - circle.old_position) / dt
+                    } else {
+                        Vec2::ZERO
+                    };
+                    let acceleration = acceleration_at(circle, current, velocity);
+                    circle.position = current * Scalar::TWO - circle.old_position 
+                        + acceleration * dt * dt;
+                }
+                Integrator::VelocityVerlet => {
+                    let velocity = circle.velocity;
+                    let acceleration = acceleration_at(circle, current, velocity);
+                    circle.position = current + velocity * dt + acceleration * dt * dt * Scalar::HALF;
+                    // Drag at the new position uses the old velocity; the
+                    // scheme stays explicit
+                    let next_acceleration = acceleration_at(circle, circle.position, velocity);
+                    circle.velocity = velocity + (acceleration + next_acceleration) * dt * Scalar::HALF;
+                }
+            }
             
             circle.old_position = current;
+            circle.advance_angle(dt);
+        }
+    }
+    
+    /// Second half of `step`: resolve collisions, apply constraints and settle velocities
+    ///
+    /// Under `Integrator::Verlet`, velocities are derived from the current
+    /// positions and Verlet history, so a custom integrator only needs to
+    /// leave `position`/`old_position` consistent. Counts as the end of the
+    /// step for `steps_taken`.
+    pub fn resolve(&mut self) {
+        // Velocity for collision calculations, from possibly hook-corrected
+        // positions; velocity Verlet already integrated it explicitly
+        let derive_velocity = self.integrator == Integrator::Verlet;
+        if derive_velocity {
+            for circle in &mut self.circles {
+                circle.velocity = (circle.position - circle.old_position) / self.timestep;
+            }
         }
         
         // Step 2: Detect and resolve collisions (functional approach)
-        self.circles = crate::physics::resolve_all_collisions(
+        let (circles, contacts, broad_phase) = crate::physics::collision::resolve_all_collisions_with_contacts(
             &self.circles,
+            &self.rects,
             self.bounds.x,
             self.bounds.y,
             &self.collision_config,
+            self.should_collide.as_ref(),
         );
+        // Borrowed means no contacts: the circles are already up to date.
+        // Otherwise keep the pre-collision circles to spot velocity changes
+        let integrated = match circles {
+            Cow::Owned(circles) => Some(core::mem::replace(&mut self.circles, circles)),
+            Cow::Borrowed(_) => None,
+        };
+        if self.record_events {
+            let step = self.steps_taken;
+            self.events.extend(contacts.events.iter().map(|event| CollisionEvent { step, ..event.clone() }));
+        }
+        self.last_contacts = contacts;
+        self.last_broad_phase = broad_phase;
         
-        // Step 3: Update velocities after collision for next frame
-        for circle in &mut self.circles {
-            circle.velocity = (circle.position - circle.old_position) / self.timestep;
+        // Constraints get the last word on positions; the Verlet history
+        // turns their corrections into velocity below
+        solve_constraints(&mut self.circles, &self.constraints);
+        
+        // Step 3: Carry collision velocity changes into the Verlet history;
+        // untouched circles just pick up any position correction
+        let max_impulse = self.collision_config.max_impulse;
+        for (index, circle) in self.circles.iter_mut().enumerate() {
+            let before = integrated.as_ref().map_or(circle.velocity, |integrated| integrated[index].velocity);
+            if circle.velocity != before {
+                circle.old_position = circle.position - circle.velocity * self.timestep;
+            } else if derive_velocity {
+                circle.velocity = (circle.position - circle.old_position) / self.timestep;
+            }
+            
+            // Bound the kick from deep position corrections as well
+            if let Some(max_impulse) = max_impulse {
+                let change = circle.velocity - before;
+                let clamped = change.magnitude_clamped(max_impulse / circle.mass);
+                if clamped != change {
+                    circle.velocity = before + clamped;
+                    circle.old_position = circle.position - circle.velocity * self.timestep;
+                }
+            }
+            
+            // Cache exactly what the history encodes, rounding and all, so
+            // `velocity()` and `verlet_velocity` agree bit for bit
+            if derive_velocity {
+                circle.update_velocity(self.timestep);
+            }
         }
+        
+        self.steps_taken += 1;
+    }
+    
+    /// Blast circles within `radius` of `center` outward
+    ///
+    /// Each circle whose center is inside the blast gets an impulse of
+    /// `strength * (1 - distance / radius)` away from `center`, so its speed
+    /// change scales with proximity and inversely with mass. A circle exactly
+    /// at `center` has no outward direction and is left alone, as are static
+    /// circles. The velocity is written into the Verlet history, so it
+    /// carries into the next step.
+    pub fn apply_explosion(&mut self, center: Vec2, radius: Scalar, strength: Scalar) {
+        use crate::physics::collision::{apply_impulses, Impulse};
+        
+        let impulses: Vec<Impulse> = self.circles.iter().enumerate()
+            .filter_map(|(idx, circle)| {
+                if circle.is_static {
+                    return None;
+                }
+                // Rule out distant circles per axis first: squaring their
+                // offset would overflow Q16.16 (past ~181)
+                let offset = circle.position - center;
+                if offset.x.abs() >= radius || offset.y.abs() >= radius {
+                    return None;
+                }
+                let distance = offset.magnitude();
+                if distance >= radius || distance == Scalar::ZERO {
+                    return None;
+                }
+                let falloff = Scalar::ONE - distance / radius;
+                Some(Impulse {
+                    idx,
+                    delta_v: offset / distance * (strength * falloff / circle.mass),
+                    delta_pos: Vec2::ZERO,
+                })
+            })
+            .collect();
+        
+        let blasted: Vec<usize> = impulses.iter().map(|i| i.idx).collect();
+        self.circles = apply_impulses(&self.circles, &impulses);
+        for idx in blasted {
+            let circle = &mut self.circles[idx];
+            circle.old_position = circle.position - circle.velocity * self.timestep;
+        }
+    }
+    
+    /// Nearest circle hit by a ray from `origin` along `dir`, within `max_dist`
+    ///
+    /// Checks circles in index order; see `physics::raycast` for how grazing
+    /// rays and origins inside circles are treated.
+    pub fn raycast(&self, origin: Vec2, dir: Vec2, max_dist: Scalar) -> Option<RayHit> {
+        crate::physics::raycast(&self.circles, origin, dir, max_dist)
+    }
+    
+    /// Number of circles in the world
+    pub fn num_circles(&self) -> usize {
+        self.circles.len()
+    }
+    
+    /// Number of circles moving at or above `SLEEP_SPEED`
+    pub fn num_awake(&self) -> usize {
+        self.num_circles() - self.num_sleeping()
+    }
+    
+    /// Number of circles below `SLEEP_SPEED` (see `Circle::is_sleeping`)
+    pub fn num_sleeping(&self) -> usize {
+        self.circles.iter().filter(|c| c.is_sleeping()).count()
+    }
+    
+    /// Whether the simulation still looks physical
+    ///
+    /// False once any circle's center strays more than one world size outside
+    /// the bounds or any velocity component exceeds `MAX_SAFE_SPEED`. Fixed
+    /// point has no NaN; an exploded simulation shows up as bodies flung (or
+    /// wrapped) far away, or moving faster than anything sane. Cheap enough
+    /// to check every few steps.
+    pub fn is_sane(&self) -> bool {
+        let margin = self.bounds.x.max(self.bounds.y);
+        let (min, max) = (Vec2::from_scalars(-margin, -margin), self.bounds + Vec2::from_scalars(margin, margin));
+        self.circles.iter().all(|c| {
+            c.position.x >= min.x && c.position.x <= max.x
+                && c.position.y >= min.y && c.position.y <= max.y
+                && c.velocity.x.abs() <= MAX_SAFE_SPEED
+                && c.velocity.y.abs() <= MAX_SAFE_SPEED
+        })
+    }
+    
+    /// Snapshot the complete simulation state, collision config included
+    pub fn checkpoint(&self) -> WorldCheckpoint {
+        WorldCheckpoint { world: self.clone() }
+    }
+    
+    /// Rewind to `checkpoint`, after which stepping reproduces the original
+    /// run bit for bit
+    ///
+    /// `should_collide` is code rather than state and can't be serialized,
+    /// so the world keeps its current filter.
+    pub fn restore(&mut self, checkpoint: &WorldCheckpoint) {
+        let should_collide = self.should_collide.take();
+        *self = checkpoint.world.clone();
+        self.should_collide = should_collide;
+    }
+    
+    /// Collision events recorded since the last call, oldest first
+    ///
+    /// Empty unless `record_events` is set.
+    pub fn take_events(&mut self) -> Vec<CollisionEvent> {
+        core::mem::take(&mut self.events)
+    }
+    
+    /// Contacts resolved by the most recent `step` (empty before the first)
+    pub fn last_contacts(&self) -> &StepContacts {
+        &self.last_contacts
+    }
+    
+    /// Broad-phase statistics from the most recent `step` (zero before the first)
+    ///
+    /// Compare `candidate_pairs` with `actual_collisions` when tuning cell sizes.
+    pub fn broadphase_stats(&self) -> BroadPhaseStats {
+        self.last_broad_phase
     }
     
     /// Perform physics step without collisions (for testing)
@@ -101,13 +784,14 @@ impl World {
             let current = circle.position;
             
             // Calculate acceleration
-            let acceleration = self.gravity;
+            let acceleration = gravity_at(&self.gravity_zones, self.gravity, current);
             
             // Verlet integration
             circle.position = current * Scalar::TWO - circle.old_position 
                 + acceleration * self.timestep * self.timestep;
             
             circle.old_position = current;
+            circle.advance_angle(self.timestep);
             
             // Simple boundary check
             if circle.position.y - circle.radius < Scalar::ZERO {
@@ -116,4 +800,44 @@ impl World {
             }
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+impl World {
+    /// Copy of the world with circles permuted by a seeded shuffle
+    ///
+    /// Returns the copy and `order`, where `copy.circles[k]` is
+    /// `self.circles[order[k]]`; `should_collide` is remapped to keep
+    /// filtering the same bodies. For checking that results don't depend on
+    /// processing order.
+    pub(crate) fn with_shuffled_circles(&self, seed: u64) -> (World, Vec<usize>) {
+        // SplitMix64: tiny, seedable and identical everywhere
+        let mut state = seed;
+        let mut next = || {
+            state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+            let mut z = state;
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+            z ^ (z >> 31)
+        };
+        
+        // Fisher-Yates
+        let mut order: Vec<usize> = (0..self.circles.len()).collect();
+        for i in (1..order.len()).rev() {
+            let j = (next() % (i as u64 + 1)) as usize;
+            order.swap(i, j);
+        }
+        
+        let mut shuffled = self.clone();
+        shuffled.circles = order.iter().map(|&idx| self.circles[idx]).collect();
+        shuffled.circle_slots.reorder(&order);
+        if let Some(filter) = self.should_collide.clone() {
+            let original = order.clone();
+            shuffled.should_collide = Some(CollisionFilter::new(move |a, b| {
+                let (a, b) = (original[a], original[b]);
+                filter.allows(a.min(b), a.max(b))
+            }));
+        }
+        (shuffled, order)
+    }
+}
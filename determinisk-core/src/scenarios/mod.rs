@@ -1,9 +1,13 @@
-//! Pre-defined simulation scenarios and TOML/JSON file support
+//! Pre-defined simulation scenarios and TOML/JSON (and bincode trace) file support
 
 #[cfg(feature = "std")]
 use std::{fs, path::Path};
 
 use crate::state::SimulationInput;
+#[cfg(all(feature = "std", feature = "bincode"))]
+use crate::state::SimulationTrace;
+#[cfg(feature = "std")]
+use crate::DeterminiskError;
 
 // Import individual scenarios
 mod pool_break;
@@ -12,6 +16,7 @@ mod simple_drop;
 mod three_body_collision;
 mod pool_break_sim;
 mod simple_drop_sim;
+mod projectile_sweep;
 
 // Re-export scenario functions
 pub use pool_break::pool_break;
@@ -20,49 +25,90 @@ pub use simple_drop::simple_drop;
 pub use three_body_collision::three_body_collision;
 pub use pool_break_sim::pool_break_simulation;
 pub use simple_drop_sim::simple_drop_simulation;
+#[cfg(feature = "std")]
+pub use projectile_sweep::projectile_sweep;
 
-/// Load simulation from TOML file
+/// Load simulation from TOML file, rejecting inputs that fail `validate`
 #[cfg(all(feature = "std", feature = "toml"))]
-pub fn from_toml_file<P: AsRef<Path>>(path: P) -> Result<SimulationInput, Box<dyn std::error::Error>> {
+pub fn from_toml_file<P: AsRef<Path>>(path: P) -> Result<SimulationInput, DeterminiskError> {
     let contents = fs::read_to_string(path)?;
     let input: SimulationInput = toml::from_str(&contents)?;
+    input.validate()?;
     Ok(input)
 }
 
 /// Save simulation to TOML file
 #[cfg(all(feature = "std", feature = "toml"))]
-pub fn to_toml_file<P: AsRef<Path>>(input: &SimulationInput, path: P) -> Result<(), Box<dyn std::error::Error>> {
+pub fn to_toml_file<P: AsRef<Path>>(input: &SimulationInput, path: P) -> Result<(), DeterminiskError> {
     let toml_string = toml::to_string_pretty(input)?;
     fs::write(path, toml_string)?;
     Ok(())
 }
 
-/// Load simulation from JSON file
+/// Load simulation from JSON file, rejecting inputs that fail `validate`
 #[cfg(all(feature = "std", feature = "serde_json"))]
-pub fn from_json_file<P: AsRef<Path>>(path: P) -> Result<SimulationInput, Box<dyn std::error::Error>> {
+pub fn from_json_file<P: AsRef<Path>>(path: P) -> Result<SimulationInput, DeterminiskError> {
     let contents = fs::read_to_string(path)?;
     let input: SimulationInput = serde_json::from_str(&contents)?;
+    input.validate()?;
     Ok(input)
 }
 
 /// Save simulation to JSON file
 #[cfg(all(feature = "std", feature = "serde_json"))]
-pub fn to_json_file<P: AsRef<Path>>(input: &SimulationInput, path: P) -> Result<(), Box<dyn std::error::Error>> {
+pub fn to_json_file<P: AsRef<Path>>(input: &SimulationInput, path: P) -> Result<(), DeterminiskError> {
     let json = serde_json::to_string_pretty(input)?;
     fs::write(path, json)?;
     Ok(())
 }
 
+/// Load a trace saved by `to_bincode_file`
+///
+/// The recorded input is validated like a TOML/JSON one.
+#[cfg(all(feature = "std", feature = "bincode"))]
+pub fn from_bincode_file<P: AsRef<Path>>(path: P) -> Result<SimulationTrace, DeterminiskError> {
+    let reader = std::io::BufReader::new(fs::File::open(path)?);
+    let trace: SimulationTrace = bincode::deserialize_from(reader)?;
+    trace.input.validate()?;
+    Ok(trace)
+}
+
+/// Save a trace as bincode, far smaller than JSON for long recordings
+#[cfg(all(feature = "std", feature = "bincode"))]
+pub fn to_bincode_file<P: AsRef<Path>>(trace: &SimulationTrace, path: P) -> Result<(), DeterminiskError> {
+    use std::io::Write;
+    
+    let mut writer = std::io::BufWriter::new(fs::File::create(path)?);
+    bincode::serialize_into(&mut writer, trace)?;
+    writer.flush()?;
+    Ok(())
+}
+
 /// Auto-detect format and load from file
+///
+/// A `.bin` file is a bincode trace; its recorded input is returned.
 #[cfg(feature = "std")]
-pub fn from_file<P: AsRef<Path>>(path: P) -> Result<SimulationInput, Box<dyn std::error::Error>> {
+pub fn from_file<P: AsRef<Path>>(path: P) -> Result<SimulationInput, DeterminiskError> {
     let path = path.as_ref();
     match path.extension().and_then(|s| s.to_str()) {
         #[cfg(feature = "toml")]
         Some("toml") => from_toml_file(path),
         #[cfg(feature = "serde_json")]
         Some("json") => from_json_file(path),
-        _ => Err("Unsupported file format. Use .toml or .json".into()),
+        #[cfg(feature = "bincode")]
+        Some("bin") => from_bincode_file(path).map(|trace| trace.input),
+        _ => Err(DeterminiskError::Unsupported("Unsupported file format. Use .toml or .json".to_string())),
+    }
+}
+
+/// Load a built-in scenario by name, or a `.toml`/`.json`/`.bin` file by path
+#[cfg(feature = "std")]
+pub fn load(name_or_path: &str) -> Result<SimulationInput, DeterminiskError> {
+    if name_or_path.ends_with(".toml") || name_or_path.ends_with(".json") || name_or_path.ends_with(".bin") {
+        from_file(name_or_path)
+    } else {
+        get_scenario(name_or_path)
+            .ok_or_else(|| DeterminiskError::UnknownScenario(name_or_path.to_string()))
     }
 }
 
@@ -11,6 +11,7 @@ pub fn pool_break() -> SimulationInput {
         velocity: [15.0, 0.1],  // Slight angle for interesting dynamics
         radius: 0.5,
         mass: 1.2,  // Slightly heavier cue ball
+        angular_velocity: 0.0,
     });
     
     // Triangle of balls
@@ -25,6 +26,7 @@ pub fn pool_break() -> SimulationInput {
         velocity: [0.0, 0.0],
         radius,
         mass: 1.0,
+        angular_velocity: 0.0,
     });
     
     // Row 2: 2 balls
@@ -37,6 +39,7 @@ pub fn pool_break() -> SimulationInput {
             velocity: [0.0, 0.0],
             radius,
             mass: 1.0,
+            angular_velocity: 0.0,
         });
     }
     
@@ -50,6 +53,7 @@ pub fn pool_break() -> SimulationInput {
             velocity: [0.0, 0.0],
             radius,
             mass: 1.0,
+            angular_velocity: 0.0,
         });
     }
     
@@ -63,19 +67,26 @@ pub fn pool_break() -> SimulationInput {
             velocity: [0.0, 0.0],
             radius,
             mass: 1.0,
+            angular_velocity: 0.0,
         });
     }
     
     SimulationInput {
+        name: Some("pool_break".into()),
+        description: Some("Pool break with 11 balls in a triangle".into()),
+        author: None,
         world_width: 30.0,
         world_height: 20.0,
         gravity: [0.0, 0.0],  // No gravity for pool table
+        y_down: false,
         timestep: 1.0 / 60.0,  // 60 Hz
         restitution: 0.95,  // Nearly elastic collisions
         position_correction: 0.8,
+        collision_preset: Some("pool".into()),
         circles,
         num_steps: 600,  // 10 seconds at 60 Hz
         record_trajectory: true,
         seed: 0,
+        checkpoint_interval: 0,
     }
 }
\ No newline at end of file
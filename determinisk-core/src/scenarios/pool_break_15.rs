@@ -11,6 +11,7 @@ pub fn pool_break_15() -> SimulationInput {
         velocity: [18.0, 0.1],  // Faster for more balls
         radius: 0.5,
         mass: 1.2,  // Slightly heavier cue ball
+        angular_velocity: 0.0,
     });
     
     // Full triangle of 15 balls (standard pool/snooker)
@@ -25,6 +26,7 @@ pub fn pool_break_15() -> SimulationInput {
         velocity: [0.0, 0.0],
         radius,
         mass: 1.0,
+        angular_velocity: 0.0,
     });
     
     // Row 2: 2 balls
@@ -37,6 +39,7 @@ pub fn pool_break_15() -> SimulationInput {
             velocity: [0.0, 0.0],
             radius,
             mass: 1.0,
+            angular_velocity: 0.0,
         });
     }
     
@@ -50,6 +53,7 @@ pub fn pool_break_15() -> SimulationInput {
             velocity: [0.0, 0.0],
             radius,
             mass: 1.0,
+            angular_velocity: 0.0,
         });
     }
     
@@ -63,6 +67,7 @@ pub fn pool_break_15() -> SimulationInput {
             velocity: [0.0, 0.0],
             radius,
             mass: 1.0,
+            angular_velocity: 0.0,
         });
     }
     
@@ -76,19 +81,26 @@ pub fn pool_break_15() -> SimulationInput {
             velocity: [0.0, 0.0],
             radius,
             mass: 1.0,
+            angular_velocity: 0.0,
         });
     }
     
     SimulationInput {
+        name: Some("pool_break_15".into()),
+        description: Some("Pool break with a full 15-ball rack".into()),
+        author: None,
         world_width: 35.0,  // Wider table for 15 balls
         world_height: 20.0,
         gravity: [0.0, 0.0],  // No gravity for pool table
+        y_down: false,
         timestep: 1.0 / 60.0,  // 60 Hz
         restitution: 0.95,  // Nearly elastic collisions
         position_correction: 0.8,
+        collision_preset: Some("pool".into()),
         circles,
         num_steps: 800,  // Longer simulation for more balls
         record_trajectory: true,
         seed: 0,
+        checkpoint_interval: 0,
     }
 }
\ No newline at end of file
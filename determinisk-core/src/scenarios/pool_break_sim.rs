@@ -12,6 +12,7 @@ pub fn pool_break_simulation() -> SimulationInput {
         velocity: [15.0, 0.1], // Slight angle for more interesting dynamics
         radius: 0.5,
         mass: 1.2, // Slightly heavier cue ball
+        angular_velocity: 0.0,
     });
     
     // Set up triangle of balls (like pool/billiards)
@@ -26,6 +27,7 @@ pub fn pool_break_simulation() -> SimulationInput {
         velocity: [0.0, 0.0],
         radius,
         mass: 1.0,
+        angular_velocity: 0.0,
     });
     
     // Row 2: 2 balls
@@ -35,6 +37,7 @@ pub fn pool_break_simulation() -> SimulationInput {
             velocity: [0.0, 0.0],
             radius,
             mass: 1.0,
+            angular_velocity: 0.0,
         });
     }
     
@@ -45,6 +48,7 @@ pub fn pool_break_simulation() -> SimulationInput {
             velocity: [0.0, 0.0],
             radius,
             mass: 1.0,
+            angular_velocity: 0.0,
         });
     }
     
@@ -55,19 +59,26 @@ pub fn pool_break_simulation() -> SimulationInput {
             velocity: [0.0, 0.0],
             radius,
             mass: 1.0,
+            angular_velocity: 0.0,
         });
     }
     
     SimulationInput {
+        name: Some("pool_break_sim".into()),
+        description: Some("Pool break built programmatically".into()),
+        author: None,
         world_width: 30.0,
         world_height: 20.0,
         gravity: [0.0, 0.0], // No gravity for pool table
+        y_down: false,
         timestep: 0.016666667, // 60 Hz
         restitution: 0.95, // High elasticity for pool balls
         position_correction: 0.8,
+        collision_preset: Some("pool".into()),
         circles,
         num_steps: 600, // 10 seconds at 60 Hz
         record_trajectory: true,
         seed: 0,
+        checkpoint_interval: 0,
     }
 }
\ No newline at end of file
@@ -0,0 +1,48 @@
+//! Launch-angle sweep for ballistics studies
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use crate::state::{SimulationInput, CircleConfig};
+
+/// Spacing between launch points so neighbouring projectiles don't start overlapping
+const LAUNCH_SPACING: f32 = 5.0;
+
+/// One projectile per launch angle (in degrees), all fired at `speed`
+///
+/// `world` is `[width, height]`. Projectiles are launched from the ground
+/// near the left wall, each offset by `LAUNCH_SPACING` along x so they don't
+/// collide at launch; measure ranges relative to each circle's start.
+/// All projectiles share one world, so crossing trajectories can still collide.
+#[cfg(feature = "std")]
+pub fn projectile_sweep(speed: f32, angles: &[f32], world: [f32; 2]) -> SimulationInput {
+    let circles: Vec<CircleConfig> = angles.iter().enumerate().map(|(i, &angle)| {
+        let angle_rad = angle.to_radians();
+        CircleConfig {
+            position: [10.0 + i as f32 * LAUNCH_SPACING, 10.0],
+            velocity: [speed * angle_rad.cos(), speed * angle_rad.sin()],
+            radius: 2.0,
+            mass: 0.5,
+            angular_velocity: 0.0,
+        }
+    }).collect();
+
+    SimulationInput {
+        name: Some("projectile_sweep".into()),
+        description: Some("One projectile per launch angle".into()),
+        author: None,
+        world_width: world[0],
+        world_height: world[1],
+        gravity: [0.0, -9.81],
+        y_down: false,
+        timestep: 1.0 / 60.0,
+        restitution: 0.8,
+        position_correction: 0.8,
+        collision_preset: None,
+        circles,
+        num_steps: 600,  // 10 seconds at 60 Hz
+        record_trajectory: true,
+        seed: 0,
+        checkpoint_interval: 0,
+    }
+}
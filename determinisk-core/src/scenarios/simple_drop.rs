@@ -4,22 +4,29 @@ use crate::state::{SimulationInput, CircleConfig};
 
 pub fn simple_drop() -> SimulationInput {
     SimulationInput {
+        name: Some("simple_drop".into()),
+        description: Some("Single ball dropped from height, bouncing on the floor".into()),
+        author: None,
         world_width: 100.0,
         world_height: 100.0,
         gravity: [0.0, -9.81],  // Earth gravity
+        y_down: false,
         timestep: 1.0 / 60.0,  // 60 Hz
         restitution: 0.8,  // Some energy loss on bounce
         position_correction: 0.8,
+        collision_preset: None,
         circles: vec![
             CircleConfig {
                 position: [50.0, 80.0],  // Start high
                 velocity: [0.0, 0.0],  // No initial velocity
                 radius: 5.0,
                 mass: 1.0,
+                angular_velocity: 0.0,
             }
         ],
         num_steps: 120,  // 2 seconds at 60 Hz
         record_trajectory: true,
         seed: 0,
+        checkpoint_interval: 0,
     }
 }
\ No newline at end of file
@@ -5,22 +5,29 @@ use crate::{SimulationInput, CircleConfig};
 /// Create a simple ball drop simulation
 pub fn simple_drop_simulation() -> SimulationInput {
     SimulationInput {
+        name: Some("simple_drop_sim".into()),
+        description: Some("Ball drop built programmatically".into()),
+        author: None,
         world_width: 100.0,
         world_height: 100.0,
         gravity: [0.0, -9.81], // Standard Earth gravity
+        y_down: false,
         timestep: 0.016666667, // 60 Hz
         restitution: 0.8, // Some energy loss on bounce
         position_correction: 0.8,
+        collision_preset: None,
         circles: vec![
             CircleConfig {
                 position: [50.0, 80.0], // High up in the middle
                 velocity: [0.0, 0.0], // Starting at rest
                 radius: 5.0,
                 mass: 1.0,
+                angular_velocity: 0.0,
             }
         ],
         num_steps: 300, // 5 seconds at 60 Hz
         record_trajectory: true,
         seed: 0,
+        checkpoint_interval: 0,
     }
 }
\ No newline at end of file
@@ -8,8 +8,15 @@ use alloc::{vec::Vec, collections::BTreeMap};
 #[cfg(feature = "std")]
 use std::collections::BTreeMap;
 
-use crate::math::{Scalar, Vec2};
-use crate::physics::Circle;
+use crate::math::{Aabb, Scalar, Vec2};
+use crate::physics::{Circle, Rect};
+use serde::{Serialize, Deserialize};
+
+#[cfg(test)]
+std::thread_local! {
+    /// Grids built on this thread, so tests can check for redundant broad phases
+    pub(crate) static GRID_BUILDS: core::cell::Cell<usize> = const { core::cell::Cell::new(0) };
+}
 
 /// Spatial grid for broad-phase collision detection
 /// Cell size is typically 2x the maximum circle radius
@@ -18,13 +25,68 @@ pub struct SpatialGrid {
     /// Grid cells containing circle indices
     /// Using BTreeMap for deterministic iteration order
     cells: BTreeMap<GridCell, Vec<usize>>,
-    /// Size of each grid cell
-    cell_size: Scalar,
+    /// Width of each grid cell
+    cell_width: Scalar,
+    /// Height of each grid cell
+    cell_height: Scalar,
     /// World boundaries for wrapping
     _world_width: Scalar,
     _world_height: Scalar,
 }
 
+/// Shape of the cells `World` uses for its broad phase
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GridCells {
+    /// Square cells of `SpatialGrid::default_cell_size`
+    #[default]
+    Square,
+    /// Cells of a fixed width and height
+    ///
+    /// Lets wide or tall worlds use fewer, longer cells along their long
+    /// axis. Any positive size finds the same pairs, since circles are
+    /// added to every cell they overlap; only the cost changes.
+    Rect { width: Scalar, height: Scalar },
+}
+
+impl GridCells {
+    /// Whether every cell side is positive
+    pub fn is_valid(&self) -> bool {
+        match *self {
+            GridCells::Square => true,
+            GridCells::Rect { width, height } => width > Scalar::ZERO && height > Scalar::ZERO,
+        }
+    }
+    
+    /// Cell width and height for a grid over `circles`
+    ///
+    /// Invalid `Rect` cells (e.g. from a hand-edited config) fall back to
+    /// `Square` rather than dividing by zero.
+    pub fn dimensions(&self, circles: &[Circle]) -> (Scalar, Scalar) {
+        match *self {
+            GridCells::Rect { width, height } if self.is_valid() => (width, height),
+            _ => {
+                let size = SpatialGrid::default_cell_size(circles);
+                (size, size)
+            }
+        }
+    }
+}
+
+/// `GridCells::Rect` with a zero or negative side
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidGridCells {
+    pub cells: GridCells,
+}
+
+impl core::fmt::Display for InvalidGridCells {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "grid cells must have a positive width and height, got {:?}", self.cells)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for InvalidGridCells {}
+
 /// Grid cell coordinates
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub struct GridCell {
@@ -35,65 +97,179 @@ pub struct GridCell {
 impl SpatialGrid {
     /// Create a new spatial grid
     pub fn new(cell_size: Scalar, world_width: Scalar, world_height: Scalar) -> Self {
+        Self::new_rect(cell_size, cell_size, world_width, world_height)
+    }
+    
+    /// Create a new spatial grid with rectangular cells
+    pub fn new_rect(cell_width: Scalar, cell_height: Scalar, world_width: Scalar, world_height: Scalar) -> Self {
         Self {
             cells: BTreeMap::new(),
-            cell_size,
+            cell_width,
+            cell_height,
             _world_width: world_width,
             _world_height: world_height,
         }
     }
 
+    /// Cell size used by the collision pipeline (2x the largest radius)
+    pub fn default_cell_size(circles: &[Circle]) -> Scalar {
+        let max_radius = circles.iter()
+            .map(|c| c.radius)
+            .max()
+            .unwrap_or(Scalar::from_float(1.0));
+        max_radius * Scalar::from_float(2.0)
+    }
+    
+    /// Size of each grid cell; the width, for rectangular cells
+    pub fn cell_size(&self) -> Scalar {
+        self.cell_width
+    }
+    
+    /// Width of each grid cell
+    pub fn cell_width(&self) -> Scalar {
+        self.cell_width
+    }
+    
+    /// Height of each grid cell
+    pub fn cell_height(&self) -> Scalar {
+        self.cell_height
+    }
+    
+    /// Number of cells holding at least one circle
+    pub fn num_occupied_cells(&self) -> usize {
+        self.cells.len()
+    }
+    
+    /// Occupied cells with the number of circles in each, in cell order
+    pub fn occupancy(&self) -> Vec<(GridCell, usize)> {
+        self.cells.iter()
+            .map(|(cell, indices)| (*cell, indices.len()))
+            .collect()
+    }
+    
+    /// All cells with their circle indices, in sorted cell order
+    ///
+    /// The BTreeMap makes this deterministic, so dumps from two machines can
+    /// be diffed directly when reproducing broad-phase bugs.
+    pub fn debug_dump(&self) -> Vec<(GridCell, Vec<usize>)> {
+        self.cells.iter()
+            .map(|(cell, indices)| (*cell, indices.clone()))
+            .collect()
+    }
+    
     /// Build grid from circle positions (functional update)
     /// This is a pure function - returns new grid without mutation
     pub fn build(circles: &[Circle], cell_size: Scalar, world_width: Scalar, world_height: Scalar) -> Self {
-        let mut grid = Self::new(cell_size, world_width, world_height);
+        Self::build_rect(circles, cell_size, cell_size, world_width, world_height)
+    }
+    
+    /// `build` with rectangular cells
+    pub fn build_rect(
+        circles: &[Circle],
+        cell_width: Scalar,
+        cell_height: Scalar,
+        world_width: Scalar,
+        world_height: Scalar,
+    ) -> Self {
+        #[cfg(test)]
+        GRID_BUILDS.with(|count| count.set(count.get() + 1));
+        
+        let mut grid = Self::new_rect(cell_width, cell_height, world_width, world_height);
         
         for (idx, circle) in circles.iter().enumerate() {
             let cell = grid.position_to_cell(circle.position);
-            grid.cells.entry(cell).or_insert_with(Vec::new).push(idx);
+            grid.cells.entry(cell).or_default().push(idx);
             
             // Also add to neighboring cells if circle overlaps boundaries
             // This ensures we don't miss collisions at cell edges
             let radius = circle.radius;
-            let neighbors = grid.get_overlapping_cells(circle.position, radius);
-            for neighbor_cell in neighbors {
+            for neighbor_cell in grid.overlapping_cells(circle.position, radius) {
                 if neighbor_cell != cell {
-                    grid.cells.entry(neighbor_cell).or_insert_with(Vec::new).push(idx);
+                    grid.cells.entry(neighbor_cell).or_default().push(idx);
                 }
             }
         }
         
         grid
     }
+    
+    /// Build a grid over boxes rather than circles, e.g. the bounds each
+    /// circle swept over a step
+    ///
+    /// Box `idx` is added to every cell it overlaps, so `get_collision_pairs`
+    /// yields every pair of overlapping boxes. Boxes are clamped to the world
+    /// first, so a sweep far past a wall costs no more cells than one to it;
+    /// boxes that overlap still do after clamping.
+    pub fn build_aabbs(
+        aabbs: &[Aabb],
+        cell_width: Scalar,
+        cell_height: Scalar,
+        world_width: Scalar,
+        world_height: Scalar,
+    ) -> Self {
+        #[cfg(test)]
+        GRID_BUILDS.with(|count| count.set(count.get() + 1));
+        
+        let mut grid = Self::new_rect(cell_width, cell_height, world_width, world_height);
+        
+        let clamp = |point: Vec2| Vec2::from_scalars(
+            point.x.max(Scalar::ZERO).min(world_width),
+            point.y.max(Scalar::ZERO).min(world_height),
+        );
+        for (idx, aabb) in aabbs.iter().enumerate() {
+            for cell in grid.cells_between(clamp(aabb.min), clamp(aabb.max)) {
+                grid.cells.entry(cell).or_default().push(idx);
+            }
+        }
+        
+        grid
+    }
 
     /// Convert world position to grid cell
     fn position_to_cell(&self, pos: Vec2) -> GridCell {
         GridCell {
-            x: (pos.x / self.cell_size).to_int(),
-            y: (pos.y / self.cell_size).to_int(),
+            x: (pos.x / self.cell_width).to_int(),
+            y: (pos.y / self.cell_height).to_int(),
         }
     }
 
-    /// Get all cells that a circle might overlap
-    fn get_overlapping_cells(&self, center: Vec2, radius: Scalar) -> Vec<GridCell> {
-        let mut cells = Vec::new();
-        
-        // Calculate the bounding box of the circle
-        let min_x = (center.x - radius) / self.cell_size;
-        let max_x = (center.x + radius) / self.cell_size;
-        let min_y = (center.y - radius) / self.cell_size;
-        let max_y = (center.y + radius) / self.cell_size;
-        
-        // Add all cells in the bounding box
-        for x in min_x.to_int()..=max_x.to_int() {
-            for y in min_y.to_int()..=max_y.to_int() {
-                cells.push(GridCell { x, y });
-            }
-        }
+    /// Get all cells that a circle might overlap, x-major
+    ///
+    /// Yields cells lazily rather than collecting them, since `build` calls
+    /// this for every circle on every step.
+    fn overlapping_cells(&self, center: Vec2, radius: Scalar) -> impl Iterator<Item = GridCell> {
+        // The bounding box of the circle
+        let extent = Vec2::from_scalars(radius, radius);
+        self.cells_between(center - extent, center + extent)
+    }
+    
+    /// All cells in the box from `min` to `max`, x-major
+    fn cells_between(&self, min: Vec2, max: Vec2) -> impl Iterator<Item = GridCell> {
+        let min_x = (min.x / self.cell_width).to_int();
+        let max_x = (max.x / self.cell_width).to_int();
+        let min_y = (min.y / self.cell_height).to_int();
+        let max_y = (max.y / self.cell_height).to_int();
         
-        cells
+        (min_x..=max_x).flat_map(move |x| (min_y..=max_y).map(move |y| GridCell { x, y }))
     }
 
+    /// Candidate neighbors of a disk: circles sharing a cell with it
+    ///
+    /// Returns each circle in any cell the disk at `center` overlaps, sorted
+    /// and without duplicates. Like `get_collision_pairs` this is a broad
+    /// phase: every circle actually within `radius` of the disk is included,
+    /// along with some that aren't.
+    pub fn query_radius(&self, center: Vec2, radius: Scalar) -> Vec<usize> {
+        let mut found: Vec<usize> = self.overlapping_cells(center, radius)
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+            .copied()
+            .collect();
+        found.sort_unstable();
+        found.dedup();
+        found
+    }
+    
     /// Get potential collision pairs from the grid
     /// Returns pairs of circle indices that might be colliding
     pub fn get_collision_pairs(&self) -> Vec<(usize, usize)> {
@@ -101,7 +277,7 @@ impl SpatialGrid {
         let mut checked = BTreeMap::new(); // Track checked pairs to avoid duplicates
         
         // Iterate over all cells in deterministic order (BTreeMap guarantees this)
-        for (_cell, indices) in &self.cells {
+        for indices in self.cells.values() {
             // Check all pairs within this cell
             for i in 0..indices.len() {
                 for j in (i + 1)..indices.len() {
@@ -117,8 +293,7 @@ impl SpatialGrid {
                     
                     // Only add if we haven't checked this pair yet
                     let key = (min_idx, max_idx);
-                    if !checked.contains_key(&key) {
-                        checked.insert(key, true);
+                    if checked.insert(key, true).is_none() {
                         pairs.push(key);
                     }
                 }
@@ -144,9 +319,28 @@ pub struct Collision {
     pub contact: Vec2,
 }
 
+/// Where `Collision::contact` is placed for overlapping circles
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ContactMode {
+    /// On A's surface along the normal (the historical placement)
+    #[default]
+    SurfaceA,
+    /// Halfway between A's and B's surfaces, the middle of the overlap
+    Midpoint,
+}
+
 /// Detect actual collisions from potential pairs
 /// This is a pure function that checks if circles actually overlap
 pub fn detect_collisions(circles: &[Circle], pairs: &[(usize, usize)]) -> Vec<Collision> {
+    detect_collisions_with_mode(circles, pairs, ContactMode::SurfaceA)
+}
+
+/// `detect_collisions` with an explicit contact placement
+pub fn detect_collisions_with_mode(
+    circles: &[Circle],
+    pairs: &[(usize, usize)],
+    mode: ContactMode,
+) -> Vec<Collision> {
     let mut collisions = Vec::new();
     
     for &(idx_a, idx_b) in pairs {
@@ -165,8 +359,12 @@ pub fn detect_collisions(circles: &[Circle], pairs: &[(usize, usize)]) -> Vec<Co
             let normal = delta / dist; // Normalized direction from A to B
             let depth = sum_radii - dist;
             
-            // Contact point is between the two circle centers
-            let contact = circle_a.position + normal * circle_a.radius;
+            // Contact point is between the two circle centers; B's surface
+            // sits `depth` back from A's along the normal
+            let contact = match mode {
+                ContactMode::SurfaceA => circle_a.position + normal * circle_a.radius,
+                ContactMode::Midpoint => circle_a.position + normal * (circle_a.radius - depth * Scalar::HALF),
+            };
             
             collisions.push(Collision {
                 idx_a,
@@ -181,6 +379,92 @@ pub fn detect_collisions(circles: &[Circle], pairs: &[(usize, usize)]) -> Vec<Co
     collisions
 }
 
+/// A contact found by sweeping a pair of circles over the last step
+#[derive(Debug, Clone)]
+pub struct SweptCollision {
+    /// The contact where the circles first touched, with zero depth
+    pub collision: Collision,
+    /// Fraction of the step (0 to 1) at which they touched
+    pub toi: Scalar,
+}
+
+/// Find pairs that passed through each other during the last step
+///
+/// Each circle moved in a straight line from `old_position` to `position`.
+/// Only pairs whose relative displacement exceeds their combined radii are
+/// swept (slower pairs can't skip past each other), and pairs overlapping at
+/// the start or end of the step are left to `detect_collisions`. The time of
+/// impact comes from casting the relative motion as a ray against a circle
+/// of the combined radius, all in fixed point; the contact is placed at the
+/// time-of-impact positions, as in `ContactMode::SurfaceA`.
+pub fn detect_swept_collisions(circles: &[Circle], pairs: &[(usize, usize)]) -> Vec<SweptCollision> {
+    let mut collisions = Vec::new();
+    
+    for &(idx_a, idx_b) in pairs {
+        let (a, b) = (&circles[idx_a], &circles[idx_b]);
+        let sum_radii = a.radius + b.radius;
+        
+        // Swept boxes must overlap; this also keeps the squares below small
+        let reach = |circle: &Circle, axis: fn(Vec2) -> Scalar| {
+            let (from, to) = (axis(circle.old_position), axis(circle.position));
+            (from.min(to) - circle.radius, from.max(to) + circle.radius)
+        };
+        let disjoint = |axis: fn(Vec2) -> Scalar| {
+            let ((min_a, max_a), (min_b, max_b)) = (reach(a, axis), reach(b, axis));
+            max_a < min_b || max_b < min_a
+        };
+        if disjoint(|v| v.x) || disjoint(|v| v.y) {
+            continue;
+        }
+        
+        // Relative motion of B as seen from A
+        let start = b.old_position - a.old_position;
+        let end = b.position - a.position;
+        let motion = end - start;
+        let radius_sq = sum_radii * sum_radii;
+        if motion.length_squared() <= radius_sq
+            || start.length_squared() < radius_sq
+            || end.length_squared() < radius_sq
+        {
+            continue;
+        }
+        
+        // Ray from `start` along `motion` against the combined-radius circle
+        let travel = motion.magnitude();
+        let dir = motion / travel;
+        let along = -start.dot(&dir);
+        if along < Scalar::ZERO {
+            continue;
+        }
+        let miss_sq = start.length_squared() - along * along;
+        if miss_sq > radius_sq {
+            continue;
+        }
+        let distance = along - (radius_sq - miss_sq).sqrt();
+        if distance > travel {
+            continue;
+        }
+        let toi = distance / travel;
+        
+        let at_toi = |circle: &Circle| circle.old_position + (circle.position - circle.old_position) * toi;
+        let (position_a, position_b) = (at_toi(a), at_toi(b));
+        let delta = position_b - position_a;
+        let normal = delta / delta.magnitude();
+        collisions.push(SweptCollision {
+            collision: Collision {
+                idx_a,
+                idx_b,
+                normal,
+                depth: Scalar::ZERO,
+                contact: position_a + normal * a.radius,
+            },
+            toi,
+        });
+    }
+    
+    collisions
+}
+
 /// Collision with boundary
 #[derive(Debug, Clone)]
 pub struct BoundaryCollision {
@@ -256,4 +540,331 @@ pub fn detect_boundary_collisions(
     }
     
     collisions
-}
\ No newline at end of file
+}
+
+/// Detect circles overlapping rectangle obstacles
+///
+/// Each `Collision` has the circle as `idx_a` and the rectangle's index as
+/// `idx_b`, with the normal pointing from the circle into the rectangle and
+/// the contact at the closest point on the rectangle. A circle whose center
+/// is inside a rectangle is pushed out through the nearest face (left,
+/// right, bottom, top on ties). Checks every circle against every
+/// rectangle; obstacles are expected to be few.
+pub fn detect_rect_collisions(circles: &[Circle], rects: &[Rect]) -> Vec<Collision> {
+    let mut collisions = Vec::new();
+    
+    for (idx_a, circle) in circles.iter().enumerate() {
+        let pos = circle.position;
+        let radius = circle.radius;
+        
+        for (idx_b, rect) in rects.iter().enumerate() {
+            let Aabb { min, max } = rect.bounds;
+            let closest = Vec2::from_scalars(pos.x.clamp(min.x, max.x), pos.y.clamp(min.y, max.y));
+            let delta = pos - closest;
+            // Component check first so far-off circles can't overflow the square
+            if delta.x.abs() >= radius || delta.y.abs() >= radius {
+                continue;
+            }
+            
+            let dist_sq = delta.length_squared();
+            if dist_sq >= radius * radius {
+                continue;
+            }
+            let (outward, depth, contact) = if dist_sq > Scalar::ZERO {
+                let dist = dist_sq.sqrt();
+                (delta / dist, radius - dist, closest)
+            } else {
+                // Center inside: leave through the nearest face
+                let faces = [
+                    (pos.x - min.x, -Vec2::UNIT_X, Vec2::from_scalars(min.x, pos.y)),
+                    (max.x - pos.x, Vec2::UNIT_X, Vec2::from_scalars(max.x, pos.y)),
+                    (pos.y - min.y, -Vec2::UNIT_Y, Vec2::from_scalars(pos.x, min.y)),
+                    (max.y - pos.y, Vec2::UNIT_Y, Vec2::from_scalars(pos.x, max.y)),
+                ];
+                let mut nearest = faces[0];
+                for face in &faces[1..] {
+                    if face.0 < nearest.0 {
+                        nearest = *face;
+                    }
+                }
+                let (distance, outward, contact) = nearest;
+                (outward, radius + distance, contact)
+            };
+            
+            collisions.push(Collision {
+                idx_a,
+                idx_b,
+                normal: -outward,
+                depth,
+                contact,
+            });
+        }
+    }
+    
+    collisions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn sample_circles() -> Vec<Circle> {
+        (0..6)
+            .map(|i| Circle::new(
+                Vec2::new(3.0 + i as f32 * 1.5, 4.0 + (i % 2) as f32),
+                Scalar::from_float(1.0),
+                Scalar::ONE,
+            ))
+            .collect()
+    }
+    
+    #[test]
+    fn test_debug_dump_is_reproducible() {
+        let circles = sample_circles();
+        let cell_size = SpatialGrid::default_cell_size(&circles);
+        let width = Scalar::from_float(20.0);
+        let height = Scalar::from_float(20.0);
+        
+        let grid_a = SpatialGrid::build(&circles, cell_size, width, height);
+        let grid_b = SpatialGrid::build(&sample_circles(), cell_size, width, height);
+        
+        let dump = grid_a.debug_dump();
+        assert!(!dump.is_empty());
+        assert_eq!(dump, grid_b.debug_dump());
+        
+        // Cells come out sorted
+        assert!(dump.windows(2).all(|w| w[0].0 < w[1].0));
+    }
+    
+    #[test]
+    fn test_build_matches_collected_overlap_reference() {
+        // The pre-iterator build: collect each circle's overlapping cells first
+        fn reference_build(circles: &[Circle], grid: &mut SpatialGrid) {
+            for (idx, circle) in circles.iter().enumerate() {
+                let cell = grid.position_to_cell(circle.position);
+                grid.cells.entry(cell).or_default().push(idx);
+                let mut neighbors = Vec::new();
+                let min_x = (circle.position.x - circle.radius) / grid.cell_width;
+                let max_x = (circle.position.x + circle.radius) / grid.cell_width;
+                let min_y = (circle.position.y - circle.radius) / grid.cell_height;
+                let max_y = (circle.position.y + circle.radius) / grid.cell_height;
+                for x in min_x.to_int()..=max_x.to_int() {
+                    for y in min_y.to_int()..=max_y.to_int() {
+                        neighbors.push(GridCell { x, y });
+                    }
+                }
+                for neighbor_cell in neighbors {
+                    if neighbor_cell != cell {
+                        grid.cells.entry(neighbor_cell).or_default().push(idx);
+                    }
+                }
+            }
+        }
+        
+        // 150 mixed-size circles, many straddling cell edges
+        let circles: Vec<Circle> = (0..150)
+            .map(|i| Circle::new(
+                Vec2::new((i % 15) as f32 * 2.3 + 1.0, (i / 15) as f32 * 2.7 + 1.0),
+                Scalar::from_float(0.5 + (i % 4) as f32 * 0.4),
+                Scalar::ONE,
+            ))
+            .collect();
+        let cell_size = SpatialGrid::default_cell_size(&circles);
+        let size = Scalar::from_float(50.0);
+        
+        let grid = SpatialGrid::build(&circles, cell_size, size, size);
+        let mut reference = SpatialGrid::new(cell_size, size, size);
+        reference_build(&circles, &mut reference);
+        
+        assert_eq!(grid.debug_dump(), reference.debug_dump());
+        assert_eq!(grid.get_collision_pairs(), reference.get_collision_pairs());
+    }
+    
+    #[test]
+    fn test_midpoint_contact_lies_between_surfaces() {
+        // Radii 2 and 1, centers 2.5 apart along x: overlap spans x in [1.5, 2]
+        let circles = vec![
+            Circle::new(Vec2::new(0.0, 0.0), Scalar::from_float(2.0), Scalar::ONE),
+            Circle::new(Vec2::new(2.5, 0.0), Scalar::ONE, Scalar::ONE),
+        ];
+        let pairs = [(0, 1)];
+        
+        let surface = detect_collisions(&circles, &pairs);
+        assert_eq!(surface[0].contact, Vec2::new(2.0, 0.0));
+        
+        let midpoint = detect_collisions_with_mode(&circles, &pairs, ContactMode::Midpoint);
+        assert_eq!(midpoint[0].contact, Vec2::new(1.75, 0.0));
+        assert_eq!(midpoint[0].depth, surface[0].depth);
+    }
+    
+    #[test]
+    fn test_rect_cells_find_the_same_pairs_as_square_cells() {
+        // A pool-table-shaped scene, dense enough for plenty of candidate pairs
+        let circles: Vec<Circle> = (0..120)
+            .map(|i| Circle::new(
+                Vec2::new((i % 20) as f32 * 1.7 + 0.8, (i / 20) as f32 * 3.1 + 1.0),
+                Scalar::from_float(0.6 + (i % 3) as f32 * 0.3),
+                Scalar::ONE,
+            ))
+            .collect();
+        let (width, height) = (Scalar::from_float(35.0), Scalar::from_float(20.0));
+        // Candidates differ with the cell shape; the overlapping pairs must not
+        let sorted_pairs = |grid: &SpatialGrid| {
+            let mut pairs: Vec<(usize, usize)> = detect_collisions(&circles, &grid.get_collision_pairs())
+                .iter()
+                .map(|c| (c.idx_a, c.idx_b))
+                .collect();
+            pairs.sort_unstable();
+            pairs
+        };
+        
+        let (size, _) = GridCells::Square.dimensions(&circles);
+        let square = sorted_pairs(&SpatialGrid::build(&circles, size, width, height));
+        assert!(!square.is_empty());
+        
+        for (cell_width, cell_height) in [(7.0, 2.5), (3.0, 6.0), (1.1, 0.9)] {
+            let cells = GridCells::Rect {
+                width: Scalar::from_float(cell_width),
+                height: Scalar::from_float(cell_height),
+            };
+            let (cw, ch) = cells.dimensions(&circles);
+            let grid = SpatialGrid::build_rect(&circles, cw, ch, width, height);
+            
+            assert_eq!(sorted_pairs(&grid), square, "{}x{} cells", cell_width, cell_height);
+            // Unsorted output is still reproducible
+            assert_eq!(
+                grid.get_collision_pairs(),
+                SpatialGrid::build_rect(&circles, cw, ch, width, height).get_collision_pairs(),
+            );
+        }
+    }
+    
+    #[test]
+    fn test_query_radius_covers_brute_force_neighbors() {
+        let circles: Vec<Circle> = (0..150)
+            .map(|i| Circle::new(
+                Vec2::new((i % 15) as f32 * 2.3 + 1.0, (i / 15) as f32 * 2.7 + 1.0),
+                Scalar::from_float(0.5 + (i % 4) as f32 * 0.4),
+                Scalar::ONE,
+            ))
+            .collect();
+        let cell_size = SpatialGrid::default_cell_size(&circles);
+        let size = Scalar::from_float(50.0);
+        let grid = SpatialGrid::build(&circles, cell_size, size, size);
+        
+        for (center, radius) in [((10.0, 10.0), 3.0), ((0.5, 0.5), 1.0), ((33.3, 25.1), 6.5), ((45.0, 45.0), 0.5)] {
+            let center = Vec2::new(center.0, center.1);
+            let radius = Scalar::from_float(radius);
+            let found = grid.query_radius(center, radius);
+            
+            // Sorted and deduplicated, though circles span several cells
+            assert!(found.windows(2).all(|w| w[0] < w[1]), "{:?}", found);
+            
+            // Every circle overlapping the query disk is a candidate
+            for (idx, circle) in circles.iter().enumerate() {
+                let reach = radius + circle.radius;
+                if (circle.position - center).length_squared() < reach * reach {
+                    assert!(found.contains(&idx), "circle {} missing near {}", idx, center);
+                }
+            }
+        }
+    }
+    
+    #[test]
+    fn test_rect_collisions_corner_and_inside() {
+        let rects = [Rect::new(Vec2::new(2.0, 2.0), Vec2::new(6.0, 4.0))];
+        let circles = vec![
+            // Overlapping the top-right corner diagonally
+            Circle::new(Vec2::new(6.5, 4.5), Scalar::ONE, Scalar::ONE),
+            // Center inside, nearest the bottom face
+            Circle::new(Vec2::new(4.0, 2.25), Scalar::HALF, Scalar::ONE),
+            // Clear of the box
+            Circle::new(Vec2::new(10.0, 3.0), Scalar::ONE, Scalar::ONE),
+        ];
+        
+        let collisions = detect_rect_collisions(&circles, &rects);
+        assert_eq!(collisions.len(), 2);
+        
+        let corner = &collisions[0];
+        assert_eq!((corner.idx_a, corner.idx_b), (0, 0));
+        assert_eq!(corner.contact, Vec2::new(6.0, 4.0));
+        assert!(corner.normal.x < Scalar::ZERO && corner.normal.y < Scalar::ZERO);
+        
+        let inside = &collisions[1];
+        assert_eq!(inside.idx_a, 1);
+        assert_eq!(inside.normal, Vec2::UNIT_Y);
+        assert_eq!(inside.depth, Scalar::from_float(0.75));
+        assert_eq!(inside.contact, Vec2::new(4.0, 2.0));
+    }
+    
+    #[test]
+    fn test_swept_collision_time_of_impact() {
+        let mut fast = Circle::new(Vec2::new(8.0, 5.0), Scalar::HALF, Scalar::ONE);
+        fast.old_position = Vec2::new(2.0, 5.0);
+        let still = Circle::new(Vec2::new(6.0, 5.0), Scalar::HALF, Scalar::ONE);
+        let mut slow = Circle::new(Vec2::new(2.5, 8.0), Scalar::HALF, Scalar::ONE);
+        slow.old_position = Vec2::new(2.0, 8.0);
+        let circles = vec![fast, still, slow];
+        
+        let hits = detect_swept_collisions(&circles, &[(0, 1), (0, 2), (1, 2)]);
+        
+        // Touching once the gap of 4 has closed to 1: 3 of the 6 travelled
+        assert_eq!(hits.len(), 1);
+        let hit = &hits[0];
+        assert_eq!((hit.collision.idx_a, hit.collision.idx_b), (0, 1));
+        assert!((hit.toi - Scalar::from_float(0.5)).abs() < Scalar::from_float(0.001), "toi {}", hit.toi);
+        assert_eq!(hit.collision.normal, Vec2::UNIT_X);
+        assert_eq!(hit.collision.depth, Scalar::ZERO);
+    }
+    
+    #[test]
+    fn test_aabb_grid_pairs_every_overlapping_box() {
+        // Swept bounds: long boxes for fast circles, small ones for slow
+        let aabbs: Vec<Aabb> = (0..60)
+            .map(|i| {
+                let min = Vec2::new((i % 10) as f32 * 4.5 + 0.5, (i / 10) as f32 * 6.0 + 0.5);
+                let reach = if i % 7 == 0 { Vec2::new(18.0, 3.0) } else { Vec2::new(1.0, 1.0) };
+                Aabb::new(min, min + reach)
+            })
+            .collect();
+        let size = Scalar::from_float(60.0);
+        let grid = SpatialGrid::build_aabbs(&aabbs, Scalar::from_float(2.0), Scalar::from_float(2.0), size, size);
+        let mut pairs = grid.get_collision_pairs();
+        pairs.sort_unstable();
+        
+        // Every overlapping pair is a candidate, without enumerating them all
+        let overlaps = |a: &Aabb, b: &Aabb| {
+            a.min.x <= b.max.x && b.min.x <= a.max.x && a.min.y <= b.max.y && b.min.y <= a.max.y
+        };
+        let mut expected = Vec::new();
+        for a in 0..aabbs.len() {
+            for b in a + 1..aabbs.len() {
+                if overlaps(&aabbs[a], &aabbs[b]) {
+                    expected.push((a, b));
+                    assert!(pairs.contains(&(a, b)), "boxes {} and {} missing", a, b);
+                }
+            }
+        }
+        assert!(!expected.is_empty());
+        assert!(pairs.len() < aabbs.len() * (aabbs.len() - 1) / 2, "no pruning: {} pairs", pairs.len());
+    }
+    
+    #[test]
+    fn test_aabb_grid_clamps_long_sweeps_to_the_world() {
+        // A body flung far past the corner of a 20 m world, and one at rest
+        // beside the wall it crossed
+        let aabbs = [
+            Aabb::new(Vec2::new(10.0, 10.0), Vec2::new(5000.0, 5000.0)),
+            Aabb::new(Vec2::new(18.0, 12.0), Vec2::new(20.0, 14.0)),
+            Aabb::new(Vec2::new(2.0, 2.0), Vec2::new(3.0, 3.0)),
+        ];
+        let size = Scalar::from_float(20.0);
+        let grid = SpatialGrid::build_aabbs(&aabbs, Scalar::from_float(2.0), Scalar::from_float(2.0), size, size);
+        
+        // Only the in-world part of the long sweep, 6 x 6 cells, is entered
+        let entries: usize = grid.occupancy().iter().map(|(_, count)| count).sum();
+        assert_eq!(entries, 6 * 6 + 2 * 2 + 1);
+        assert!(grid.debug_dump().iter().all(|(cell, _)| (0..=10).contains(&cell.x) && (0..=10).contains(&cell.y)));
+        assert_eq!(grid.get_collision_pairs(), vec![(0, 1)]);
+    }
+}
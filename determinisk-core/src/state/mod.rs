@@ -1,25 +1,62 @@
 //! State management and serialization
 
 #[cfg(not(feature = "std"))]
-use alloc::vec::Vec;
+use alloc::{string::String, vec::Vec};
 #[cfg(feature = "std")]
 use std::vec::Vec;
 
 use serde::{Serialize, Deserialize};
-use crate::{World, Scalar};
+use crate::{World, Circle, Integrator, Scalar, Vec2};
 
 /// Simulation state snapshot
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SimulationState {
     pub step: u64,
     pub time: f32,
     pub circles: Vec<CircleState>,
     pub frame_collisions: u32,
     pub frame_boundary_hits: u32,
+    /// Total kinetic energy, see `World::calculate_kinetic_energy`
+    #[serde(default)]
+    pub kinetic_energy: f32,
+    /// Total potential energy, see `World::calculate_potential_energy`
+    #[serde(default)]
+    pub potential_energy: f32,
+}
+
+/// Equality of f32 slices by bit pattern, so 0.0 != -0.0 and NaN == NaN
+fn f32_bits_eq(a: &[f32], b: &[f32]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).all(|(x, y)| x.to_bits() == y.to_bits())
+}
+
+impl SimulationState {
+    /// Bit-exact comparison of every field, f32s by `to_bits()`
+    pub fn bit_eq(&self, other: &SimulationState) -> bool {
+        self.step == other.step
+            && f32_bits_eq(
+                &[self.time, self.kinetic_energy, self.potential_energy],
+                &[other.time, other.kinetic_energy, other.potential_energy],
+            )
+            && self.frame_collisions == other.frame_collisions
+            && self.frame_boundary_hits == other.frame_boundary_hits
+            && self.circles.len() == other.circles.len()
+            && self.circles.iter().zip(&other.circles).all(|(a, b)| a.bit_eq(b))
+    }
+    
+    /// Index of the circle containing `point`, if any
+    ///
+    /// Later circles are drawn on top, so overlaps resolve to the highest index.
+    pub fn circle_at_point(&self, point: [f32; 2]) -> Option<usize> {
+        self.circles.iter().rposition(|circle| {
+            let dx = point[0] - circle.position[0];
+            let dy = point[1] - circle.position[1];
+            dx * dx + dy * dy <= circle.radius * circle.radius
+        })
+    }
 }
 
 /// State of a single circle
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CircleState {
     pub position: [f32; 2],
     pub velocity: [f32; 2],
@@ -27,13 +64,58 @@ pub struct CircleState {
     pub mass: f32,
 }
 
+impl CircleState {
+    /// Bit-exact comparison of every field, f32s by `to_bits()`
+    pub fn bit_eq(&self, other: &CircleState) -> bool {
+        f32_bits_eq(&self.position, &other.position)
+            && f32_bits_eq(&self.velocity, &other.velocity)
+            && f32_bits_eq(&[self.radius, self.mass], &[other.radius, other.mass])
+    }
+    
+    /// Rebuild a circle from this snapshot (e.g. to rebuild a spatial grid)
+    ///
+    /// The snapshot stores `f32` values, so the result is only as exact as the
+    /// recorded trace; the Verlet history starts at rest.
+    pub fn to_circle(&self) -> Circle {
+        let mut circle = Circle::new(
+            Vec2::new(self.position[0], self.position[1]),
+            Scalar::from_float(self.radius),
+            Scalar::from_float(self.mass),
+        );
+        circle.velocity = Vec2::new(self.velocity[0], self.velocity[1]);
+        circle
+    }
+}
+
 /// Input configuration for a simulation
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SimulationInput {
+    // Metadata for scenario libraries; ignored by the physics
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub author: Option<String>,
+    
     // World configuration
     pub world_width: f32,
     pub world_height: f32,
     pub gravity: [f32; 2],
+    /// Positions, velocities and gravity are in screen coordinates (y grows
+    /// downward from the top edge) instead of the engine's y-up convention
+    ///
+    /// The world converts on the way in and recorded states are reported back
+    /// in the same convention. The proof journal commits engine coordinates.
+    #[serde(default)]
+    pub y_down: bool,
+    /// Integration timestep in seconds
+    ///
+    /// This is part of the physics, not just a frame rate: Verlet integration
+    /// and gravity scale with `timestep`, so the same scene with a different
+    /// timestep follows a (slightly) different trajectory, and larger steps
+    /// raise the risk of fast bodies tunneling through each other. Use
+    /// `recommended_steps_for_duration` to convert a duration into `num_steps`.
     pub timestep: f32,
     
     // Physics configuration
@@ -41,6 +123,10 @@ pub struct SimulationInput {
     pub restitution: f32,  // Coefficient of restitution (0.0-1.0)
     #[serde(default = "default_position_correction")]
     pub position_correction: f32,  // Position correction factor
+    /// Named `CollisionConfig` preset; overrides `restitution` and
+    /// `position_correction` when set
+    #[serde(default)]
+    pub collision_preset: Option<String>,
     
     // Objects
     pub circles: Vec<CircleConfig>,
@@ -49,7 +135,328 @@ pub struct SimulationInput {
     pub num_steps: u32,
     pub record_trajectory: bool,
     pub seed: u64,  // For deterministic randomness (0 = no seed)
+    /// Commit the state hash every this many steps to the proof journal
+    /// (0 = final state only), so verifiers can spot-check the trajectory
+    #[serde(default)]
+    pub checkpoint_interval: u32,
+}
+
+/// Segment sizes `recommended_segment_po2` picks from
+///
+/// A subset of RISC Zero's valid `segment_limit_po2` range (13..=24).
+pub const RECOMMENDED_SEGMENT_PO2: core::ops::RangeInclusive<u32> = 16..=20;
+
+/// Approximate guest cycles per body per step, as a power of two
+const CYCLES_PER_BODY_STEP_PO2: u32 = 10;
+
+/// Fixed part of a proof's size, before public outputs
+const PROOF_OVERHEAD_BYTES: u64 = 1024;
+
+/// Size of the state hash committed with every proof (SHA-256)
+const STATE_HASH_BYTES: u64 = 32;
+
+/// Up-front cost of running and proving an input, found without stepping
+///
+/// See `SimulationInput::estimate_cost`. Like `estimated_cycles`, these are
+/// heuristics good to within a small factor.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CostEstimate {
+    /// Steps to simulate
+    pub steps: u32,
+    /// Number of circles
+    pub bodies: usize,
+    /// Narrow-phase checks over the run if every pair were tested each step
+    pub est_collision_checks: u64,
+    /// Guest cycles, as `SimulationInput::estimated_cycles`
+    pub est_cycles: u64,
+    /// Proof size in KB: overhead, final positions, step count and state hash
+    pub est_proof_size_kb: f32,
+}
+
+/// An input field held NaN or an infinity
+///
+/// Non-finite floats have no Q16.16 value, so they are rejected at load time
+/// rather than turned into platform-dependent garbage by the conversion.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NonFiniteInput {
+    /// Offending field, e.g. `"gravity"` or `"position"`
+    pub field: &'static str,
+    /// Index of the offending circle, for per-circle fields
+    pub circle: Option<usize>,
+}
+
+impl core::fmt::Display for NonFiniteInput {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self.circle {
+            Some(index) => write!(f, "circles[{}].{} is not finite", index, self.field),
+            None => write!(f, "{} is not finite", self.field),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for NonFiniteInput {}
+
+/// Why `SimulationInput::validate` rejected an input
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValidationError {
+    /// A field held NaN or an infinity
+    NonFinite(NonFiniteInput),
+    /// A field that must be positive was zero or negative: `timestep`,
+    /// `world_width`, `world_height`, or a circle's `radius` or `mass`
+    NotPositive {
+        field: &'static str,
+        /// Index of the offending circle, for per-circle fields
+        circle: Option<usize>,
+    },
+    /// A circle's center lies outside the world
+    OutOfBounds { circle: usize },
+    /// More than `MAX_CIRCLES` circles, from `merge`
+    TooManyCircles(crate::TooManyCircles),
+}
+
+impl core::fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ValidationError::NonFinite(err) => write!(f, "{}", err),
+            ValidationError::NotPositive { field, circle: Some(index) } => {
+                write!(f, "circles[{}].{} must be positive", index, field)
+            }
+            ValidationError::NotPositive { field, circle: None } => write!(f, "{} must be positive", field),
+            ValidationError::OutOfBounds { circle } => {
+                write!(f, "circles[{}].position is outside the world", circle)
+            }
+            ValidationError::TooManyCircles(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ValidationError {}
+
+impl From<NonFiniteInput> for ValidationError {
+    fn from(err: NonFiniteInput) -> Self {
+        ValidationError::NonFinite(err)
+    }
+}
+
+impl From<crate::TooManyCircles> for ValidationError {
+    fn from(err: crate::TooManyCircles) -> Self {
+        ValidationError::TooManyCircles(err)
+    }
+}
+
+impl SimulationInput {
+    /// Check that the input can be simulated as given
+    ///
+    /// Beyond `check_finite`: the timestep, world size and every circle's
+    /// radius and mass must be positive, and every circle's center must lie
+    /// within the world (edges included). Reports the first problem, world
+    /// settings before circles.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        self.check_finite()?;
+        
+        let not_positive = |field, circle| ValidationError::NotPositive { field, circle };
+        for (field, value) in [("timestep", self.timestep), ("world_width", self.world_width), ("world_height", self.world_height)] {
+            if value <= 0.0 {
+                return Err(not_positive(field, None));
+            }
+        }
+        for (index, circle) in self.circles.iter().enumerate() {
+            if circle.radius <= 0.0 {
+                return Err(not_positive("radius", Some(index)));
+            }
+            if circle.mass <= 0.0 {
+                return Err(not_positive("mass", Some(index)));
+            }
+            let [x, y] = circle.position;
+            if !(0.0..=self.world_width).contains(&x) || !(0.0..=self.world_height).contains(&y) {
+                return Err(ValidationError::OutOfBounds { circle: index });
+            }
+        }
+        Ok(())
+    }
+    
+    /// Check that every float in the input is finite
+    ///
+    /// Reports the first NaN or infinite field, world settings before circles.
+    pub fn check_finite(&self) -> Result<(), NonFiniteInput> {
+        let finite = |values: &[f32]| values.iter().all(|v| v.is_finite());
+        let world_fields: [(&'static str, &[f32]); 6] = [
+            ("world_width", &[self.world_width]),
+            ("world_height", &[self.world_height]),
+            ("gravity", &self.gravity),
+            ("timestep", &[self.timestep]),
+            ("restitution", &[self.restitution]),
+            ("position_correction", &[self.position_correction]),
+        ];
+        if let Some(&(field, _)) = world_fields.iter().find(|(_, values)| !finite(values)) {
+            return Err(NonFiniteInput { field, circle: None });
+        }
+        for (index, circle) in self.circles.iter().enumerate() {
+            let circle_fields: [(&'static str, &[f32]); 5] = [
+                ("position", &circle.position),
+                ("velocity", &circle.velocity),
+                ("radius", &[circle.radius]),
+                ("mass", &[circle.mass]),
+                ("angular_velocity", &[circle.angular_velocity]),
+            ];
+            if let Some(&(field, _)) = circle_fields.iter().find(|(_, values)| !finite(values)) {
+                return Err(NonFiniteInput { field, circle: Some(index) });
+            }
+        }
+        Ok(())
+    }
+    
+    /// Number of steps needed to simulate `seconds` at this input's timestep
+    pub fn recommended_steps_for_duration(&self, seconds: f32) -> u32 {
+        // Round to nearest; f32 division of e.g. 2.0 / (1/60) lands just below 120
+        (seconds / self.timestep + 0.5) as u32
+    }
+    
+    /// Append `other`'s circles to this input, e.g. a rack file plus a cue file
+    ///
+    /// Positions are absolute, so circles are copied as-is; everything else
+    /// (world size, gravity, timestep, collision config) stays this input's.
+    /// Fails without modifying `self` if the combined input would exceed
+    /// `MAX_CIRCLES` or fail `validate` (e.g. a fragment circle outside this
+    /// world).
+    pub fn merge(&mut self, other: &SimulationInput) -> Result<(), ValidationError> {
+        let count = self.circles.len() + other.circles.len();
+        if count > crate::MAX_CIRCLES {
+            return Err(crate::TooManyCircles { count }.into());
+        }
+        let own = self.circles.len();
+        self.circles.extend(other.circles.iter().cloned());
+        if let Err(err) = self.validate() {
+            self.circles.truncate(own);
+            return Err(err);
+        }
+        Ok(())
+    }
     
+    /// Stable fingerprint of everything that affects the simulation
+    ///
+    /// SHA-256 over the numeric fields (and collision preset) in declaration
+    /// order, circles in order, little-endian. Floats are hashed by bit
+    /// pattern after normalizing `-0.0` to `0.0` and any NaN to one canonical
+    /// NaN, so equal values always hash equally. Metadata and
+    /// `record_trajectory` don't change the physics and are left out, so they
+    /// don't invalidate cached proofs. `checkpoint_interval` is included: it
+    /// changes the committed journal.
+    pub fn hash(&self) -> [u8; 32] {
+        use sha2::{Sha256, Digest};
+        
+        fn canonical_bits(value: f32) -> u32 {
+            if value.is_nan() {
+                f32::NAN.to_bits()
+            } else if value == 0.0 {
+                0
+            } else {
+                value.to_bits()
+            }
+        }
+        
+        let mut hasher = Sha256::new();
+        let mut floats = |values: &[f32]| {
+            for &value in values {
+                hasher.update(canonical_bits(value).to_le_bytes());
+            }
+        };
+        floats(&[self.world_width, self.world_height]);
+        floats(&self.gravity);
+        floats(&[self.timestep, self.restitution, self.position_correction]);
+        for circle in &self.circles {
+            floats(&circle.position);
+            floats(&circle.velocity);
+            floats(&[circle.radius, circle.mass, circle.angular_velocity]);
+        }
+        
+        hasher.update([u8::from(self.y_down)]);
+        match &self.collision_preset {
+            Some(preset) => {
+                hasher.update([1]);
+                hasher.update((preset.len() as u64).to_le_bytes());
+                hasher.update(preset.as_bytes());
+            }
+            None => hasher.update([0]),
+        }
+        hasher.update((self.circles.len() as u64).to_le_bytes());
+        hasher.update(self.num_steps.to_le_bytes());
+        hasher.update(self.seed.to_le_bytes());
+        hasher.update(self.checkpoint_interval.to_le_bytes());
+        hasher.finalize().into()
+    }
+    
+    /// Rough guest cycle count for proving this input
+    ///
+    /// Body count × steps, scaled by the approximate per body-step cost. Only
+    /// good to within a small factor; real counts come from the prover.
+    pub fn estimated_cycles(&self) -> u64 {
+        let work = (self.circles.len() as u64).max(1) * u64::from(self.num_steps.max(1));
+        work << CYCLES_PER_BODY_STEP_PO2
+    }
+    
+    /// Estimate the cost of running and proving this input, instantly
+    ///
+    /// Nothing is simulated, so this is cheap enough to call before deciding
+    /// whether to start a long run or prove.
+    pub fn estimate_cost(&self) -> CostEstimate {
+        let bodies = self.circles.len();
+        let pairs_per_step = (bodies as u64) * (bodies as u64).saturating_sub(1) / 2;
+        // Final positions (2 × 4 bytes per body) plus the step count
+        let public_outputs = bodies as u64 * 8 + 4;
+        let proof_bytes = PROOF_OVERHEAD_BYTES + public_outputs + STATE_HASH_BYTES;
+        
+        CostEstimate {
+            steps: self.num_steps,
+            bodies,
+            est_collision_checks: pairs_per_step * u64::from(self.num_steps),
+            est_cycles: self.estimated_cycles(),
+            est_proof_size_kb: proof_bytes as f32 / 1024.0,
+        }
+    }
+    
+    /// Segment size (as a power of two) suited to this input's proving cost
+    ///
+    /// Takes `estimated_cycles` and picks the smallest segment that would
+    /// hold them, clamped to `RECOMMENDED_SEGMENT_PO2`. Small scenes get
+    /// small segments, keeping prover memory down on small GPUs; large
+    /// scenes cap out at the 6GB-friendly default of 20.
+    pub fn recommended_segment_po2(&self) -> u32 {
+        // ceil(log2(cycles))
+        let cycles_po2 = u64::BITS - (self.estimated_cycles() - 1).leading_zeros();
+        cycles_po2
+            .clamp(*RECOMMENDED_SEGMENT_PO2.start(), *RECOMMENDED_SEGMENT_PO2.end())
+    }
+    
+    /// Run the full simulation and build the journal a guest would commit
+    ///
+    /// Every zkVM guest and the mock backend go through this, so the
+    /// collision config (restitution, position correction, preset) is applied
+    /// identically and committed outputs agree across backends.
+    ///
+    /// # Panics
+    ///
+    /// If the input has more than `MAX_CIRCLES` circles, before any stepping.
+    pub fn execute_journal(&self) -> JournalOutput {
+        let mut world = match World::try_from_input(self) {
+            Ok(world) => world,
+            Err(e) => panic!("{}", e),
+        };
+        let initial = world.conservation_bits();
+        let mut checkpoints = Vec::new();
+        for step in 1..=self.num_steps {
+            world.step();
+            if self.checkpoint_interval > 0 && step.is_multiple_of(self.checkpoint_interval) {
+                checkpoints.push((step, world.state_hash()));
+            }
+        }
+        JournalOutput {
+            checkpoints,
+            ..world.journal_output(self.num_steps, initial)
+        }
+    }
 }
 
 fn default_restitution() -> f32 {
@@ -67,6 +474,10 @@ pub struct CircleConfig {
     pub velocity: [f32; 2],
     pub radius: f32,
     pub mass: f32,
+    /// Initial spin in radians per second, counterclockwise (clockwise
+    /// on screen for `y_down` inputs)
+    #[serde(default)]
+    pub angular_velocity: f32,
 }
 
 /// Output of a simulation
@@ -84,6 +495,12 @@ pub struct SimulationMetrics {
     pub max_velocity: f32,
     pub collision_count: u32,
     pub boundary_hits: u32,
+    /// Effective gravity vector the energy numbers were computed under
+    #[serde(default)]
+    pub gravity: [f32; 2],
+    /// World width and height
+    #[serde(default)]
+    pub world_size: [f32; 2],
 }
 
 /// Complete trace of a simulation including all intermediate states
@@ -94,45 +511,281 @@ pub struct SimulationTrace {
     pub output: SimulationOutput,
 }
 
+impl SimulationTrace {
+    /// Bit-exact comparison of all states and the output, f32s by `to_bits()`
+    ///
+    /// The input is not compared; two traces of different inputs that
+    /// produce identical states are equal.
+    pub fn bit_eq(&self, other: &SimulationTrace) -> bool {
+        let (a, b) = (&self.output, &other.output);
+        let metrics_eq = f32_bits_eq(
+            &[a.metrics.total_energy, a.metrics.max_velocity],
+            &[b.metrics.total_energy, b.metrics.max_velocity],
+        ) && a.metrics.collision_count == b.metrics.collision_count
+            && a.metrics.boundary_hits == b.metrics.boundary_hits
+            && f32_bits_eq(&a.metrics.gravity, &b.metrics.gravity)
+            && f32_bits_eq(&a.metrics.world_size, &b.metrics.world_size);
+        
+        self.states.len() == other.states.len()
+            && self.states.iter().zip(&other.states).all(|(a, b)| a.bit_eq(b))
+            && a.final_state.bit_eq(&b.final_state)
+            && a.steps_executed == b.steps_executed
+            && metrics_eq
+    }
+    
+    /// Wrap for `==` comparison with `bit_eq` semantics (e.g. in `assert_eq!`)
+    pub fn bit_exact(&self) -> BitExact<'_> {
+        BitExact(self)
+    }
+}
+
+/// A trace compared bit-exactly by `PartialEq`; see `SimulationTrace::bit_eq`
+#[derive(Debug, Clone, Copy)]
+pub struct BitExact<'a>(pub &'a SimulationTrace);
+
+impl PartialEq for BitExact<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.bit_eq(other.0)
+    }
+}
+
+/// Total momentum and energy as fixed-point bits
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConservationBits {
+    /// Total momentum (x, y)
+    pub momentum: (i32, i32),
+    /// Total kinetic + potential energy
+    pub energy: i32,
+}
+
+/// Public output committed to the zkVM journal by the guest programs
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JournalOutput {
+    /// Final positions of all circles (fixed-point bit representation)
+    pub final_positions: Vec<(i32, i32)>,
+    /// Number of steps executed
+    pub steps_executed: u32,
+    /// Hash of final world state
+    pub state_hash: [u8; 32],
+    /// Momentum and energy before the first step
+    pub initial_conservation: ConservationBits,
+    /// Momentum and energy after the last step
+    pub final_conservation: ConservationBits,
+    /// `(step, state_hash)` after every `checkpoint_interval` steps, in order
+    #[serde(default)]
+    pub checkpoints: Vec<(u32, [u8; 32])>,
+}
+
+/// Lazily steps a world, yielding one state per step
+///
+/// The first item is the initial state (step 0), matching the first entry of
+/// `run_with_recording`. The iterator never ends; bound it with `take`.
+pub struct StateIterator<'a> {
+    world: &'a mut World,
+    step: u64,
+}
+
+impl Iterator for StateIterator<'_> {
+    type Item = SimulationState;
+    
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.step > 0 {
+            self.world.step();
+        }
+        let state = self.world.capture_state(self.step);
+        self.step += 1;
+        Some(state)
+    }
+}
+
 impl World {
+    /// Iterate over simulation states without recording the whole trace
+    pub fn states(&mut self) -> StateIterator<'_> {
+        StateIterator {
+            world: self,
+            step: 0,
+        }
+    }
+    
+    /// Hash of the current circle positions
+    ///
+    /// Chains SHA-256 over each circle's position bits in index order. This is
+    /// the `state_hash` committed by the guest, so a native run can be compared
+    /// against a proof's journal directly.
+    pub fn state_hash(&self) -> [u8; 32] {
+        use sha2::{Sha256, Digest};
+        
+        let mut hash: [u8; 32] = Sha256::digest(b"").into();
+        for circle in &self.circles {
+            let mut hasher = Sha256::new();
+            hasher.update(hash);
+            hasher.update(circle.position.x.to_bits().to_le_bytes());
+            hasher.update(circle.position.y.to_bits().to_le_bytes());
+            hash = hasher.finalize().into();
+        }
+        hash
+    }
+    
+    /// Total momentum and energy of the current state as bits
+    pub fn conservation_bits(&self) -> ConservationBits {
+        let momentum = self.calculate_total_momentum();
+        ConservationBits {
+            momentum: (momentum.x.to_bits(), momentum.y.to_bits()),
+            energy: self.calculate_total_energy().to_bits(),
+        }
+    }
+    
+    /// Build the journal output for the current state
+    ///
+    /// `initial` is the world's `conservation_bits()` captured before stepping.
+    pub fn journal_output(&self, steps_executed: u32, initial: ConservationBits) -> JournalOutput {
+        JournalOutput {
+            final_positions: self.circles
+                .iter()
+                .map(|c| (c.position.x.to_bits(), c.position.y.to_bits()))
+                .collect(),
+            steps_executed,
+            state_hash: self.state_hash(),
+            initial_conservation: initial,
+            final_conservation: self.conservation_bits(),
+            checkpoints: Vec::new(),
+        }
+    }
+    
+    /// A position in the input's coordinate convention (see `SimulationInput::y_down`)
+    fn external_position(&self, p: Vec2) -> [f32; 2] {
+        if self.y_down {
+            [p.x.to_float(), (self.bounds.y - p.y).to_float()]
+        } else {
+            [p.x.to_float(), p.y.to_float()]
+        }
+    }
+    
+    /// An angular velocity in the input's coordinate convention
+    fn external_spin(&self, w: Scalar) -> f32 {
+        if self.y_down { (-w).to_float() } else { w.to_float() }
+    }
+    
+    /// A velocity or acceleration in the input's coordinate convention
+    fn external_vector(&self, v: Vec2) -> [f32; 2] {
+        if self.y_down {
+            [v.x.to_float(), (-v.y).to_float()]
+        } else {
+            [v.x.to_float(), v.y.to_float()]
+        }
+    }
+    
     /// Capture current state as a snapshot
+    ///
+    /// Frame collision and boundary counts are those resolved by the most
+    /// recent `step` (zero before the first step).
     pub fn capture_state(&self, step: u64) -> SimulationState {
-        // Count current collisions and boundary hits
-        let collisions = self.detect_collisions();
-        let boundary_hits = self.detect_boundary_collisions();
+        // Contacts resolved by the step that produced this state
+        let contacts = self.last_contacts();
+        let (kinetic_energy, potential_energy) = self.frame_energies();
         
         SimulationState {
             step,
             time: (step as f32) * self.timestep.to_float(),
             circles: self.circles.iter().map(|c| CircleState {
-                position: [c.position.x.to_float(), c.position.y.to_float()],
-                velocity: [c.velocity.x.to_float(), c.velocity.y.to_float()],
+                position: self.external_position(c.position),
+                velocity: self.external_vector(c.velocity),
                 radius: c.radius.to_float(),
                 mass: c.mass.to_float(),
             }).collect(),
-            frame_collisions: collisions.len() as u32,
-            frame_boundary_hits: boundary_hits.len() as u32,
+            frame_collisions: contacts.pairs.len() as u32,
+            frame_boundary_hits: contacts.boundary_hits,
+            kinetic_energy,
+            potential_energy,
         }
     }
     
+    /// Kinetic and potential energy of a recorded frame, and of the final metrics
+    ///
+    /// The sums of `calculate_kinetic_energy` and
+    /// `calculate_potential_energy`, taken in f32 over `to_float()` values:
+    /// every frame is captured, and a heavy or high body would overflow the
+    /// Q16.16 sums mid-run.
+    fn frame_energies(&self) -> (f32, f32) {
+        let g = -self.gravity.y.to_float();
+        self.circles.iter().fold((0.0, 0.0), |(kinetic, potential), circle| {
+            let mass = circle.mass.to_float();
+            let velocity = self.metric_velocity(circle);
+            let (vx, vy) = (velocity.x.to_float(), velocity.y.to_float());
+            (
+                kinetic + 0.5 * mass * (vx * vx + vy * vy),
+                potential + mass * g * circle.position.y.to_float(),
+            )
+        })
+    }
+    
     /// Run simulation with trajectory recording
     pub fn run_with_recording(&mut self, num_steps: u32) -> SimulationTrace {
-        let input = SimulationInput {
+        self.run_trace(num_steps, true)
+    }
+    
+    /// Run simulation keeping only the final state
+    ///
+    /// Produces the same output and metrics as `run_with_recording`, but the
+    /// trace's `states` holds just the final state, so memory stays constant
+    /// regardless of `num_steps`.
+    pub fn simulate(&mut self, num_steps: u32) -> SimulationTrace {
+        self.run_trace(num_steps, false)
+    }
+    
+    /// Stream states to `writer` as NDJSON, one `SimulationState` per line
+    ///
+    /// Writes the same `num_steps + 1` states as `run_with_recording` (step 0
+    /// first) but never holds more than one in memory, so recordings can be
+    /// arbitrarily long. Wrap unbuffered writers in a `BufWriter`.
+    #[cfg(feature = "std")]
+    pub fn record_to_writer<W: std::io::Write>(&mut self, num_steps: u32, mut writer: W) -> std::io::Result<()> {
+        for state in self.states().take(num_steps as usize + 1) {
+            serde_json::to_writer(&mut writer, &state)?;
+            writer.write_all(b"\n")?;
+        }
+        writer.flush()
+    }
+    
+    /// Export the current state as an input for a new run of `num_steps`
+    ///
+    /// World settings and circles are taken as they are now, in the world's
+    /// coordinate convention, so the result can be saved with `to_toml_file`
+    /// (e.g. a settled rack). Collision config is written out as explicit
+    /// `restitution`/`position_correction` rather than a preset. Velocities
+    /// come from the cached velocity, so the Verlet history is rebuilt on
+    /// load and positions are only as exact as `f32` can hold them.
+    pub fn to_input(&self, num_steps: u32) -> SimulationInput {
+        SimulationInput {
+            name: None,
+            description: None,
+            author: None,
             world_width: self.bounds.x.to_float(),
             world_height: self.bounds.y.to_float(),
-            gravity: [self.gravity.x.to_float(), self.gravity.y.to_float()],
+            gravity: self.external_vector(self.gravity),
+            y_down: self.y_down,
             timestep: self.timestep.to_float(),
             restitution: self.collision_config.restitution.to_float(),
             position_correction: self.collision_config.position_correction.to_float(),
+            collision_preset: None,
             circles: self.circles.iter().map(|c| CircleConfig {
-                position: [c.position.x.to_float(), c.position.y.to_float()],
-                velocity: [c.velocity.x.to_float(), c.velocity.y.to_float()],
+                position: self.external_position(c.position),
+                velocity: self.external_vector(c.velocity),
                 radius: c.radius.to_float(),
                 mass: c.mass.to_float(),
+                angular_velocity: self.external_spin(c.angular_velocity),
             }).collect(),
             num_steps,
             record_trajectory: true,
             seed: 0,
+            checkpoint_interval: 0,
+        }
+    }
+    
+    fn run_trace(&mut self, num_steps: u32, record: bool) -> SimulationTrace {
+        let input = SimulationInput {
+            record_trajectory: record,
+            ..self.to_input(num_steps)
         };
         
         let mut states = Vec::new();
@@ -141,12 +794,16 @@ impl World {
         let mut boundary_hits = 0u32;
         
         // Record initial state
-        states.push(self.capture_state(0));
+        if record {
+            states.push(self.capture_state(0));
+        }
         
         // Run simulation and record each step
         for step in 1..=num_steps {
             self.step();
-            states.push(self.capture_state(step as u64));
+            if record {
+                states.push(self.capture_state(step as u64));
+            }
             
             // Update metrics
             for circle in &self.circles {
@@ -158,17 +815,19 @@ impl World {
                 }
             }
             
-            // Count collisions (simplified - would need proper event tracking)
-            let collisions = self.detect_collisions();
-            collision_count += collisions.len() as u32;
-            
-            // Count boundary hits
-            let boundary_collisions = self.detect_boundary_collisions();
-            boundary_hits += boundary_collisions.len() as u32;
+            // Count the contacts this step resolved (no second broad phase)
+            let contacts = self.last_contacts();
+            collision_count += contacts.pairs.len() as u32;
+            boundary_hits += contacts.boundary_hits;
         }
         
         // Calculate total energy
-        let total_energy = self.calculate_total_energy().to_float();
+        let (kinetic_energy, potential_energy) = self.frame_energies();
+        let total_energy = kinetic_energy + potential_energy;
+        
+        if !record {
+            states.push(self.capture_state(num_steps as u64));
+        }
         
         let output = SimulationOutput {
             final_state: states.last().unwrap().clone(),
@@ -178,6 +837,8 @@ impl World {
                 max_velocity,
                 collision_count,
                 boundary_hits,
+                gravity: [self.gravity.x.to_float(), self.gravity.y.to_float()],
+                world_size: [self.bounds.x.to_float(), self.bounds.y.to_float()],
             },
         };
         
@@ -188,47 +849,64 @@ impl World {
         }
     }
     
-    /// Helper to detect collisions (for metrics)
+    /// Detect currently overlapping pairs with a fresh broad phase
+    ///
+    /// Stepping already records this; prefer `last_contacts` after `step`.
     pub fn detect_collisions(&self) -> Vec<(usize, usize)> {
         use crate::spatial::SpatialGrid;
         
-        let max_radius = self.circles.iter()
-            .map(|c| c.radius)
-            .max()
-            .unwrap_or(Scalar::from_float(1.0));
-        let cell_size = max_radius * Scalar::from_float(2.0);
-        
-        let grid = SpatialGrid::build(&self.circles, cell_size, self.bounds.x, self.bounds.y);
-        let pairs = grid.get_collision_pairs();
+        let (cell_width, cell_height) = self.collision_config.grid_cells.dimensions(&self.circles);
+        let grid = SpatialGrid::build_rect(&self.circles, cell_width, cell_height, self.bounds.x, self.bounds.y);
+        let mut pairs = grid.get_collision_pairs();
+        if let Some(filter) = &self.should_collide {
+            pairs.retain(|&(a, b)| filter.allows(a, b));
+        }
         
         let collisions = crate::spatial::detect_collisions(&self.circles, &pairs);
         collisions.iter().map(|c| (c.idx_a, c.idx_b)).collect()
     }
     
-    /// Helper to detect boundary collisions (for metrics)
-    fn detect_boundary_collisions(&self) -> Vec<usize> {
-        let boundary_collisions = crate::spatial::detect_boundary_collisions(
-            &self.circles,
-            self.bounds.x,
-            self.bounds.y,
-        );
-        boundary_collisions.iter().map(|c| c.idx).collect()
+    /// Velocity the momentum and energy metrics use for `circle`
+    ///
+    /// Velocity Verlet keeps `velocity` in sync with `position`; position
+    /// Verlet's velocity is the one its history encodes (see
+    /// `Circle::kinetic_energy`).
+    fn metric_velocity(&self, circle: &Circle) -> Vec2 {
+        match self.integrator {
+            Integrator::Verlet => circle.verlet_velocity(self.timestep),
+            Integrator::VelocityVerlet => circle.velocity(),
+        }
     }
     
-    /// Calculate total energy of the system
-    fn calculate_total_energy(&self) -> Scalar {
+    /// Calculate total momentum of the system
+    pub fn calculate_total_momentum(&self) -> Vec2 {
+        let mut total = Vec2::ZERO;
+        for circle in &self.circles {
+            total += self.metric_velocity(circle) * circle.mass;
+        }
+        total
+    }
+    
+    /// Total kinetic energy `sum(0.5 * m * v^2)`
+    pub fn calculate_kinetic_energy(&self) -> Scalar {
         let mut total = Scalar::ZERO;
         for circle in &self.circles {
-            // Kinetic energy: 0.5 * m * v^2
-            let v_squared = circle.velocity.x * circle.velocity.x + 
-                           circle.velocity.y * circle.velocity.y;
-            let kinetic = circle.mass * v_squared * Scalar::from_float(0.5);
-            
-            // Potential energy: m * g * h
-            let potential = circle.mass * (-self.gravity.y) * circle.position.y;
-            
-            total = total + kinetic + potential;
+            total = total + circle.mass * self.metric_velocity(circle).magnitude_squared() * Scalar::HALF;
         }
         total
     }
+    
+    /// Total potential energy `sum(m * g * h)`, with height measured from the floor
+    pub fn calculate_potential_energy(&self) -> Scalar {
+        let mut total = Scalar::ZERO;
+        for circle in &self.circles {
+            total = total + circle.mass * (-self.gravity.y) * circle.position.y;
+        }
+        total
+    }
+    
+    /// Calculate total energy of the system
+    pub fn calculate_total_energy(&self) -> Scalar {
+        self.calculate_kinetic_energy() + self.calculate_potential_energy()
+    }
 }
\ No newline at end of file
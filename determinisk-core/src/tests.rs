@@ -57,4 +57,1885 @@ mod integration_tests {
             world2.circles[0].position.y.to_bits()
         );
     }
-}
\ No newline at end of file
+    
+    #[test]
+    fn test_state_iterator_matches_recording() {
+        let make_world = || {
+            let mut world = World::new(100.0, 100.0);
+            let mut circle = Circle::new(
+                Vec2::new(20.0, 60.0),
+                Scalar::from_float(2.0),
+                Scalar::from_float(1.0),
+            );
+            circle.set_velocity(Vec2::new(5.0, 0.0), world.timestep);
+            world.add_circle(circle);
+            world
+        };
+        
+        let recorded = make_world().run_with_recording(20);
+        
+        let mut world = make_world();
+        let streamed: Vec<_> = world.states().take(10).collect();
+        
+        assert_eq!(streamed.len(), 10);
+        assert_eq!(streamed[..], recorded.states[..10]);
+    }
+    
+    #[test]
+    fn test_pool_preset_matches_pool_break() {
+        use crate::{CollisionConfig, scenarios};
+        
+        let input = scenarios::pool_break_15();
+        let world = World::from_input(&input);
+        let pool = CollisionConfig::preset("pool").unwrap();
+        
+        assert_eq!(input.collision_preset.as_deref(), Some("pool"));
+        assert_eq!(pool.restitution, Scalar::from_float(input.restitution));
+        assert_eq!(world.collision_config.restitution, pool.restitution);
+        assert_eq!(world.collision_config.position_correction, pool.position_correction);
+    }
+    
+    #[test]
+    fn test_recommended_steps_for_duration() {
+        let mut input = crate::scenarios::simple_drop();
+        
+        input.timestep = 1.0 / 60.0;
+        assert_eq!(input.recommended_steps_for_duration(2.0), 120);
+        assert_eq!(input.recommended_steps_for_duration(0.0), 0);
+        
+        input.timestep = 0.01;
+        assert_eq!(input.recommended_steps_for_duration(1.5), 150);
+    }
+    
+    #[test]
+    fn test_with_timestep_tunneling_risk() {
+        let mut circle = Circle::new(
+            Vec2::new(50.0, 50.0),
+            Scalar::from_float(0.5),
+            Scalar::ONE,
+        );
+        circle.set_velocity(Vec2::new(20.0, 0.0), Scalar::from_float(1.0 / 60.0));
+        
+        let mut world = World::new(100.0, 100.0);
+        world.add_circle(circle);
+        
+        // 20 m/s * 1/60 s = 0.33 m per step, under the 0.5 m radius
+        assert!(!world.tunneling_risk());
+        
+        // 20 m/s * 0.1 s = 2 m per step
+        let world = world.with_timestep(0.1);
+        assert!(world.tunneling_risk());
+        assert_eq!(world.timestep, Scalar::from_float(0.1));
+        assert_eq!(world.circles[0].velocity, Vec2::new(20.0, 0.0));
+    }
+    
+    #[test]
+    fn test_projectile_sweep() {
+        let angles = [15.0, 45.0, 75.0];
+        let input = crate::scenarios::projectile_sweep(30.0, &angles, [300.0, 150.0]);
+        
+        assert_eq!(input.circles.len(), angles.len());
+        assert_eq!((input.world_width, input.world_height), (300.0, 150.0));
+        for (circle, &angle) in input.circles.iter().zip(angles.iter()) {
+            let angle_rad = f32::to_radians(angle);
+            assert!((circle.velocity[0] - 30.0 * angle_rad.cos()).abs() < 1e-4);
+            assert!((circle.velocity[1] - 30.0 * angle_rad.sin()).abs() < 1e-4);
+        }
+        
+        // Launch points must not overlap
+        let gap = input.circles[1].position[0] - input.circles[0].position[0];
+        assert!(gap > 2.0 * input.circles[0].radius);
+    }
+    
+    #[test]
+    fn test_execute_journal_applies_collision_config() {
+        let mut input = crate::scenarios::pool_break();
+        input.collision_preset = Some("bouncy".into());
+        input.num_steps = 120;
+        
+        // Every backend commits this journal; it must match a native run of
+        // the same input built with the bouncy restitution
+        let journal = input.execute_journal();
+        
+        let mut world = World::from_input(&input);
+        let initial = world.conservation_bits();
+        for _ in 0..input.num_steps {
+            world.step();
+        }
+        assert_eq!(journal, world.journal_output(input.num_steps, initial));
+        assert_eq!(journal, input.execute_journal());
+        assert_eq!(world.collision_config.restitution, Scalar::ONE);
+    }
+    
+    #[test]
+    fn test_with_config_bounces_accordingly() {
+        use crate::CollisionConfig;
+        
+        // Peak height reached after the first bounce off the floor
+        fn rebound_height(world: &mut World) -> f32 {
+            let mut bounced = false;
+            let mut peak = 0.0f32;
+            for _ in 0..600 {
+                world.step();
+                let circle = &world.circles[0];
+                if !bounced && circle.velocity.y > Scalar::ZERO {
+                    bounced = true;
+                }
+                if bounced {
+                    peak = peak.max(circle.position.y.to_float());
+                }
+            }
+            peak
+        }
+        
+        let ball = Circle::new(Vec2::new(50.0, 50.0), Scalar::ONE, Scalar::ONE);
+        
+        let mut bouncy = World::with_config(100.0, 100.0, CollisionConfig::preset("bouncy").unwrap());
+        bouncy.add_circle(ball);
+        let mut sticky = World::new(100.0, 100.0);
+        sticky.set_collision_config(CollisionConfig::preset("sticky").unwrap());
+        sticky.add_circle(ball);
+        
+        let bouncy_peak = rebound_height(&mut bouncy);
+        let sticky_peak = rebound_height(&mut sticky);
+        
+        // Restitution 1.0 comes back close to the drop height, 0.1 barely leaves the floor
+        assert!(bouncy_peak > 45.0, "bouncy peak {}", bouncy_peak);
+        assert!(sticky_peak < 5.0, "sticky peak {}", sticky_peak);
+    }
+    
+    #[test]
+    fn test_metrics_report_gravity_and_world_size() {
+        let mut input = crate::scenarios::simple_drop();
+        input.gravity = [2.5, 0.0];
+        input.world_width = 120.0;
+        input.num_steps = 10;
+        
+        let trace = World::from_input(&input).simulate(input.num_steps);
+        let metrics = &trace.output.metrics;
+        
+        assert_eq!(metrics.gravity, [2.5, 0.0]);
+        assert_eq!(metrics.world_size, [120.0, input.world_height]);
+    }
+    
+    #[test]
+    fn test_velocity_consistency_detects_moved_position() {
+        let dt = Scalar::from_float(1.0 / 60.0);
+        let mut ball = Circle::new(
+            Vec2::new(50.0, 50.0),
+            Scalar::from_float(5.0),
+            Scalar::ONE,
+        );
+        assert!(ball.velocity_consistent(dt));
+        
+        ball.set_velocity(Vec2::new(3.0, -1.5), dt);
+        assert!(ball.velocity_consistent(dt));
+        
+        // Moving the circle afterwards changes the encoded velocity
+        ball.position = Vec2::new(60.0, 50.0);
+        assert!(!ball.velocity_consistent(dt));
+        
+        ball.set_velocity(Vec2::new(3.0, -1.5), dt);
+        assert!(ball.velocity_consistent(dt));
+        
+        // Velocities cached by stepping stay consistent
+        let mut world = World::new(100.0, 100.0);
+        world.add_circle(ball);
+        for _ in 0..30 {
+            world.step();
+            assert!(world.circles[0].velocity_consistent(world.timestep));
+        }
+    }
+    
+    #[test]
+    fn test_try_from_input_enforces_max_circles() {
+        use crate::{MAX_CIRCLES, TooManyCircles};
+        
+        let mut input = crate::scenarios::simple_drop();
+        let circle = input.circles[0].clone();
+        
+        input.circles = vec![circle.clone(); MAX_CIRCLES];
+        assert_eq!(World::try_from_input(&input).unwrap().circles.len(), MAX_CIRCLES);
+        
+        input.circles.push(circle);
+        let err = World::try_from_input(&input).unwrap_err();
+        assert_eq!(err, TooManyCircles { count: MAX_CIRCLES + 1 });
+    }
+    
+    #[test]
+    #[should_panic(expected = "at most")]
+    fn test_execute_journal_rejects_too_many_circles() {
+        let mut input = crate::scenarios::simple_drop();
+        let circle = input.circles[0].clone();
+        input.circles = vec![circle; crate::MAX_CIRCLES + 1];
+        input.execute_journal();
+    }
+    
+    #[test]
+    fn test_record_to_writer_round_trips_states() {
+        use crate::SimulationState;
+        
+        let input = crate::scenarios::three_body_collision();
+        let mut buffer = Vec::new();
+        World::from_input(&input).record_to_writer(50, &mut buffer).unwrap();
+        
+        let streamed: Vec<SimulationState> = std::str::from_utf8(&buffer)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        let recorded = World::from_input(&input).run_with_recording(50);
+        
+        assert_eq!(streamed.len(), 51);
+        assert_eq!(streamed, recorded.states);
+    }
+    
+    #[test]
+    fn test_set_restitution_mid_run() {
+        let mut constant = World::new(100.0, 100.0);
+        constant.add_circle(Circle::new(Vec2::new(50.0, 30.0), Scalar::ONE, Scalar::ONE));
+        let mut tweaked = constant.clone();
+        
+        // Identical up to the change
+        for _ in 0..20 {
+            constant.step();
+            tweaked.step();
+        }
+        constant.assert_bit_equal(&tweaked);
+        
+        tweaked.set_restitution(0.2);
+        assert_eq!(tweaked.circles[0].position, constant.circles[0].position);
+        
+        // Step until the first bounce, then compare rebound speeds
+        let mut rebound = None;
+        for _ in 0..300 {
+            constant.step();
+            tweaked.step();
+            if constant.circles[0].velocity.y > Scalar::ZERO {
+                rebound = Some((constant.circles[0].velocity.y, tweaked.circles[0].velocity.y));
+                break;
+            }
+        }
+        let (constant_speed, tweaked_speed) = rebound.expect("ball never bounced");
+        assert!(tweaked_speed < constant_speed);
+        assert!(tweaked_speed > Scalar::ZERO);
+    }
+    
+    #[test]
+    fn test_y_down_scene_mirrors_y_up_scene() {
+        let mut up = crate::scenarios::simple_drop();
+        up.circles[0].position = [30.0, 70.0];
+        up.circles[0].velocity = [4.0, 6.0];
+        up.num_steps = 400;
+        
+        // Same scene in screen coordinates: y measured from the top, down is +y
+        let mut down = up.clone();
+        down.y_down = true;
+        down.gravity = [0.0, 9.81];
+        down.circles[0].position = [30.0, up.world_height - 70.0];
+        down.circles[0].velocity = [4.0, -6.0];
+        
+        let up_trace = World::from_input(&up).run_with_recording(up.num_steps);
+        let down_trace = World::from_input(&down).run_with_recording(down.num_steps);
+        
+        // Includes bounces off the floor, which is the top of the screen frame
+        assert!(up_trace.output.metrics.boundary_hits > 0);
+        for (u, d) in up_trace.states.iter().zip(&down_trace.states) {
+            let (u, d) = (&u.circles[0], &d.circles[0]);
+            assert_eq!(d.position[0], u.position[0]);
+            assert_eq!(d.position[1], up.world_height - u.position[1]);
+            assert_eq!(d.velocity, [u.velocity[0], -u.velocity[1]]);
+        }
+        assert_eq!(down_trace.input.gravity, [0.0, -up_trace.input.gravity[1]]);
+    }
+    
+    #[test]
+    fn test_with_velocity_encodes_exact_bits() {
+        let dt = Scalar::from_float(1.0 / 60.0);
+        let position = Vec2::new(10.0, 20.0);
+        let velocity = Vec2::new(3.0, -1.5);
+        
+        let circle = Circle::with_velocity(position, velocity, Scalar::ONE, Scalar::TWO, dt);
+        
+        assert_eq!(circle.velocity, velocity);
+        assert_eq!(circle.old_position.x.to_bits(), (position.x - velocity.x * dt).to_bits());
+        assert_eq!(circle.old_position.y.to_bits(), (position.y - velocity.y * dt).to_bits());
+        assert!(circle.velocity_consistent(dt));
+        
+        // Round trip through raw bits
+        let bits = |v: Vec2| [v.x.to_bits(), v.y.to_bits()];
+        let rebuilt = Circle::from_bits(
+            bits(circle.position),
+            bits(circle.old_position),
+            bits(circle.velocity),
+            circle.radius.to_bits(),
+            circle.mass.to_bits(),
+        );
+        let mut a = World::new(100.0, 100.0);
+        a.add_circle(circle);
+        let mut b = World::new(100.0, 100.0);
+        b.add_circle(rebuilt);
+        a.assert_bit_equal(&b);
+    }
+    
+    #[test]
+    fn test_metadata_toml_round_trip() {
+        let mut input = crate::scenarios::simple_drop();
+        input.name = Some("drop".into());
+        input.description = Some("A ball falls".into());
+        input.author = Some("Ada".into());
+        
+        let text = toml::to_string(&input).unwrap();
+        let loaded: crate::SimulationInput = toml::from_str(&text).unwrap();
+        assert_eq!(loaded.name.as_deref(), Some("drop"));
+        assert_eq!(loaded.description.as_deref(), Some("A ball falls"));
+        assert_eq!(loaded.author.as_deref(), Some("Ada"));
+        
+        // Files without metadata still load
+        input.name = None;
+        input.description = None;
+        input.author = None;
+        let text = toml::to_string(&input).unwrap();
+        assert!(!text.contains("name"));
+        let loaded: crate::SimulationInput = toml::from_str(&text).unwrap();
+        assert_eq!((loaded.name, loaded.description, loaded.author), (None, None, None));
+    }
+    
+    #[test]
+    fn test_recording_reuses_step_contacts() {
+        use crate::spatial::GRID_BUILDS;
+        
+        let input = crate::scenarios::pool_break();
+        let steps = 120;
+        
+        // Contacts resolved while stepping by hand
+        let mut world = World::from_input(&input);
+        let mut resolved = 0;
+        for _ in 0..steps {
+            world.step();
+            resolved += world.last_contacts().pairs.len() as u32;
+        }
+        
+        GRID_BUILDS.with(|count| count.set(0));
+        let trace = World::from_input(&input).run_with_recording(steps);
+        
+        // One grid per step: metrics and snapshots read the cached contacts
+        assert_eq!(GRID_BUILDS.with(|count| count.get()), steps as usize);
+        assert!(resolved > 0);
+        assert_eq!(trace.output.metrics.collision_count, resolved);
+        let per_frame: u32 = trace.states.iter().map(|s| s.frame_collisions).sum();
+        assert_eq!(per_frame, resolved);
+    }
+    
+    #[test]
+    fn test_step_with_length_constraint() {
+        let mut world = World::new(200.0, 200.0);
+        let pivot = Vec2::new(100.0, 150.0);
+        let length = Scalar::from_float(50.0);
+        world.add_circle(Circle::new(
+            Vec2::new(130.0, 110.0),
+            Scalar::from_float(5.0),
+            Scalar::ONE,
+        ));
+        let start = (world.circles[0].position - pivot).magnitude();
+        
+        for _ in 0..300 {
+            world.step_with(|w| {
+                let bob = &mut w.circles[0];
+                let to_bob = bob.position - pivot;
+                bob.position = pivot + to_bob * (length / to_bob.magnitude());
+            });
+            let radius = (world.circles[0].position - pivot).magnitude();
+            assert!((radius - length).abs() < Scalar::from_float(0.01), "radius drifted to {}", radius.to_float());
+        }
+        
+        // The bob actually swung rather than sitting still
+        assert!((start - length).abs() < Scalar::from_float(0.01));
+        assert!(world.circles[0].position.x < Scalar::from_float(130.0));
+    }
+    
+    #[test]
+    fn test_recommended_segment_po2_scales_with_work() {
+        use crate::{scenarios, RECOMMENDED_SEGMENT_PO2};
+        
+        let tiny = scenarios::simple_drop().recommended_segment_po2();
+        let large = scenarios::pool_break_15().recommended_segment_po2();
+        
+        assert!(large > tiny, "pool_break_15 ({}) should want larger segments than simple_drop ({})", large, tiny);
+        for po2 in [tiny, large] {
+            assert!(RECOMMENDED_SEGMENT_PO2.contains(&po2));
+            assert!((13..=24).contains(&po2), "outside RISC Zero's segment_limit_po2 range");
+        }
+    }
+    
+    #[test]
+    fn test_stepped_matches_clone_then_step() {
+        let mut world = World::from_input(&crate::scenarios::pool_break());
+        for _ in 0..30 {
+            let before = world.clone();
+            let next = world.stepped();
+            
+            // `stepped` leaves the original untouched
+            world.assert_bit_equal(&before);
+            
+            let mut expected = world.clone();
+            expected.step();
+            next.assert_bit_equal(&expected);
+            world = next;
+        }
+    }
+    
+    #[test]
+    fn test_set_velocity_clamped_prevents_overflow() {
+        use crate::MAX_SAFE_SPEED;
+        
+        let mut world = World::new(1000.0, 1000.0);
+        let mut ball = Circle::new(Vec2::new(500.0, 500.0), Scalar::ONE, Scalar::ONE);
+        // |v|² here is far beyond Q16.16
+        ball.set_velocity_clamped(Vec2::new(30000.0, -30000.0), world.timestep);
+        
+        // Compare squares; `Scalar::sqrt` is a few hundredths off at this size
+        let speed_sq = ball.velocity.magnitude_squared();
+        assert!(speed_sq <= MAX_SAFE_SPEED * MAX_SAFE_SPEED);
+        assert!(speed_sq > Scalar::from_float(127.9 * 127.9));
+        // Direction is kept
+        assert_eq!(ball.velocity.x, -ball.velocity.y);
+        
+        // Sane velocities pass through untouched
+        let mut slow = ball;
+        slow.set_velocity_clamped(Vec2::new(3.0, 4.0), world.timestep);
+        assert_eq!(slow.velocity, Vec2::new(3.0, 4.0));
+        
+        world.add_circle(ball);
+        world.step();
+        let _ = world.calculate_total_energy();
+        assert!(world.circles[0].position.x > Scalar::from_float(500.0));
+    }
+    
+    #[test]
+    fn test_verify_runs_reports_divergence() {
+        use crate::determinism::verify_runs;
+        use std::cell::Cell;
+        
+        let build = || World::from_input(&crate::scenarios::three_body_collision());
+        let report = verify_runs(build, 3, 250);
+        assert!(report.is_deterministic());
+        assert_eq!(report.first_divergence(), None);
+        // Step 0, 100, 200 and the final step
+        let steps: Vec<u32> = report.checkpoints.iter().map(|c| c.step).collect();
+        assert_eq!(steps, vec![0, 100, 200, 250]);
+        
+        // Every build after the first nudges a ball by one bit
+        let calls = Cell::new(0);
+        let flaky = || {
+            let mut world = build();
+            let nudge = Scalar::from_bits(calls.get());
+            world.circles[0].position.x = world.circles[0].position.x + nudge;
+            world.circles[0].old_position.x = world.circles[0].old_position.x + nudge;
+            calls.set(calls.get() + 1);
+            world
+        };
+        let report = verify_runs(flaky, 3, 250);
+        assert!(!report.is_deterministic());
+        assert_eq!(report.first_divergence(), Some((0, 1)));
+        assert_eq!(report.checkpoints[0].diverging_runs, vec![1, 2]);
+    }
+    
+    #[test]
+    fn test_collision_filter_rejects_pair() {
+        let mut world = World::new(100.0, 100.0);
+        world.gravity = Vec2::ZERO;
+        // Two overlapping, approaching pairs far apart from each other
+        for (x, y) in [(20.0, 50.0), (60.0, 50.0)] {
+            let mut left = Circle::new(Vec2::new(x, y), Scalar::ONE, Scalar::ONE);
+            left.set_velocity(Vec2::new(1.0, 0.0), world.timestep);
+            let mut right = Circle::new(Vec2::new(x + 1.5, y), Scalar::ONE, Scalar::ONE);
+            right.set_velocity(Vec2::new(-1.0, 0.0), world.timestep);
+            world.add_circle(left);
+            world.add_circle(right);
+        }
+        world.set_collision_filter(|a, b| (a, b) != (0, 1));
+        
+        world.step();
+        assert_eq!(world.detect_collisions(), vec![(2, 3)]);
+        assert_eq!(world.last_contacts().pairs, vec![(2, 3)]);
+        
+        // The filtered pair passes through; the other pair bounces apart
+        assert_eq!(world.circles[0].velocity, Vec2::new(1.0, 0.0));
+        assert_eq!(world.circles[1].velocity, Vec2::new(-1.0, 0.0));
+        assert!(world.circles[2].velocity.x < Scalar::ZERO);
+        assert!(world.circles[3].velocity.x > Scalar::ZERO);
+    }
+    
+    #[test]
+    fn test_trace_bit_eq() {
+        let input = crate::scenarios::pool_break();
+        let trace = World::from_input(&input).run_with_recording(60);
+        assert!(trace.bit_eq(&trace));
+        assert_eq!(trace.bit_exact(), trace.clone().bit_exact());
+        
+        // One ulp off in a single state breaks equality
+        let mut perturbed = trace.clone();
+        let x = &mut perturbed.states[30].circles[0].position[0];
+        *x = f32::from_bits(x.to_bits() + 1);
+        assert!(!trace.bit_eq(&perturbed));
+        assert_ne!(trace.bit_exact(), perturbed.bit_exact());
+        
+        // Unlike f32 ==, signed zeros are told apart
+        let mut signed = trace.clone();
+        signed.states[0].circles[1].velocity = [-0.0, -0.0];
+        assert_eq!(trace.states[0].circles[1].velocity, [0.0, 0.0]);
+        assert_eq!(trace.states[0], signed.states[0]);
+        assert!(!trace.bit_eq(&signed));
+    }
+    
+    #[test]
+    fn test_scheduled_gravity_activation() {
+        use crate::{Schedule, ParamChange};
+        
+        let mut input = crate::scenarios::pool_break();
+        input.gravity = [0.0, 0.0];
+        let mut scheduled = World::from_input(&input);
+        scheduled.schedule = Schedule::new()
+            .at(100, ParamChange::Gravity(Vec2::new(0.0, -9.81)));
+        let mut reference = World::from_input(&input);
+        
+        // Steps 0..100 run without gravity either way
+        for _ in 0..100 {
+            scheduled.step();
+            reference.step();
+        }
+        // Identical apart from the still-pending schedule
+        let mut pending = scheduled.clone();
+        pending.schedule = reference.schedule.clone();
+        pending.assert_bit_equal(&reference);
+        assert_eq!(scheduled.gravity, Vec2::ZERO);
+        
+        // Gravity switches on at the start of step 100
+        scheduled.step();
+        reference.step();
+        assert_eq!(scheduled.gravity, Vec2::new(0.0, -9.81));
+        assert_eq!(scheduled.steps_taken, 101);
+        for _ in 0..20 {
+            scheduled.step();
+            reference.step();
+        }
+        let dropped = scheduled.circles.iter().zip(&reference.circles)
+            .all(|(s, r)| s.position.y < r.position.y);
+        assert!(dropped);
+    }
+    
+    #[test]
+    fn test_sleeping_counts_as_bodies_settle() {
+        let mut world = World::new(1000.0, 1000.0);
+        world.gravity = Vec2::ZERO;
+        // Two bodies under heavy drag settle; the third keeps coasting
+        for (i, drag) in [3.0, 3.0, 0.0].into_iter().enumerate() {
+            let mut ball = Circle::new(Vec2::new(100.0 + i as f32 * 100.0, 500.0), Scalar::ONE, Scalar::ONE);
+            ball.drag = Scalar::from_float(drag);
+            ball.set_velocity(Vec2::new(5.0, 0.0), world.timestep);
+            world.add_circle(ball);
+        }
+        
+        assert_eq!(world.num_circles(), 3);
+        assert_eq!((world.num_awake(), world.num_sleeping()), (3, 0));
+        
+        for _ in 0..300 {
+            world.step();
+        }
+        assert_eq!((world.num_awake(), world.num_sleeping()), (1, 2));
+        assert!(!world.circles[2].is_sleeping());
+    }
+    
+    #[test]
+    fn test_merge_scenario_fragments() {
+        use crate::{MAX_CIRCLES, ValidationError};
+        
+        // Split the pool break into a cue fragment and a 15-ball rack fragment
+        let full = crate::scenarios::pool_break_15();
+        let mut cue = full.clone();
+        cue.circles.truncate(1);
+        let mut rack = full.clone();
+        rack.circles.remove(0);
+        assert_eq!(rack.circles.len(), 15);
+        
+        cue.merge(&rack).unwrap();
+        assert_eq!(cue.circles.len(), 16);
+        assert!(World::try_from_input(&cue).is_ok());
+        
+        // Same scene as the original, so the same trajectory
+        let mut merged = World::from_input(&cue);
+        let mut original = World::from_input(&full);
+        for _ in 0..60 {
+            merged.step();
+            original.step();
+        }
+        merged.assert_bit_equal(&original);
+        
+        // Overflowing merges are rejected and leave the input untouched
+        let mut crowded = full.clone();
+        crowded.circles = vec![full.circles[0].clone(); MAX_CIRCLES];
+        assert_eq!(
+            crowded.merge(&cue),
+            Err(ValidationError::TooManyCircles(crate::TooManyCircles { count: MAX_CIRCLES + 16 })),
+        );
+        assert_eq!(crowded.circles.len(), MAX_CIRCLES);
+        
+        // So are merges the combined world doesn't validate, here a rack
+        // placed beyond a smaller table
+        let mut small = full.clone();
+        small.circles.truncate(1);
+        small.world_width = 10.0;
+        let before = small.clone();
+        assert_eq!(small.merge(&rack), Err(ValidationError::OutOfBounds { circle: 1 }));
+        assert_eq!(small.hash(), before.hash());
+    }
+    
+    #[test]
+    fn test_contact_free_step_keeps_circle_buffer() {
+        let mut world = World::new(100.0, 100.0);
+        let mut ball = Circle::new(Vec2::new(50.0, 50.0), Scalar::ONE, Scalar::ONE);
+        ball.set_velocity(Vec2::new(2.0, 3.0), world.timestep);
+        world.add_circle(ball);
+        let mut reference = world.clone();
+        
+        let buffer = world.circles.as_ptr();
+        for _ in 0..30 {
+            world.step();
+            // Free fall: same positions as plain Verlet integration
+            reference.step_no_collision();
+            assert_eq!(world.circles[0].position, reference.circles[0].position);
+            assert_eq!(world.circles[0].old_position, reference.circles[0].old_position);
+        }
+        
+        // Mid-air with nothing to hit, the circles were never copied
+        assert_eq!(world.circles.as_ptr(), buffer);
+        assert_eq!(*world.last_contacts(), crate::StepContacts::default());
+    }
+    
+    #[test]
+    fn test_trace_roundtrip_stable_from_initial_state() {
+        use crate::determinism::assert_trace_roundtrip_stable;
+        
+        for name in crate::scenarios::list_scenarios() {
+            let mut input = crate::scenarios::get_scenario(name).unwrap();
+            input.num_steps = input.num_steps.min(240);
+            assert_trace_roundtrip_stable(&input);
+        }
+    }
+    
+    #[test]
+    #[should_panic(expected = "trace round-trip diverged")]
+    fn test_trace_resume_from_f32_state_diverges() {
+        // Mid-run f32 snapshots lose the Verlet history, so replay drifts
+        let mut input = crate::scenarios::pool_break();
+        input.num_steps = 240;
+        crate::determinism::assert_trace_resume_stable(&input, 120);
+    }
+    
+    #[test]
+    fn test_explosion_pushes_nearby_bodies_outward() {
+        let mut world = World::new(100.0, 100.0);
+        world.gravity = Vec2::ZERO;
+        let center = Vec2::new(50.0, 50.0);
+        for (x, y) in [(52.0, 50.0), (50.0, 45.0), (65.0, 50.0)] {
+            world.add_circle(Circle::new(Vec2::new(x, y), Scalar::ONE, Scalar::ONE));
+        }
+        
+        world.apply_explosion(center, Scalar::from_float(10.0), Scalar::from_float(10.0));
+        
+        // Outward: +x for the right body, -y for the lower one
+        let near = world.circles[0].velocity;
+        let far = world.circles[1].velocity;
+        assert!(near.x > Scalar::ZERO && near.y == Scalar::ZERO);
+        assert!(far.y < Scalar::ZERO && far.x == Scalar::ZERO);
+        
+        // Speed follows the linear falloff: 10 * (1 - 2/10) vs 10 * (1 - 5/10)
+        assert!((near.x.to_float() - 8.0).abs() < 1e-3);
+        assert!((far.y.to_float() + 5.0).abs() < 1e-3);
+        
+        // Outside the radius nothing changes
+        assert_eq!(world.circles[2].velocity, Vec2::ZERO);
+        assert_eq!(world.circles[2].old_position, world.circles[2].position);
+        
+        // The impulse carries into the next step
+        world.step();
+        assert!(world.circles[0].position.x > Scalar::from_float(52.1));
+        assert_eq!(world.circles[2].position, Vec2::new(65.0, 50.0));
+    }
+    
+    #[test]
+    fn test_explosion_skips_distant_and_static_circles() {
+        // Squaring the offset to (900, 900) would overflow Q16.16
+        let mut world = World::new(1000.0, 1000.0);
+        world.gravity = Vec2::ZERO;
+        world.add_circle(Circle::new(Vec2::new(900.0, 900.0), Scalar::ONE, Scalar::ONE));
+        world.add_circle(Circle::new_static(Vec2::new(14.0, 10.0), Scalar::ONE));
+        world.add_circle(Circle::new(Vec2::new(10.0, 10.0), Scalar::ONE, Scalar::ONE));
+        world.circles[1].old_position = Vec2::new(13.0, 10.0);
+        
+        world.apply_explosion(Vec2::new(12.0, 10.0), Scalar::from_float(5.0), Scalar::from_float(10.0));
+        
+        assert_eq!(world.circles[0].velocity, Vec2::ZERO);
+        assert_eq!(world.circles[0].old_position, Vec2::new(900.0, 900.0));
+        // The static circle's history is left as it was
+        assert_eq!(world.circles[1].old_position, Vec2::new(13.0, 10.0));
+        assert!(world.circles[2].velocity.x < Scalar::ZERO);
+    }
+    
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "call set_velocity after moving it")]
+    fn test_add_circle_rejects_stale_verlet_history() {
+        let mut world = World::new(10.0, 10.0);
+        let mut circle = Circle::new(Vec2::new(2.0, 2.0), Scalar::ONE, Scalar::ONE);
+        circle.set_velocity(Vec2::new(3.0, 0.0), world.timestep);
+        circle.position = Vec2::new(5.0, 5.0);
+        world.add_circle(circle);
+    }
+    
+    #[test]
+    fn test_add_circle_accepts_velocity_verlet_history() {
+        // Velocity Verlet's cached velocity is not the encoded one
+        let mut world = World::new(10.0, 10.0);
+        world.integrator = crate::physics::Integrator::VelocityVerlet;
+        let mut circle = Circle::new(Vec2::new(2.0, 2.0), Scalar::ONE, Scalar::ONE);
+        circle.set_velocity(Vec2::new(3.0, 0.0), world.timestep);
+        circle.position = Vec2::new(5.0, 5.0);
+        world.add_circle(circle);
+        assert_eq!(world.circles[0].velocity(), Vec2::new(3.0, 0.0));
+    }
+    
+    #[test]
+    fn test_elastic_boundary_bounce_returns_to_height() {
+        // 10 m box, radius 1: 8 m of travel each way, 60 steps at 8 m/s
+        let mut world = World::new(10.0, 10.0);
+        world.gravity = Vec2::ZERO;
+        world.set_restitution(1.0);
+        let mut ball = Circle::new(Vec2::new(5.0, 5.0), Scalar::ONE, Scalar::ONE);
+        ball.set_velocity(Vec2::new(0.0, -8.0), world.timestep);
+        world.add_circle(ball);
+        let start = world.circles[0];
+        
+        for _ in 0..4 {
+            // One floor and one ceiling bounce per 120 steps
+            for _ in 0..120 {
+                world.step();
+            }
+            let ball = &world.circles[0];
+            assert_eq!(ball.velocity, start.velocity, "elastic bounces must preserve speed exactly");
+            // Q16.16 dt is a hair under 1/60, costing ~0.004 m per period;
+            // the old partial push-out lost ~0.35 m per period
+            assert!(
+                (ball.position.y - start.position.y).abs() < Scalar::from_float(0.02),
+                "returned to y = {} instead of 5", ball.position.y.to_float(),
+            );
+        }
+    }
+    
+    #[test]
+    fn test_toml_with_nan_position_is_rejected() {
+        use crate::NonFiniteInput;
+        
+        let text = toml::to_string(&crate::scenarios::simple_drop()).unwrap();
+        let (head, tail) = text.split_once("position = [").unwrap();
+        let (_, rest) = tail.split_once(']').unwrap();
+        let text = format!("{}position = [nan, 5.0]{}", head, rest);
+        
+        let path = std::env::temp_dir().join(format!("determinisk-nan-{}.toml", std::process::id()));
+        std::fs::write(&path, text).unwrap();
+        let result = crate::scenarios::from_toml_file(&path);
+        std::fs::remove_file(&path).unwrap();
+        
+        let err = result.unwrap_err();
+        let crate::DeterminiskError::Validation(inner) = &err else {
+            panic!("expected a validation error, got {:?}", err);
+        };
+        assert_eq!(
+            inner.downcast_ref::<NonFiniteInput>(),
+            Some(&NonFiniteInput { field: "position", circle: Some(0) }),
+        );
+        assert_eq!(err.to_string(), "circles[0].position is not finite");
+    }
+    
+    #[test]
+    fn test_to_input_round_trip() {
+        let mut world = World::from_input(&crate::scenarios::pool_break());
+        for _ in 0..200 {
+            world.step();
+        }
+        
+        let input = world.to_input(50);
+        assert_eq!(input.num_steps, 50);
+        // Survives being saved as a scenario file
+        let input: crate::SimulationInput = toml::from_str(&toml::to_string(&input).unwrap()).unwrap();
+        let rebuilt = World::from_input(&input);
+        
+        assert_eq!(rebuilt.circles.len(), world.circles.len());
+        assert_eq!(rebuilt.bounds, world.bounds);
+        assert_eq!(rebuilt.gravity, world.gravity);
+        assert_eq!(rebuilt.timestep, world.timestep);
+        for (a, b) in world.circles.iter().zip(&rebuilt.circles) {
+            // Within one Q16.16 step; small coordinates fit in f32 exactly
+            assert!((a.position.x.to_bits() - b.position.x.to_bits()).abs() <= 1);
+            assert!((a.position.y.to_bits() - b.position.y.to_bits()).abs() <= 1);
+            assert!((a.velocity - b.velocity).magnitude() < Scalar::from_float(0.01));
+            assert_eq!((a.radius, a.mass), (b.radius, b.mass));
+        }
+    }
+    
+    #[test]
+    fn test_broadphase_stats_clustered_vs_spread() {
+        let scene = |positions: &[(f32, f32)]| {
+            let mut world = World::new(100.0, 100.0);
+            world.gravity = Vec2::ZERO;
+            for &(x, y) in positions {
+                world.add_circle(Circle::new(Vec2::new(x, y), Scalar::from_float(0.5), Scalar::ONE));
+            }
+            // One overlapping pair in both scenes
+            world.add_circle(Circle::new(Vec2::new(50.0, 80.0), Scalar::from_float(0.5), Scalar::ONE));
+            world.add_circle(Circle::new(Vec2::new(50.9, 80.0), Scalar::from_float(0.5), Scalar::ONE));
+            assert_eq!(world.broadphase_stats(), crate::BroadPhaseStats::default());
+            world.step();
+            world.broadphase_stats()
+        };
+        
+        let spread: Vec<(f32, f32)> = (0..16)
+            .map(|i| (10.0 + 20.0 * (i % 4) as f32, 10.0 + 15.0 * (i / 4) as f32))
+            .collect();
+        let clustered: Vec<(f32, f32)> = (0..16)
+            .map(|i| (20.0 + 1.5 * (i % 4) as f32, 20.0 + 1.5 * (i / 4) as f32))
+            .collect();
+        let spread = scene(&spread);
+        let clustered = scene(&clustered);
+        
+        assert_eq!(spread.actual_collisions, 1);
+        assert_eq!(clustered.actual_collisions, 1);
+        // Spread out, the grid only pairs up the circles that really touch;
+        // clustered, neighbours share cells without overlapping
+        assert_eq!(spread.candidate_pairs, 1);
+        assert!(clustered.candidate_pairs > 1, "{:?}", clustered);
+        assert!(clustered.occupied_cells < spread.occupied_cells, "{:?} vs {:?}", clustered, spread);
+    }
+    
+    #[test]
+    fn test_max_impulse_bounds_overlap_separation() {
+        use crate::CollisionConfig;
+        
+        // Spawned almost on top of each other: 1.5 of 2.0 overlapping
+        let separation_speed = |max_impulse| {
+            let mut world = World::with_config(100.0, 100.0, CollisionConfig {
+                max_impulse,
+                ..CollisionConfig::default()
+            });
+            world.gravity = Vec2::ZERO;
+            world.add_circle(Circle::new(Vec2::new(50.0, 50.0), Scalar::ONE, Scalar::ONE));
+            world.add_circle(Circle::new(Vec2::new(50.5, 50.0), Scalar::ONE, Scalar::ONE));
+            let mut fastest = Scalar::ZERO;
+            for _ in 0..60 {
+                world.step();
+                for circle in &world.circles {
+                    fastest = fastest.max(circle.velocity.magnitude());
+                }
+            }
+            (fastest, world.circles[1].position.x - world.circles[0].position.x)
+        };
+        
+        // Unclamped, the position correction launches them at over 15 m/s
+        let (unclamped, _) = separation_speed(None);
+        assert!(unclamped > Scalar::from_float(15.0), "{:?}", unclamped);
+        
+        // Clamped, each body gains at most 0.5 m/s per step and they still
+        // end up apart
+        let (clamped, gap) = separation_speed(Some(Scalar::HALF));
+        assert!(clamped <= Scalar::from_float(3.0), "{:?}", clamped);
+        assert!(gap >= Scalar::TWO, "{:?}", gap);
+    }
+    
+    #[test]
+    fn test_gravity_zone_overrides_global_gravity() {
+        use crate::Aabb;
+        
+        let mut world = World::new(100.0, 100.0);
+        // Zero gravity in the right half, above the floor
+        world.add_gravity_zone(Aabb::new(Vec2::new(50.0, 20.0), Vec2::new(100.0, 100.0)), Vec2::ZERO);
+        // Drifts right into the zone, and one that stays outside
+        let mut drifter = Circle::new(Vec2::new(48.0, 80.0), Scalar::ONE, Scalar::ONE);
+        drifter.set_velocity(Vec2::new(10.0, 0.0), world.timestep);
+        world.add_circle(drifter);
+        world.add_circle(Circle::new(Vec2::new(20.0, 80.0), Scalar::ONE, Scalar::ONE));
+        
+        for _ in 0..60 {
+            let entered = world.circles[0].position.x >= Scalar::from_float(50.0);
+            let before = world.circles.iter().map(|c| c.velocity.y).collect::<Vec<_>>();
+            world.step();
+            let after = world.circles.iter().map(|c| c.velocity.y).collect::<Vec<_>>();
+            
+            if entered {
+                // Inside: vertical velocity no longer changes
+                assert_eq!(after[0], before[0]);
+            }
+            // Outside: falls at the global 9.81 m/s²
+            assert!(after[1] < before[1]);
+        }
+        
+        assert!(world.circles[0].position.x > Scalar::from_float(50.0));
+        assert_eq!(world.gravity_at(world.circles[0].position), Vec2::ZERO);
+        assert_eq!(world.gravity_at(world.circles[1].position), world.gravity);
+        // The drifter only fell until it entered (0.2 s); the other kept
+        // falling for the full second
+        assert!(world.circles[0].position.y > Scalar::from_float(77.5));
+        assert!(world.circles[1].position.y < Scalar::from_float(75.5));
+    }
+    
+    #[test]
+    fn test_input_hash_is_stable_and_ignores_signed_zero() {
+        let input = crate::scenarios::pool_break();
+        assert_eq!(input.hash(), input.clone().hash());
+        
+        // ±0 and metadata don't matter
+        let mut same = input.clone();
+        same.gravity = [-0.0, -0.0];
+        same.circles[1].velocity = [-0.0, 0.0];
+        same.name = Some("renamed".into());
+        same.record_trajectory = !input.record_trajectory;
+        assert_eq!(same.hash(), input.hash());
+        
+        // Any physical difference does
+        let mut nudged = input.clone();
+        nudged.circles[3].position[0] += 0.001;
+        assert_ne!(nudged.hash(), input.hash());
+        let mut longer = input.clone();
+        longer.num_steps += 1;
+        assert_ne!(longer.hash(), input.hash());
+        let mut preset = input.clone();
+        preset.collision_preset = Some("bouncy".into());
+        assert_ne!(preset.hash(), input.hash());
+    }
+    
+    #[test]
+    fn test_reset_with_matches_from_input_and_keeps_capacity() {
+        let big = crate::scenarios::pool_break_15();
+        let small = crate::scenarios::simple_drop();
+        
+        let mut world = World::from_input(&big);
+        for _ in 0..50 {
+            world.step();
+        }
+        world.add_gravity_zone(crate::Aabb::new(Vec2::ZERO, Vec2::new(5.0, 5.0)), Vec2::ZERO);
+        let capacity = world.circles.capacity();
+        let buffer = world.circles.as_ptr();
+        
+        world.reset_with(&small);
+        world.assert_bit_equal(&World::from_input(&small));
+        assert!(world.gravity_zones.is_empty());
+        assert_eq!(world.steps_taken, 0);
+        assert_eq!(world.circles.capacity(), capacity);
+        assert_eq!(world.circles.as_ptr(), buffer);
+        
+        // And stepping from there matches too
+        let mut fresh = World::from_input(&small);
+        for _ in 0..small.num_steps {
+            world.step();
+            fresh.step();
+        }
+        world.assert_bit_equal(&fresh);
+    }
+    
+    #[test]
+    fn test_explosive_overlap_is_detected_as_insane() {
+        // Big circles spawned almost on top of each other: position correction
+        // turns the overlap into a launch well past any sane speed
+        let mut world = World::new(1000.0, 1000.0);
+        world.gravity = Vec2::ZERO;
+        for i in 0..4 {
+            let offset = 0.05 * i as f32;
+            world.add_circle(Circle::new(Vec2::new(500.0 + offset, 500.0 - offset), Scalar::from_float(20.0), Scalar::ONE));
+        }
+        assert!(world.is_sane());
+        
+        let insane_at = (1..=5).find(|_| {
+            world.step();
+            !world.is_sane()
+        });
+        assert!(insane_at.is_some(), "still sane after 5 steps");
+        
+        // Ordinary scenarios stay sane throughout
+        let input = crate::scenarios::pool_break();
+        let mut world = World::from_input(&input);
+        for _ in 0..input.num_steps {
+            world.step();
+            assert!(world.is_sane());
+        }
+    }
+    
+    #[test]
+    fn test_toml_angular_velocity_spins_circle() {
+        let mut input = crate::scenarios::simple_drop();
+        input.circles.push(input.circles[0].clone());
+        input.circles[1].position[0] += 10.0;
+        let text = toml::to_string(&input).unwrap();
+        // Files written before spin existed still load, without it
+        assert_eq!(text.matches("angular_velocity = 0.0").count(), 2);
+        let text = text.replace("angular_velocity = 0.0\n", "");
+        let mut loaded: crate::SimulationInput = toml::from_str(&text).unwrap();
+        assert_eq!(loaded.circles[0].angular_velocity, 0.0);
+        
+        loaded.circles[1].angular_velocity = 3.0;
+        let text = toml::to_string(&loaded).unwrap();
+        let loaded: crate::SimulationInput = toml::from_str(&text).unwrap();
+        let mut world = World::from_input(&loaded);
+        for _ in 0..30 {
+            world.step();
+        }
+        
+        // Half a second at 3 rad/s, wrapped into [-π, π)
+        assert_eq!(world.circles[0].angle, Scalar::ZERO);
+        assert!((world.circles[1].angle.to_float() - 1.5).abs() < 0.01);
+        assert_eq!(world.circles[1].angular_velocity, Scalar::from_float(3.0));
+        // Spin doesn't affect the trajectory (no torque coupling yet)
+        assert_eq!(world.circles[0].position.y, world.circles[1].position.y);
+        assert_eq!(world.to_input(1).circles[1].angular_velocity, 3.0);
+        
+        // Past a half turn the angle wraps
+        for _ in 0..60 {
+            world.step();
+        }
+        assert!((world.circles[1].angle.to_float() - (4.5 - std::f32::consts::TAU)).abs() < 0.01);
+    }
+    
+    #[test]
+    fn test_integrate_then_resolve_equals_step() {
+        let input = crate::scenarios::pool_break();
+        let mut stepped = World::from_input(&input);
+        let mut split = World::from_input(&input);
+        
+        for _ in 0..200 {
+            stepped.step();
+            split.integrate();
+            split.resolve();
+            split.assert_bit_equal(&stepped);
+            assert_eq!(split.last_contacts(), stepped.last_contacts());
+        }
+        assert_eq!(split.steps_taken, stepped.steps_taken);
+    }
+    
+    #[test]
+    fn test_stationary_circle_has_zero_kinetic_energy() {
+        let dt = Scalar::from_float(1.0 / 60.0);
+        let circle = Circle::new(Vec2::new(10.0, 10.0), Scalar::ONE, Scalar::from_float(3.0));
+        
+        assert_eq!(circle.kinetic_energy(dt), Scalar::ZERO);
+        assert_eq!(circle.momentum(dt), Vec2::ZERO);
+    }
+    
+    #[test]
+    fn test_momentum_is_mass_times_velocity() {
+        let dt = Scalar::from_float(0.05);
+        let mass = Scalar::from_float(2.0);
+        let velocity = Vec2::new(4.0, -2.0);
+        let circle = Circle::with_velocity(Vec2::new(10.0, 10.0), velocity, Scalar::ONE, mass, dt);
+        
+        assert_eq!(circle.momentum(dt), velocity * mass);
+        // 0.5 * 2 * (16 + 4)
+        assert_eq!(circle.kinetic_energy(dt), Scalar::from_float(20.0));
+    }
+    
+    #[test]
+    fn test_ball_driven_into_corner_stays_inside() {
+        for velocity in [Vec2::new(-30.0, -30.0), Vec2::new(-100.0, -60.0), Vec2::new(-5.0, -90.0)] {
+            let mut world = World::new(20.0, 20.0);
+            world.gravity = Vec2::ZERO;
+            let mut circle = Circle::new(Vec2::new(2.0, 2.0), Scalar::ONE, Scalar::ONE);
+            circle.set_velocity(velocity, world.timestep);
+            world.add_circle(circle);
+            let e = world.collision_config.restitution;
+            let tolerance = Scalar::from_float(0.01);
+            
+            // Until the ball has left the corner on both axes
+            while world.circles[0].velocity.x < Scalar::ZERO || world.circles[0].velocity.y < Scalar::ZERO {
+                world.step();
+                let circle = &world.circles[0];
+                let radius = circle.radius;
+                assert!(circle.position.x >= radius && circle.position.y >= radius,
+                    "{:?} left the box: {:?}", velocity, circle.position);
+                
+                // An axis that has bounced rebounds at exactly e times its
+                // incoming speed; a double correction would eject it faster
+                for (now, before) in [(circle.velocity.x, velocity.x), (circle.velocity.y, velocity.y)] {
+                    if now > Scalar::ZERO {
+                        assert!((now - -before * e).abs() <= tolerance, "{:?} -> {:?}", velocity, circle.velocity);
+                    }
+                }
+            }
+        }
+    }
+    
+    #[test]
+    fn test_load_unknown_scenario_is_typed() {
+        use crate::DeterminiskError;
+        
+        assert!(crate::scenarios::load("pool_break").is_ok());
+        match crate::scenarios::load("no_such_scenario") {
+            Err(DeterminiskError::UnknownScenario(name)) => assert_eq!(name, "no_such_scenario"),
+            other => panic!("expected UnknownScenario, got {:?}", other.map(|_| ())),
+        }
+        assert!(matches!(crate::scenarios::load("missing.toml"), Err(DeterminiskError::Io(_))));
+        assert!(matches!(crate::scenarios::from_file("scene.yaml"), Err(DeterminiskError::Unsupported(_))));
+    }
+    
+    #[test]
+    fn test_journal_checkpoints_match_native_run() {
+        use crate::determinism::check_journal_checkpoints;
+        
+        let mut input = crate::scenarios::pool_break();
+        input.num_steps = 250;
+        input.checkpoint_interval = 100;
+        let journal = input.execute_journal();
+        
+        let mut world = World::from_input(&input);
+        let mut native = Vec::new();
+        for step in 1..=input.num_steps {
+            world.step();
+            if step % 100 == 0 {
+                native.push((step, world.state_hash()));
+            }
+        }
+        assert_eq!(journal.checkpoints, native);
+        assert_eq!(journal.state_hash, world.state_hash());
+        assert_eq!(check_journal_checkpoints(&input, &journal), Ok(2));
+        
+        // A tampered checkpoint is caught at its step
+        let mut forged = journal.clone();
+        forged.checkpoints[1].1[0] ^= 1;
+        assert_eq!(check_journal_checkpoints(&input, &forged).unwrap_err().step, 200);
+        
+        // No interval, no checkpoints, nothing to check
+        input.checkpoint_interval = 0;
+        assert!(input.execute_journal().checkpoints.is_empty());
+    }
+    
+    #[test]
+    fn test_velocity_verlet_free_fall_is_closer_to_analytic() {
+        use crate::Integrator;
+        
+        const STEPS: u32 = 90;
+        let v0 = 12.0f32;
+        let fall = |integrator: Integrator| {
+            let mut world = World::new(100.0, 500.0);
+            world.integrator = integrator;
+            world.add_circle(Circle::with_velocity(
+                Vec2::new(50.0, 100.0),
+                Vec2::new(0.0, v0),
+                Scalar::ONE,
+                Scalar::ONE,
+                world.timestep,
+            ));
+            for _ in 0..STEPS {
+                world.step();
+            }
+            let t = world.timestep.to_float() * STEPS as f32;
+            let g = world.gravity.y.to_float();
+            let circle = &world.circles[0];
+            let position_error = (circle.position.y.to_float() - (100.0 + v0 * t + 0.5 * g * t * t)).abs();
+            let velocity_error = (circle.velocity.y.to_float() - (v0 + g * t)).abs();
+            (position_error, velocity_error)
+        };
+        
+        let (verlet_pos, verlet_vel) = fall(Integrator::Verlet);
+        let (vv_pos, vv_vel) = fall(Integrator::VelocityVerlet);
+        
+        assert!(vv_pos < verlet_pos, "position error: velocity Verlet {} vs Verlet {}", vv_pos, verlet_pos);
+        assert!(vv_vel < verlet_vel, "velocity error: velocity Verlet {} vs Verlet {}", vv_vel, verlet_vel);
+        assert!(vv_pos < 0.01 && vv_vel < 0.01, "velocity Verlet off by {} / {}", vv_pos, vv_vel);
+    }
+    
+    #[test]
+    fn test_velocity_verlet_energy_metric_tracks_free_fall() {
+        use crate::Integrator;
+        
+        const STEPS: u32 = 60;
+        let kinetic_error = |integrator: Integrator| {
+            let mut world = World::new(100.0, 500.0);
+            world.integrator = integrator;
+            world.add_circle(Circle::new(Vec2::new(50.0, 400.0), Scalar::ONE, Scalar::from_float(2.0)));
+            for _ in 0..STEPS {
+                world.step();
+            }
+            let t = world.timestep.to_float() * STEPS as f32;
+            let speed = world.gravity.y.to_float() * t;
+            let momentum = world.calculate_total_momentum().y.to_float();
+            (
+                (world.calculate_kinetic_energy().to_float() - speed * speed).abs(),
+                (momentum - 2.0 * speed).abs(),
+            )
+        };
+        
+        // KE = 0.5 * 2 * (g t)^2; the Verlet history lags half a step
+        let (verlet_ke, verlet_p) = kinetic_error(Integrator::Verlet);
+        let (vv_ke, vv_p) = kinetic_error(Integrator::VelocityVerlet);
+        assert!(vv_ke < verlet_ke, "KE error: velocity Verlet {} vs Verlet {}", vv_ke, verlet_ke);
+        assert!(vv_p < verlet_p, "momentum error: velocity Verlet {} vs Verlet {}", vv_p, verlet_p);
+        assert!(vv_ke < 0.05, "velocity Verlet KE off by {}", vv_ke);
+    }
+    
+    #[test]
+    fn test_circle_order_does_not_change_results() {
+        use crate::CollisionFilter;
+        
+        let mut reference = World::from_input(&crate::scenarios::pool_break());
+        // An index-based filter must follow the bodies through the shuffle
+        reference.should_collide = Some(CollisionFilter::new(|a, b| (a, b) != (1, 2)));
+        
+        for seed in [1, 2, 3, 42, 0xDEAD_BEEF] {
+            let (mut shuffled, order) = reference.with_shuffled_circles(seed);
+            assert_ne!(order, (0..order.len()).collect::<Vec<_>>(), "seed {} left the order unchanged", seed);
+            let mut original = reference.clone();
+            
+            for _ in 0..300 {
+                original.step();
+                shuffled.step();
+            }
+            
+            // Compare by stable id: shuffled slot k holds original body order[k]
+            for (k, &id) in order.iter().enumerate() {
+                let (a, b) = (&original.circles[id], &shuffled.circles[k]);
+                assert!(
+                    a.position == b.position && a.old_position == b.old_position && a.velocity == b.velocity,
+                    "seed {}: body {} differs after shuffling ({:?} vs {:?})", seed, id, a.position, b.position,
+                );
+            }
+        }
+    }
+    
+    #[test]
+    fn test_world_rect_grid_cells_detect_the_same_pairs() {
+        let mut world = World::from_input(&crate::scenarios::pool_break_15());
+        let sorted = |world: &World| {
+            let mut pairs = world.detect_collisions();
+            pairs.sort_unstable();
+            pairs
+        };
+        
+        let mut touching = 0;
+        for _ in 0..120 {
+            world.step();
+            let mut wide = world.clone();
+            wide.set_grid_cells(crate::GridCells::Rect {
+                width: Scalar::from_float(5.0),
+                height: Scalar::from_float(1.5),
+            }).unwrap();
+            
+            let pairs = sorted(&world);
+            touching += pairs.len();
+            assert_eq!(sorted(&wide), pairs, "step {}", world.steps_taken);
+        }
+        assert!(touching > 0);
+        
+        // Stepping with rectangular cells is itself reproducible
+        let run = || {
+            let mut world = World::from_input(&crate::scenarios::pool_break_15());
+            world.set_grid_cells(crate::GridCells::Rect {
+                width: Scalar::from_float(5.0),
+                height: Scalar::from_float(1.5),
+            }).unwrap();
+            for _ in 0..120 {
+                world.step();
+            }
+            world
+        };
+        run().assert_bit_equal(&run());
+    }
+    
+    #[test]
+    fn test_degenerate_grid_cells_are_rejected() {
+        use crate::{CollisionConfig, GridCells, InvalidGridCells};
+        
+        let zero_width = GridCells::Rect { width: Scalar::ZERO, height: Scalar::from_float(4.0) };
+        let negative_height = GridCells::Rect { width: Scalar::ONE, height: -Scalar::ONE };
+        let mut world = World::from_input(&crate::scenarios::pool_break_15());
+        for cells in [zero_width, negative_height] {
+            assert_eq!(world.set_grid_cells(cells), Err(InvalidGridCells { cells }));
+            assert_eq!(world.collision_config.grid_cells, GridCells::Square);
+        }
+        
+        // Set directly (or deserialized), they fall back to square cells
+        let square = World::from_input(&crate::scenarios::pool_break_15());
+        let mut direct = square.clone();
+        direct.collision_config = CollisionConfig { grid_cells: zero_width, ..direct.collision_config };
+        let run = |mut world: World| {
+            for _ in 0..60 {
+                world.step();
+            }
+            world.circles
+        };
+        let (expected, actual) = (run(square), run(direct));
+        assert!(expected.iter().zip(&actual).all(|(a, b)| a.position == b.position));
+    }
+    
+    #[test]
+    fn test_estimate_cost_pool_break_15() {
+        let mut input = crate::scenarios::pool_break_15();
+        
+        let estimate = input.estimate_cost();
+        assert_eq!(estimate.bodies, 16);
+        assert_eq!(estimate.steps, input.num_steps);
+        assert_eq!(estimate.est_collision_checks, 120 * u64::from(input.num_steps));
+        assert_eq!(estimate.est_cycles, input.estimated_cycles());
+        assert!(estimate.est_proof_size_kb > 1.0);
+        
+        // Cycles grow with the step count; the proof size doesn't
+        let mut previous = estimate;
+        for steps in [1000, 2000, 4000] {
+            input.num_steps = steps;
+            let estimate = input.estimate_cost();
+            assert!(estimate.est_cycles > previous.est_cycles, "{} steps", steps);
+            assert!(estimate.est_collision_checks > previous.est_collision_checks);
+            assert_eq!(estimate.est_proof_size_kb, previous.est_proof_size_kb);
+            previous = estimate;
+        }
+    }
+    
+    #[test]
+    fn test_circle_handles_survive_removal() {
+        let mut world = World::new(20.0, 20.0);
+        let handles: Vec<_> = (0..5)
+            .map(|i| world.add_circle(Circle::new(
+                Vec2::new(2.0 + 3.0 * i as f32, 5.0),
+                Scalar::from_float(0.5 + 0.1 * i as f32),
+                Scalar::ONE,
+            )))
+            .collect();
+        let radius = |world: &World, handle| world.get_circle(handle).map(|c: &Circle| c.radius);
+        let expected: Vec<_> = handles.iter().map(|&h| radius(&world, h)).collect();
+        
+        // Remove the middle circle: it is returned and its handle goes dead
+        let removed = world.remove_circle(handles[2]).unwrap();
+        assert_eq!(Some(removed.radius), expected[2]);
+        assert_eq!(world.circles.len(), 4);
+        assert!(world.get_circle(handles[2]).is_none());
+        assert!(world.remove_circle(handles[2]).is_none());
+        
+        // Every other handle still finds its own body
+        for (i, &handle) in handles.iter().enumerate().filter(|&(i, _)| i != 2) {
+            assert_eq!(radius(&world, handle), expected[i], "handle {}", i);
+        }
+        // The rest keep their relative order
+        let order: Vec<_> = world.circles.iter().map(|c| Some(c.radius)).collect();
+        assert_eq!(order, [expected[0], expected[1], expected[3], expected[4]]);
+        
+        for _ in 0..10 {
+            world.step();
+        }
+        world.get_circle_mut(handles[4]).unwrap().radius = Scalar::TWO;
+        assert_eq!(world.circles[3].radius, Scalar::TWO);
+        
+        // A new circle may reuse the freed slot, but never the old handle
+        let replacement = world.add_circle(Circle::new(Vec2::new(10.0, 15.0), Scalar::ONE, Scalar::ONE));
+        assert_ne!(replacement, handles[2]);
+        assert!(world.get_circle(handles[2]).is_none());
+        assert_eq!(world.get_circle(replacement).unwrap().position, Vec2::new(10.0, 15.0));
+        assert_eq!(world.circles.len(), 5);
+    }
+    
+    #[test]
+    fn test_friction_slows_ball_sliding_on_floor() {
+        use crate::CollisionConfig;
+        
+        let slide = |friction: f32| {
+            let mut world = World::with_config(40.0, 20.0, CollisionConfig {
+                restitution: Scalar::ZERO,
+                friction: Scalar::from_float(friction),
+                ..CollisionConfig::default()
+            });
+            // Resting on the floor, sliding right without spin
+            world.add_circle(Circle::with_velocity(
+                Vec2::new(2.0, 0.5),
+                Vec2::new(5.0, 0.0),
+                Scalar::HALF,
+                Scalar::ONE,
+                world.timestep,
+            ));
+            for _ in 0..60 {
+                world.step();
+            }
+            world.circles[0].velocity.x
+        };
+        
+        let frictionless = slide(0.0);
+        assert_eq!(frictionless, Scalar::from_float(5.0));
+        let rough = slide(0.3);
+        assert!(rough < frictionless - Scalar::ONE, "{} vs {}", rough, frictionless);
+        assert!(rough >= Scalar::ZERO, "friction must not reverse the slide: {}", rough);
+    }
+    
+    #[test]
+    fn test_distance_constraint_converges_to_rest_length() {
+        use crate::Constraint;
+        
+        let separation = |stiffness: f32| {
+            let mut world = World::new(40.0, 20.0);
+            world.gravity = Vec2::ZERO;
+            world.add_circle(Circle::new(Vec2::new(15.0, 10.0), Scalar::HALF, Scalar::ONE));
+            world.add_circle(Circle::new(Vec2::new(17.0, 10.0), Scalar::HALF, Scalar::from_float(3.0)));
+            world.add_constraint(Constraint::DistanceConstraint {
+                a: 0,
+                b: 1,
+                rest_length: Scalar::from_float(5.0),
+                stiffness: Scalar::from_float(stiffness),
+            });
+            for _ in 0..240 {
+                world.step();
+            }
+            (world.circles[1].position - world.circles[0].position).magnitude()
+        };
+        
+        let tolerance = Scalar::from_float(0.01);
+        for stiffness in [1.0, 0.5] {
+            let distance = separation(stiffness);
+            assert!((distance - Scalar::from_float(5.0)).abs() < tolerance,
+                "stiffness {}: separation {}", stiffness, distance);
+        }
+    }
+    
+    #[test]
+    fn test_remove_circle_renumbers_constraints() {
+        use crate::Constraint;
+        
+        let mut world = World::new(40.0, 20.0);
+        let handles: Vec<_> = (0..3)
+            .map(|i| world.add_circle(Circle::new(Vec2::new(5.0 + 5.0 * i as f32, 10.0), Scalar::HALF, Scalar::ONE)))
+            .collect();
+        let rod = |a, b| Constraint::DistanceConstraint { a, b, rest_length: Scalar::TWO, stiffness: Scalar::ONE };
+        world.add_constraint(rod(0, 1));
+        world.add_constraint(rod(0, 2));
+        
+        world.remove_circle(handles[1]);
+        assert_eq!(world.constraints, vec![rod(0, 1)]);
+    }
+    
+    #[test]
+    fn test_static_circle_never_moves() {
+        let mut world = World::new(40.0, 20.0);
+        let anchor = Circle::new_static(Vec2::new(20.0, 5.0), Scalar::ONE);
+        world.add_circle(anchor);
+        // Dropped slightly off-center onto the anchor
+        world.add_circle(Circle::new(Vec2::new(20.25, 12.0), Scalar::HALF, Scalar::ONE));
+        
+        let mut bounced = false;
+        for _ in 0..180 {
+            world.step();
+            let pinned = &world.circles[0];
+            assert_eq!(pinned.position.x.to_bits(), anchor.position.x.to_bits());
+            assert_eq!(pinned.position.y.to_bits(), anchor.position.y.to_bits());
+            assert_eq!(pinned.old_position, anchor.old_position);
+            assert_eq!(pinned.velocity, Vec2::ZERO);
+            bounced |= world.circles[1].velocity.y > Scalar::ZERO;
+        }
+        assert!(bounced, "the falling ball should rebound off the anchor");
+    }
+    
+    #[test]
+    fn test_captured_potential_energy_is_mgh() {
+        let mut world = World::new(20.0, 20.0);
+        world.add_circle(Circle::new(Vec2::new(10.0, 8.0), Scalar::HALF, Scalar::TWO));
+        
+        let state = world.capture_state(0);
+        let expected = 2.0 * 9.81 * 8.0;
+        assert!((state.potential_energy - expected).abs() < 0.01,
+            "{} vs {}", state.potential_energy, expected);
+        assert_eq!(state.kinetic_energy, 0.0);
+        
+        // Falling trades potential for kinetic energy
+        for _ in 0..30 {
+            world.step();
+        }
+        let state = world.capture_state(30);
+        assert!(state.potential_energy < expected);
+        assert!(state.kinetic_energy > 0.0);
+        let total = world.calculate_total_energy().to_float();
+        assert!((state.kinetic_energy + state.potential_energy - total).abs() < 0.01);
+    }
+    
+    #[test]
+    fn test_recording_heavy_high_body_does_not_overflow() {
+        // m * g * h = 10 * 9.81 * 500, far beyond Q16.16
+        let mut world = World::new(100.0, 600.0);
+        world.add_circle(Circle::new(Vec2::new(50.0, 500.0), Scalar::ONE, Scalar::from_float(10.0)));
+        
+        let trace = world.run_with_recording(10);
+        let first = &trace.states[0];
+        assert!((first.potential_energy - 49_050.0).abs() < 1.0, "{}", first.potential_energy);
+        assert!(trace.states.iter().all(|state| state.potential_energy.is_finite()));
+    }
+    
+    #[test]
+    #[cfg(feature = "bincode")]
+    fn test_bincode_trace_round_trip() {
+        let input = crate::scenarios::pool_break_15();
+        let mut world = World::from_input(&input);
+        let trace = world.run_with_recording(300);
+        
+        let path = std::env::temp_dir().join(format!("determinisk-trace-{}.bin", std::process::id()));
+        crate::scenarios::to_bincode_file(&trace, &path).unwrap();
+        let size = std::fs::metadata(&path).unwrap().len() as usize;
+        let loaded = crate::scenarios::from_bincode_file(&path);
+        let reloaded_input = crate::scenarios::from_file(&path);
+        std::fs::remove_file(&path).unwrap();
+        
+        let loaded = loaded.unwrap();
+        assert!(loaded.bit_eq(&trace));
+        assert_eq!(reloaded_input.unwrap().hash(), trace.input.hash());
+        // Replaying the recorded input lands on the same final state
+        let mut replay = World::from_input(&loaded.input);
+        replay.simulate(loaded.output.steps_executed);
+        assert_eq!(replay.state_hash(), world.state_hash());
+        
+        let json = serde_json::to_string(&trace).unwrap();
+        assert!(size * 2 < json.len(), "bincode {} bytes vs JSON {}", size, json.len());
+    }
+    
+    #[test]
+    fn test_checkpoint_restore_replays_bit_for_bit() {
+        use crate::CollisionConfig;
+        
+        let input = crate::scenarios::pool_break_15();
+        let mut world = World::from_input(&input);
+        world.set_collision_config(CollisionConfig {
+            restitution: Scalar::from_float(0.6),
+            friction: Scalar::from_float(0.2),
+            ..CollisionConfig::default()
+        });
+        for _ in 0..100 {
+            world.step();
+        }
+        let checkpoint = world.checkpoint();
+        
+        let mut frames = Vec::new();
+        for _ in 0..50 {
+            world.step();
+            frames.push(world.clone());
+        }
+        
+        // Straight from memory, and through serialization
+        let json = serde_json::to_string(&checkpoint).unwrap();
+        let reloaded: crate::WorldCheckpoint = serde_json::from_str(&json).unwrap();
+        for checkpoint in [&checkpoint, &reloaded] {
+            world.restore(checkpoint);
+            for frame in &frames {
+                world.step();
+                world.assert_bit_equal(frame);
+            }
+        }
+    }
+    
+    #[test]
+    fn test_head_on_collision_records_one_event() {
+        let mut world = World::new(20.0, 20.0);
+        world.gravity = Vec2::ZERO;
+        world.record_events = true;
+        let dt = world.timestep;
+        world.add_circle(Circle::with_velocity(Vec2::new(8.0, 10.0), Vec2::new(3.0, 0.0), Scalar::HALF, Scalar::ONE, dt));
+        world.add_circle(Circle::with_velocity(Vec2::new(12.0, 10.0), Vec2::new(-3.0, 0.0), Scalar::HALF, Scalar::ONE, dt));
+        
+        for _ in 0..60 {
+            world.step();
+        }
+        let events = world.take_events();
+        
+        assert_eq!(events.len(), 1, "{:?}", events);
+        let event = &events[0];
+        assert_eq!((event.idx_a, event.idx_b), (0, 1));
+        assert!(event.impulse > Scalar::ZERO);
+        assert!(event.relative_speed > Scalar::from_float(5.9));
+        assert!(event.step > 0 && event.step < 60);
+        assert!(world.take_events().is_empty());
+        
+        // Not recorded unless asked for
+        world.record_events = false;
+        world.circles[0].set_velocity(Vec2::new(-3.0, 0.0), dt);
+        world.circles[1].set_velocity(Vec2::new(3.0, 0.0), dt);
+        for _ in 0..120 {
+            world.step();
+        }
+        assert!(world.take_events().is_empty());
+    }
+    
+    #[test]
+    fn test_ball_rests_on_platform() {
+        let mut world = World::new(20.0, 20.0);
+        world.add_rect(crate::Rect::new(Vec2::new(5.0, 9.0), Vec2::new(15.0, 10.0)));
+        world.add_circle(Circle::new(Vec2::new(10.0, 14.0), Scalar::HALF, Scalar::ONE));
+        
+        for _ in 0..600 {
+            world.step();
+        }
+        let ball = &world.circles[0];
+        
+        // Settled on top of the platform rather than falling through it
+        assert!((ball.position.y - Scalar::from_float(10.5)).abs() < Scalar::from_float(0.05),
+            "ball at y = {}", ball.position.y);
+        assert!(ball.velocity.magnitude() < Scalar::from_float(0.2), "still moving: {:?}", ball.velocity);
+    }
+    
+    #[test]
+    fn test_ball_bounces_off_wall() {
+        let mut world = World::new(20.0, 20.0);
+        world.gravity = Vec2::ZERO;
+        world.add_rect(crate::Rect::new(Vec2::new(12.0, 2.0), Vec2::new(13.0, 18.0)));
+        let dt = world.timestep;
+        world.add_circle(Circle::with_velocity(Vec2::new(6.0, 10.0), Vec2::new(4.0, 0.0), Scalar::HALF, Scalar::ONE, dt));
+        
+        for _ in 0..120 {
+            world.step();
+        }
+        let ball = &world.circles[0];
+        
+        // Rebounded with the config's restitution, never reaching the wall's far side
+        assert!(ball.velocity.x < Scalar::ZERO, "velocity {:?}", ball.velocity);
+        assert!((ball.velocity.x + Scalar::from_float(3.2)).abs() < Scalar::from_float(0.05), "velocity {:?}", ball.velocity);
+        assert_eq!(ball.velocity.y, Scalar::ZERO);
+        assert!(ball.position.x < Scalar::from_float(11.5));
+    }
+    
+    #[test]
+    fn test_per_circle_restitution_combines_by_rule() {
+        use crate::{CollisionConfig, RestitutionRule};
+        
+        let config = CollisionConfig {
+            restitution_rule: Some(RestitutionRule::GeometricMean),
+            position_correction: Scalar::ZERO,
+            ..CollisionConfig::default()
+        };
+        let (high, low) = (0.9f32, 0.4f32);
+        let ball = |x: f32, y: f32, vx: f32, restitution: f32, dt: Scalar| {
+            let mut ball = Circle::with_velocity(Vec2::new(x, y), Vec2::new(vx, 0.0), Scalar::HALF, Scalar::ONE, dt);
+            ball.restitution = Scalar::from_float(restitution);
+            ball
+        };
+        
+        // Head-on: equal masses rebound at sqrt(e_a * e_b) of the closing speed
+        let mut world = World::with_config(20.0, 20.0, config.clone());
+        world.gravity = Vec2::ZERO;
+        let dt = world.timestep;
+        world.add_circle(ball(8.0, 10.0, 2.0, high, dt));
+        world.add_circle(ball(12.0, 10.0, -2.0, low, dt));
+        for _ in 0..60 {
+            world.step();
+        }
+        let expected = 4.0 * (high * low).sqrt();
+        let separating = (world.circles[1].velocity.x - world.circles[0].velocity.x).to_float();
+        assert!((separating - expected).abs() < 0.01, "{} vs {}", separating, expected);
+        
+        // Off the wall, each ball keeps its own restitution
+        let mut world = World::with_config(20.0, 20.0, config);
+        world.gravity = Vec2::ZERO;
+        world.add_circle(ball(18.0, 10.0, 3.0, high, dt));
+        world.add_circle(ball(18.0, 5.0, 3.0, low, dt));
+        for _ in 0..60 {
+            world.step();
+        }
+        let rebound = |i: usize| world.circles[i].velocity.x.to_float();
+        assert!((rebound(0) + 3.0 * high).abs() < 0.01, "{}", rebound(0));
+        assert!((rebound(1) + 3.0 * low).abs() < 0.01, "{}", rebound(1));
+    }
+    
+    #[test]
+    fn test_validate_reports_each_failure() {
+        use crate::{NonFiniteInput, SimulationInput, ValidationError};
+        
+        let not_positive = |field, circle| ValidationError::NotPositive { field, circle };
+        type Case = (fn(&mut SimulationInput), ValidationError);
+        let cases: [Case; 8] = [
+            (|input| input.timestep = 0.0, not_positive("timestep", None)),
+            (|input| input.gravity[1] = f32::NAN,
+                ValidationError::NonFinite(NonFiniteInput { field: "gravity", circle: None })),
+            (|input| input.world_width = 0.0, not_positive("world_width", None)),
+            (|input| input.world_height = -5.0, not_positive("world_height", None)),
+            (|input| input.circles[1].radius = 0.0, not_positive("radius", Some(1))),
+            (|input| input.circles[2].mass = -1.0, not_positive("mass", Some(2))),
+            (|input| input.circles[3].position[0] = -0.5, ValidationError::OutOfBounds { circle: 3 }),
+            (|input| input.circles[0].position[1] = 1000.0, ValidationError::OutOfBounds { circle: 0 }),
+        ];
+        
+        for (break_input, expected) in cases {
+            let mut input = crate::scenarios::pool_break_15();
+            break_input(&mut input);
+            assert_eq!(input.validate(), Err(expected));
+        }
+        assert_eq!(not_positive("radius", Some(1)).to_string(), "circles[1].radius must be positive");
+        
+        for name in crate::scenarios::list_scenarios() {
+            let input = crate::scenarios::get_scenario(name).unwrap();
+            assert_eq!(input.validate(), Ok(()), "built-in scenario {}", name);
+        }
+    }
+    
+    #[test]
+    fn test_toml_with_zero_timestep_is_rejected() {
+        use crate::ValidationError;
+        
+        let mut input = crate::scenarios::simple_drop();
+        input.timestep = 0.0;
+        let path = std::env::temp_dir().join(format!("determinisk-zero-dt-{}.toml", std::process::id()));
+        crate::scenarios::to_toml_file(&input, &path).unwrap();
+        let result = crate::scenarios::from_toml_file(&path);
+        std::fs::remove_file(&path).unwrap();
+        
+        let err = result.unwrap_err();
+        let crate::DeterminiskError::Validation(inner) = &err else {
+            panic!("expected a validation error, got {:?}", err);
+        };
+        assert_eq!(
+            inner.downcast_ref::<ValidationError>(),
+            Some(&ValidationError::NotPositive { field: "timestep", circle: None }),
+        );
+        assert_eq!(err.to_string(), "timestep must be positive");
+    }
+    
+    #[test]
+    fn test_bundled_scenario_files_validate() {
+        let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/scenarios");
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            crate::scenarios::from_file(&path).unwrap_or_else(|err| panic!("{}: {}", path.display(), err));
+        }
+    }
+    
+    #[test]
+    fn test_continuous_detection_stops_tunneling() {
+        use crate::CollisionConfig;
+        
+        let shoot = |continuous: bool| {
+            let mut world = World::with_config(40.0, 20.0, CollisionConfig {
+                continuous,
+                ..CollisionConfig::default()
+            });
+            world.gravity = Vec2::ZERO;
+            let dt = world.timestep;
+            // Moves about 1.5 per step, more than the 0.6 combined radius
+            world.add_circle(Circle::with_velocity(
+                Vec2::new(2.75, 10.0),
+                Vec2::new(90.0, 0.0),
+                Scalar::from_float(0.1),
+                Scalar::ONE,
+                dt,
+            ));
+            world.add_circle(Circle::new(Vec2::new(20.0, 10.0), Scalar::HALF, Scalar::ONE));
+            world.record_events = true;
+            for _ in 0..16 {
+                world.step();
+            }
+            let (shooter, target) = (world.circles[0], world.circles[1]);
+            (world.take_events().len(), target.position.x - shooter.position.x, target.velocity.x)
+        };
+        
+        // Discrete detection misses the target entirely
+        let (events, gap, target_vx) = shoot(false);
+        assert_eq!(events, 0);
+        assert!(gap < Scalar::ZERO, "shooter should have passed the target");
+        assert_eq!(target_vx, Scalar::ZERO);
+        
+        // Swept detection catches it: the target is knocked forward and the
+        // shooter stays behind it
+        let (events, gap, target_vx) = shoot(true);
+        assert_eq!(events, 1);
+        assert!(gap > Scalar::from_float(0.6), "gap {}", gap);
+        assert!(target_vx > Scalar::from_float(50.0), "target speed {}", target_vx);
+    }
+    
+    #[test]
+    fn test_cached_velocity_matches_verlet_after_step() {
+        let dt = Scalar::from_float(1.0 / 60.0);
+        let mut world = World::new(10.0, 10.0);
+        world.timestep = dt;
+        // Head-on pair plus one resting near the floor, so the step covers
+        // circle-circle and boundary contacts as well as free flight
+        world.add_circle(Circle::with_velocity(
+            Vec2::new(3.0, 5.0),
+            Vec2::new(4.0, 0.0),
+            Scalar::from_float(0.5), Scalar::ONE, dt,
+        ));
+        world.add_circle(Circle::with_velocity(
+            Vec2::new(4.1, 5.0),
+            Vec2::new(-4.0, 0.0),
+            Scalar::from_float(0.5), Scalar::from_float(2.0), dt,
+        ));
+        world.add_circle(Circle::new(
+            Vec2::new(7.0, 0.55),
+            Scalar::from_float(0.5), Scalar::ONE,
+        ));
+        
+        let (mut pair_hits, mut boundary_hits) = (0, 0);
+        for _ in 0..30 {
+            world.step();
+            pair_hits += world.last_contacts().events.len();
+            boundary_hits += world.last_contacts().boundary_hits;
+            for circle in &world.circles {
+                assert_eq!(circle.velocity(), circle.verlet_velocity(dt));
+                assert!(circle.velocity_consistent(dt));
+            }
+        }
+        assert!(pair_hits > 0 && boundary_hits > 0);
+    }
+    
+    #[test]
+    fn test_assert_bit_equal_covers_future_state() {
+        use crate::{ParamChange, Schedule};
+        
+        let world = World::from_input(&crate::scenarios::pool_break());
+        type Tweak = fn(&mut World);
+        let tweaks: [(&str, Tweak); 7] = [
+            ("schedule", |w| w.schedule = Schedule::new().at(5, ParamChange::Gravity(Vec2::ZERO))),
+            ("steps_taken", |w| w.steps_taken += 1),
+            ("y_down", |w| w.y_down = !w.y_down),
+            ("max_impulse", |w| w.collision_config.max_impulse = Some(Scalar::ONE)),
+            ("correction_order", |w| w.collision_config.correction_order = crate::CorrectionOrder::Split),
+            ("angle", |w| w.circles[0].angle = Scalar::HALF),
+            ("angular_velocity", |w| w.circles[0].angular_velocity = Scalar::ONE),
+        ];
+        
+        for (field, tweak) in tweaks {
+            let mut tweaked = world.clone();
+            tweak(&mut tweaked);
+            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| world.assert_bit_equal(&tweaked)));
+            assert!(result.is_err(), "{} difference went unnoticed", field);
+        }
+        world.assert_bit_equal(&world.clone());
+    }
+    
+    #[test]
+    fn test_set_friction_mid_run_slows_sliding_ball() {
+        let dt = Scalar::from_float(1.0 / 60.0);
+        let mut smooth = World::new(20.0, 10.0);
+        smooth.add_circle(Circle::with_velocity(
+            Vec2::new(2.0, 0.5),
+            Vec2::new(3.0, 0.0),
+            Scalar::HALF, Scalar::ONE, dt,
+        ));
+        
+        // Let the ball settle onto the floor, then roughen it for one copy
+        for _ in 0..30 {
+            smooth.step();
+        }
+        let mut rough = smooth.clone();
+        rough.set_friction(0.5);
+        assert_eq!(rough.collision_config.friction, Scalar::from_float(0.5));
+        
+        for _ in 0..30 {
+            smooth.step();
+            rough.step();
+        }
+        let (smooth_vx, rough_vx) = (smooth.circles[0].velocity().x, rough.circles[0].velocity().x);
+        assert!(rough_vx < smooth_vx, "rough {} vs smooth {}", rough_vx, smooth_vx);
+    }
+    
+    #[test]
+    fn test_world_is_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        
+        // A collision filter must not pin a world to one thread
+        assert_send_sync::<World>();
+        assert_send_sync::<crate::CollisionFilter>();
+    }
+}
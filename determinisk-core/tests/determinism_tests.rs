@@ -1,6 +1,7 @@
 //! Tests specifically for deterministic behavior
 
-use determinisk_core::{Scalar, Vec2, Circle, World};
+use determinisk_core::{Scalar, Vec2, Circle, World, SpatialGrid, Collision};
+use determinisk_core::spatial::detect_collisions;
 use std::collections::HashMap;
 
 /// Create a complex world with many interacting elements
@@ -80,18 +81,7 @@ fn test_determinism_with_different_step_orders() {
     }
     
     // Compare all circles
-    for i in 0..world1.circles.len() {
-        assert_eq!(
-            world1.circles[i].position.x.to_bits(),
-            world2.circles[i].position.x.to_bits(),
-            "Circle {} x position differs", i
-        );
-        assert_eq!(
-            world1.circles[i].position.y.to_bits(),
-            world2.circles[i].position.y.to_bits(),
-            "Circle {} y position differs", i
-        );
-    }
+    world1.assert_bit_equal(&world2);
 }
 
 #[test]
@@ -169,8 +159,8 @@ fn test_determinism_state_hash() {
                 // Hash world state
                 let mut hasher = Sha256::new();
                 for circle in &world.circles {
-                    hasher.update(&circle.position.x.to_bits().to_le_bytes());
-                    hasher.update(&circle.position.y.to_bits().to_le_bytes());
+                    hasher.update(circle.position.x.to_bits().to_le_bytes());
+                    hasher.update(circle.position.y.to_bits().to_le_bytes());
                 }
                 let hash = hasher.finalize().to_vec();
                 
@@ -224,14 +214,79 @@ fn test_determinism_with_extreme_values() {
     }
     
     // Should still be deterministic
-    for i in 0..2 {
-        assert_eq!(
-            world.circles[i].position.x.to_bits(),
-            world2.circles[i].position.x.to_bits()
-        );
-        assert_eq!(
-            world.circles[i].position.y.to_bits(),
-            world2.circles[i].position.y.to_bits()
-        );
+    world.assert_bit_equal(&world2);
+}
+
+#[test]
+fn test_assert_bit_equal_identical_worlds() {
+    let mut world1 = create_test_world(7);
+    let mut world2 = create_test_world(7);
+    for _ in 0..50 {
+        world1.step();
+        world2.step();
     }
-}
\ No newline at end of file
+    world1.assert_bit_equal(&world2);
+}
+
+#[test]
+#[should_panic(expected = "circle 0 position.x differs")]
+fn test_assert_bit_equal_reports_first_difference() {
+    let world1 = create_test_world(7);
+    let mut world2 = world1.clone();
+    world2.step();
+    world1.assert_bit_equal(&world2);
+}
+
+/// Narrow-phase results for `pairs`, sorted so broad-phase order doesn't matter
+fn sorted_collisions(circles: &[Circle], pairs: &[(usize, usize)]) -> Vec<Collision> {
+    let mut collisions = detect_collisions(circles, pairs);
+    collisions.sort_by_key(|c| (c.idx_a, c.idx_b));
+    collisions
+}
+
+#[test]
+fn test_grid_broad_phase_matches_all_pairs() {
+    // The exhaustive pair list is the reference any broad phase must agree
+    // with; a missed pair here would silently change the physics
+    const SEEDS: [u32; 5] = [0, 7, 42, 1337, 9001];
+    const STEPS: usize = 200;
+    let mut total_collisions = 0;
+    
+    for seed in SEEDS {
+        let mut world = create_test_world(seed);
+        let n = world.circles.len();
+        
+        for step in 0..STEPS {
+            let circles = &world.circles;
+            // Every pair whose bounding boxes touch; skipping disjoint ones
+            // loses nothing and keeps squared distances inside Q16.16
+            let all_pairs: Vec<(usize, usize)> = (0..n)
+                .flat_map(|a| ((a + 1)..n).map(move |b| (a, b)))
+                .filter(|&(a, b)| {
+                    let reach = circles[a].radius + circles[b].radius;
+                    let delta = circles[b].position - circles[a].position;
+                    delta.x.abs() <= reach && delta.y.abs() <= reach
+                })
+                .collect();
+            let cell_size = SpatialGrid::default_cell_size(circles);
+            let grid = SpatialGrid::build(circles, cell_size, world.bounds.x, world.bounds.y);
+            
+            let from_grid = sorted_collisions(circles, &grid.get_collision_pairs());
+            let reference = sorted_collisions(circles, &all_pairs);
+            
+            assert_eq!(from_grid.len(), reference.len(), "seed {} step {}: collision count", seed, step);
+            total_collisions += reference.len();
+            for (g, r) in from_grid.iter().zip(&reference) {
+                assert_eq!((g.idx_a, g.idx_b), (r.idx_a, r.idx_b), "seed {} step {}", seed, step);
+                assert!(
+                    g.normal == r.normal && g.depth == r.depth && g.contact == r.contact,
+                    "seed {} step {}: pair ({}, {}) differs", seed, step, g.idx_a, g.idx_b
+                );
+            }
+            
+            world.step();
+        }
+    }
+    
+    assert!(total_collisions > 0, "scenes never collided; the comparison proved nothing");
+}
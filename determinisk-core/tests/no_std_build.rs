@@ -0,0 +1,25 @@
+//! Guard against `std` leaking into the core crate
+
+use std::path::Path;
+use std::process::Command;
+
+/// The `no_std_world` example must type-check with `std` off
+///
+/// Runs a nested `cargo check` into its own target directory, so it doesn't
+/// contend for the lock held by the outer `cargo test`.
+#[test]
+fn test_no_std_example_compiles() {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let output = Command::new(env!("CARGO"))
+        .args(["check", "--quiet", "--example", "no_std_world", "--no-default-features"])
+        .current_dir(manifest_dir)
+        .env("CARGO_TARGET_DIR", manifest_dir.join("../target/no_std_check"))
+        .output()
+        .expect("failed to run cargo");
+    
+    assert!(
+        output.status.success(),
+        "no_std build failed:\n{}",
+        String::from_utf8_lossy(&output.stderr),
+    );
+}
@@ -7,10 +7,7 @@ fn calculate_total_energy(world: &World) -> f32 {
     let mut total = 0.0;
     
     for circle in &world.circles {
-        // Kinetic energy: 0.5 * m * v^2
-        let velocity = circle.velocity;
-        let speed_squared = velocity.magnitude_squared().to_float();
-        let ke = 0.5 * circle.mass.to_float() * speed_squared;
+        let ke = circle.kinetic_energy(world.timestep).to_float();
         
         // Potential energy: m * g * h
         let height = circle.position.y.to_float();
@@ -27,9 +24,7 @@ fn calculate_total_momentum(world: &World) -> Vec2 {
     let mut total = Vec2::ZERO;
     
     for circle in &world.circles {
-        let velocity = circle.velocity;
-        let momentum = velocity * circle.mass;
-        total = total + momentum;
+        total += circle.momentum(world.timestep);
     }
     
     total
@@ -249,7 +244,7 @@ fn test_galilean_relativity() {
     );
     
     // Horizontal velocity should be preserved in world2
-    let vx2 = world2.circles[0].velocity.x.to_float();
+    let vx2 = world2.circles[0].velocity().x.to_float();
     assert!(
         (vx2 - 20.0).abs() < 0.1,
         "Horizontal velocity not preserved: {:.3}",
@@ -316,4 +311,45 @@ fn test_pendulum_period() {
             period1, theoretical_period
         );
     }
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_drag_shortens_projectile_range() {
+    /// Launch a single projectile and return the x position where it lands
+    fn launch_range(drag: f32) -> Scalar {
+        let mut world = World::new(1000.0, 500.0);
+        
+        let mut ball = Circle::new(
+            Vec2::new(10.0, 10.0),
+            Scalar::from_float(1.0),
+            Scalar::from_float(1.0),
+        );
+        ball.drag = Scalar::from_float(drag);
+        ball.set_velocity(Vec2::new(20.0, 20.0), world.timestep);
+        world.add_circle(ball);
+        
+        for _ in 0..600 {
+            world.step();
+            
+            // Landed once it falls back to the launch height
+            if world.circles[0].position.y < Scalar::from_float(10.0) {
+                break;
+            }
+        }
+        
+        world.circles[0].position.x
+    }
+    
+    let no_drag = launch_range(0.0);
+    let light_drag = launch_range(0.1);
+    let heavy_drag = launch_range(0.5);
+    
+    assert!(
+        light_drag < no_drag,
+        "Drag should shorten range: {} vs {}", light_drag, no_drag
+    );
+    assert!(
+        heavy_drag < light_drag,
+        "Higher drag should shorten range further: {} vs {}", heavy_drag, light_drag
+    );
+}
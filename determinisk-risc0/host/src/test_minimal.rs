@@ -16,16 +16,22 @@ struct SimulationOutput {
 fn main() {
     // Create a minimal simulation input
     let input = SimulationInput {
+        name: None,
+        description: None,
+        author: None,
         world_width: 10.0,
         world_height: 10.0,
         gravity: [0.0, -9.81],
+        y_down: false,
         timestep: 0.016,
         restitution: 0.8,
         position_correction: 0.8,
+        collision_preset: None,
         circles: vec![],
         num_steps: 1,
         record_trajectory: false,
         seed: 0,
+        checkpoint_interval: 0,
     };
 
     println!("Creating minimal test proof...");
@@ -0,0 +1,27 @@
+#![no_main]
+#![no_std]
+
+extern crate alloc;
+use alloc::vec::Vec;
+
+risc0_zkvm::guest::entry!(main);
+use risc0_zkvm::guest::env;
+use determinisk_core::JournalOutput;
+
+fn main() {
+    // Image ID of the physics guest and the journals of the receipts to roll up
+    let (physics_image_id, journals): ([u32; 8], Vec<Vec<u8>>) = env::read();
+    
+    // Each verify adds an assumption that the host must resolve with the
+    // matching receipt, so this proof only exists if every run was proven
+    let mut state_hashes = Vec::with_capacity(journals.len());
+    for journal in &journals {
+        env::verify(physics_image_id, journal).expect("physics receipt must verify");
+        let output: JournalOutput = risc0_zkvm::serde::from_slice(journal)
+            .expect("journal must decode as JournalOutput");
+        state_hashes.push(output.state_hash);
+    }
+    
+    // Commit every covered run's state hash, in input order
+    env::commit(&state_hashes);
+}
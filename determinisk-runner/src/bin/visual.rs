@@ -6,6 +6,7 @@
 use clap::Parser;
 use determinisk_core::scenarios;
 use determinisk_runner::ZkVmBackend;
+use determinisk_runner::proof::{mock::{prove_segments, MockBackend}, ProofBackend, ProofProgress};
 use determinisk_runner::render::{visualize_trace_with_updates, ProofMetrics};
 use determinisk_core::{World, SimulationInput};
 use std::sync::{Arc, Mutex};
@@ -42,7 +43,7 @@ fn generate_proof(
     input: SimulationInput,
     metrics: Arc<Mutex<Option<ProofMetrics>>>,
     verbose: bool,
-    #[allow(unused_variables)] segment_po2: u32,
+    segment_po2: u32,
 ) -> Option<ProofMetrics> {
     if verbose {
         println!("Generating proof with backend: {:?}", backend);
@@ -59,31 +60,40 @@ fn generate_proof(
         proving_time_ms: 0,
         verification_time_ms: None,
         zkvm_backend: "Mock (Generating...)".to_string(),
+        peak_memory_bytes: None,
     });
     
+    // Count up the segments on screen while proving
+    let publish_progress = |progress: ProofProgress| {
+        if let Some(current) = metrics.lock().unwrap().as_mut() {
+            current.segments = progress.segments_done;
+        }
+    };
+    
     // Simulate proof generation based on backend
     let proof_metrics = match backend {
         ZkVmBackend::Mock => {
-            // Mock proof generation with longer delay
-            thread::sleep(std::time::Duration::from_secs(5));
+            // Mock proof generation, one segment at a time
+            let segments = prove_segments(&input, segment_po2, publish_progress);
             
-            // Use the input to avoid unused warning
-            let _num_steps = input.num_steps;
+            // Time a real verify call rather than reporting a fixed figure
+            let (_, verification_time) = MockBackend::default().verify_timed(&[]);
             
             ProofMetrics {
                 total_cycles: 100_000,
                 user_cycles: Some(80_000),
-                segments: 1,
+                segments,
                 proof_size_bytes: 1024,
                 proving_time_ms: 2000,
-                verification_time_ms: Some(10),
+                verification_time_ms: Some(verification_time),
                 zkvm_backend: "Mock".to_string(),
+                peak_memory_bytes: None,
             }
         }
         #[cfg(feature = "risc0")]
         ZkVmBackend::Risc0 => {
-            use methods::{PHYSICS_GUEST_ELF, PHYSICS_GUEST_ID};
-            use risc0_zkvm::{default_prover, ExecutorEnv};
+            use determinisk_runner::proof::risc0::prove_with_progress;
+            use methods::PHYSICS_GUEST_ID;
             
             // Update status
             *metrics.lock().unwrap() = Some(ProofMetrics {
@@ -94,21 +104,13 @@ fn generate_proof(
                 proving_time_ms: 0,
                 verification_time_ms: None,
                 zkvm_backend: "RISC Zero (Generating...)".to_string(),
+                peak_memory_bytes: None,
             });
             
-            // Create executor environment with simulation input and segment configuration
-            let env = ExecutorEnv::builder()
-                .write(&input)
-                .unwrap()
-                .segment_limit_po2(segment_po2)
-                .build()
-                .unwrap();
-            
-            // Generate proof
-            let prover = default_prover();
+            // Generate proof, counting segments as the executor seals them
             let prove_start = Instant::now();
             
-            match prover.prove(env, PHYSICS_GUEST_ELF) {
+            match prove_with_progress(&input, segment_po2, publish_progress) {
                 Ok(prove_info) => {
                     let proving_time = prove_start.elapsed().as_millis();
                     
@@ -156,6 +158,7 @@ fn generate_proof(
                         proving_time_ms: proving_time,
                         verification_time_ms: Some(verification_time),
                         zkvm_backend: "RISC Zero".to_string(),
+                        peak_memory_bytes: None,
                     }
                 }
                 Err(e) => {
@@ -166,15 +169,18 @@ fn generate_proof(
                         segments: 1,
                         proof_size_bytes: 1024,
                         proving_time_ms: 2000,
-                        verification_time_ms: Some(10),
+                        verification_time_ms: None,
                         zkvm_backend: format!("Mock (RISC Zero error: {})", e),
+                        peak_memory_bytes: None,
                     }
                 }
             }
         }
         #[cfg(feature = "sp1")]
         ZkVmBackend::Sp1 => {
-            todo!("SP1 proof generation")
+            eprintln!("{}", determinisk_runner::runner::SP1_UNAVAILABLE);
+            *metrics.lock().unwrap() = None;
+            return None;
         }
     };
     
@@ -197,12 +203,8 @@ async fn main() {
     let cli = Cli::parse();
     
     // Load simulation input
-    let sim_input = if cli.input.ends_with(".toml") {
-        scenarios::from_toml_file(&cli.input).expect("Failed to load TOML file")
-    } else {
-        scenarios::get_scenario(&cli.input)
-            .expect(&format!("Unknown scenario: {}", cli.input))
-    };
+    let sim_input = scenarios::load(&cli.input)
+        .unwrap_or_else(|err| panic!("Failed to load {}: {}", cli.input, err));
     
     // Configure backend
     let backend = match cli.backend.as_str() {
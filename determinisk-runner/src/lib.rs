@@ -2,12 +2,17 @@
 
 pub mod runner;
 
-#[cfg(feature = "visual")]
+#[cfg(any(feature = "visual", feature = "image"))]
 pub mod render;
 
 pub mod proof;
 
-pub use runner::{RunnerConfig, SimulationRunner, ZkVmBackend};
+pub use determinisk_core::DeterminiskError;
+pub use proof::{ProofMetrics, ProofProgress};
+pub use runner::{BenchSummary, RunnerConfig, RunnerConfigBuilder, SimulationRunner, ZkVmBackend};
 
 #[cfg(feature = "visual")]
-pub use render::{visualize_trace, visualize_trace_with_updates, ProofMetrics};
\ No newline at end of file
+pub use render::{visualize_trace, visualize_trace_with_updates};
+
+#[cfg(feature = "image")]
+pub use render::{export_frames, render_initial_state};
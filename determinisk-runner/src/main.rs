@@ -1,7 +1,7 @@
 //! CLI for running determinisk simulations
 
 use clap::{Parser, Subcommand};
-use determinisk_core::scenarios;
+use determinisk_core::{scenarios, Scalar, SimulationInput};
 use determinisk_runner::{RunnerConfig, SimulationRunner, ZkVmBackend};
 
 #[derive(Parser)]
@@ -32,15 +32,53 @@ enum Commands {
         #[arg(short, long, default_value = "mock")]
         backend: String,
         
-        /// Segment size for RISC Zero proving (power of 2, default 20 for 6GB GPUs)
-        #[arg(long, default_value = "20")]
-        segment_po2: u32,
+        /// Segment size for RISC Zero proving (power of 2, defaults to a
+        /// per-scenario recommendation capped at 20 for 6GB GPUs)
+        #[arg(long)]
+        segment_po2: Option<u32>,
+        
+        /// Warn when peak proving memory nears this budget (MB)
+        #[arg(long)]
+        memory_budget_mb: Option<u64>,
+        
+        /// Verbose output
+        #[arg(long)]
+        verbose: bool,
+    },
+    
+    /// Prove a simulation several times and report median metrics
+    Bench {
+        /// Path to TOML file or built-in scenario name
+        input: String,
+        
+        /// Number of proofs to generate
+        #[arg(short, long, default_value = "5")]
+        runs: usize,
+        
+        /// Proof backend (mock, risc0, sp1)
+        #[arg(short, long, default_value = "mock")]
+        backend: String,
+        
+        /// Segment size for RISC Zero proving (power of 2, defaults to a
+        /// per-scenario recommendation capped at 20 for 6GB GPUs)
+        #[arg(long)]
+        segment_po2: Option<u32>,
+        
+        /// Warn when peak proving memory nears this budget (MB)
+        #[arg(long)]
+        memory_budget_mb: Option<u64>,
         
         /// Verbose output
         #[arg(long)]
         verbose: bool,
     },
     
+    /// Describe a scenario without running it
+    Info {
+        /// Path to TOML file or built-in scenario name
+        input: String,
+    },
+    
     /// List available scenarios
     List,
 }
@@ -54,38 +92,34 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let cli = Cli::parse();
     
     match cli.command {
-        Commands::Run { input, visual, prove, backend, segment_po2, verbose } => {
+        Commands::Run { input, visual, prove, backend, segment_po2, memory_budget_mb, verbose } => {
             // Load simulation input
-            let sim_input = if input.ends_with(".toml") {
-                // Load from TOML file
-                scenarios::from_toml_file(&input)?
-            } else {
-                // Try as built-in scenario
-                scenarios::get_scenario(&input)
-                    .ok_or_else(|| format!("Unknown scenario: {}", input))?
-            };
+            let sim_input = scenarios::load(&input)?;
             
             // Configure backend
-            let backend = match backend.as_str() {
-                #[cfg(feature = "risc0")]
-                "risc0" => ZkVmBackend::Risc0,
-                #[cfg(feature = "sp1")]
-                "sp1" => ZkVmBackend::Sp1,
-                _ => ZkVmBackend::Mock,
-            };
+            let backend = parse_backend(&backend);
             
             // Configure runner
-            let config = RunnerConfig {
-                visualize: visual,
-                prove,
-                backend,
-                verbose,
-                segment_po2,
-            };
+            let config = RunnerConfig::builder()
+                .visualize(visual)
+                .prove(prove)
+                .backend(backend)
+                .verbose(verbose)
+                .segment_po2(segment_po2.unwrap_or_else(|| sim_input.recommended_segment_po2()))
+                .memory_budget_bytes(memory_budget_mb.map(|mb| mb * 1024 * 1024))
+                .build();
             
             // Run simulation
             let runner = SimulationRunner::new(config);
-            let result = runner.run(sim_input)?;
+            let result = runner.run_with_status(sim_input, |status| {
+                if status.total_cycles == 0 && status.segments > 0 {
+                    println!("Proof status: {} ({} segments so far)", status.zkvm_backend, status.segments);
+                } else if status.total_cycles == 0 {
+                    println!("Proof status: {}", status.zkvm_backend);
+                } else {
+                    println!("Proof status: {} done ({} cycles)", status.zkvm_backend, status.total_cycles);
+                }
+            })?;
             
             // Display results
             if verbose {
@@ -101,18 +135,119 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                     }
                     println!("Proof size: {} KB", metrics.proof_size_bytes / 1024);
                     println!("Proving time: {:.2}s", metrics.proving_time_ms as f32 / 1000.0);
+                    if let Some(peak) = metrics.peak_memory_bytes {
+                        println!("Peak memory: {} MB", peak / (1024 * 1024));
+                    }
+                }
+                
+                if let Some(journal) = result.journal {
+                    let show = |label: &str, bits: determinisk_core::ConservationBits| {
+                        println!("{} momentum: ({:.4}, {:.4}), energy: {:.4}",
+                            label,
+                            Scalar::from_bits(bits.momentum.0).to_float(),
+                            Scalar::from_bits(bits.momentum.1).to_float(),
+                            Scalar::from_bits(bits.energy).to_float(),
+                        );
+                    };
+                    println!("\n=== COMMITTED CONSERVATION ===");
+                    show("Initial", journal.initial_conservation);
+                    show("Final", journal.final_conservation);
                 }
             }
         }
         
+        Commands::Bench { input, runs, backend, segment_po2, memory_budget_mb, verbose } => {
+            let sim_input = scenarios::load(&input)?;
+            let config = RunnerConfig::builder()
+                .prove(true)
+                .backend(parse_backend(&backend))
+                .verbose(verbose)
+                .segment_po2(segment_po2.unwrap_or_else(|| sim_input.recommended_segment_po2()))
+                .memory_budget_bytes(memory_budget_mb.map(|mb| mb * 1024 * 1024))
+                .build();
+            
+            let summary = SimulationRunner::new(config).bench(&sim_input, runs)?;
+            
+            println!("=== BENCH ({} runs, {} kept) ===", summary.runs, summary.kept);
+            println!("Backend: {}", summary.zkvm_backend);
+            println!("Median cycles: {}", summary.median_total_cycles);
+            println!("Median proving time: {:.2}s", summary.median_proving_time_ms as f32 / 1000.0);
+            println!("Median proof size: {} KB", summary.median_proof_size_bytes / 1024);
+        }
+        
+        Commands::Info { input } => {
+            let sim_input = scenarios::load(&input)?;
+            print!("{}", describe_input(&sim_input));
+        }
+        
         Commands::List => {
             println!("Available scenarios:");
             for name in scenarios::list_scenarios() {
-                println!("  - {}", name);
+                let description = scenarios::get_scenario(name).and_then(|input| input.description);
+                match description {
+                    Some(description) => println!("  - {:<22} {}", name, description),
+                    None => println!("  - {}", name),
+                }
             }
             println!("\nYou can also provide a path to a TOML file.");
         }
     }
     
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Human-readable summary of an input, as printed by `info`
+fn describe_input(input: &SimulationInput) -> String {
+    use std::fmt::Write;
+    
+    let mut out = String::new();
+    // Writing to a String can't fail
+    let mut line = |text: String| writeln!(out, "{}", text).unwrap();
+    
+    if let Some(name) = &input.name {
+        line(format!("Name: {}", name));
+    }
+    if let Some(description) = &input.description {
+        line(format!("Description: {}", description));
+    }
+    if let Some(author) = &input.author {
+        line(format!("Author: {}", author));
+    }
+    line(format!("World: {} x {} m", input.world_width, input.world_height));
+    line(format!("Gravity: ({}, {}) m/s²", input.gravity[0], input.gravity[1]));
+    line(format!("Circles: {}", input.circles.len()));
+    line(format!("Steps: {} (dt = {:.4}s, {:.2}s total)",
+        input.num_steps, input.timestep, input.num_steps as f32 * input.timestep));
+    match &input.collision_preset {
+        Some(preset) => line(format!("Restitution: {} (preset {})", input.restitution, preset)),
+        None => line(format!("Restitution: {}", input.restitution)),
+    }
+    line(format!("Estimated cycles: ~{} (segment_po2 {})",
+        input.estimated_cycles(), input.recommended_segment_po2()));
+    out
+}
+
+/// Map a backend name to a compiled-in backend, falling back to mock
+fn parse_backend(name: &str) -> ZkVmBackend {
+    match name {
+        #[cfg(feature = "risc0")]
+        "risc0" => ZkVmBackend::Risc0,
+        #[cfg(feature = "sp1")]
+        "sp1" => ZkVmBackend::Sp1,
+        _ => ZkVmBackend::Mock,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_info_describes_simple_drop() {
+        let input = scenarios::load("simple_drop").unwrap();
+        let info = describe_input(&input);
+        assert!(info.contains("Circles: 1\n"), "{}", info);
+        assert!(info.contains("Steps: 120 "), "{}", info);
+        assert!(info.contains(&format!("Estimated cycles: ~{} ", input.estimated_cycles())), "{}", info);
+    }
+}
@@ -0,0 +1,100 @@
+//! Roll many RISC Zero receipts up into one proof via composition
+
+use determinisk_core::SimulationInput;
+use methods::{AGGREGATE_GUEST_ELF, AGGREGATE_GUEST_ID, PHYSICS_GUEST_ID};
+use risc0_zkvm::{default_prover, ExecutorEnv, ProverOpts, Receipt};
+
+/// One receipt attesting to a batch of physics runs
+pub struct AggregatedProof {
+    /// Succinct receipt of the aggregation guest
+    pub receipt: Receipt,
+    /// State hash committed by each covered run, in input order
+    pub state_hashes: Vec<[u8; 32]>,
+    /// Serialized size of the aggregate receipt
+    pub aggregate_size_bytes: usize,
+    /// Sum of the serialized sizes of the individual receipts
+    pub individual_size_bytes: usize,
+}
+
+impl AggregatedProof {
+    /// Verify the aggregate and that it commits exactly `state_hashes`
+    pub fn verify(&self) -> Result<(), Box<dyn std::error::Error>> {
+        self.receipt.verify(AGGREGATE_GUEST_ID)?;
+        let committed: Vec<[u8; 32]> = self.receipt.journal.decode()?;
+        if committed != self.state_hashes {
+            return Err("aggregate journal does not match the covered state hashes".into());
+        }
+        Ok(())
+    }
+}
+
+/// Prove one input with the physics guest, returning its receipt
+pub fn prove_receipt(input: &SimulationInput) -> Result<Receipt, Box<dyn std::error::Error>> {
+    let env = ExecutorEnv::builder().write(input)?.build()?;
+    let receipt = default_prover()
+        .prove_with_opts(env, methods::PHYSICS_GUEST_ELF, &ProverOpts::succinct())?
+        .receipt;
+    Ok(receipt)
+}
+
+/// Aggregate physics guest receipts into a single succinct receipt
+///
+/// Each receipt is added as an assumption and verified inside the
+/// aggregation guest, so verifying the result once attests to every run.
+pub fn aggregate(receipts: &[Receipt]) -> Result<AggregatedProof, Box<dyn std::error::Error>> {
+    let journals: Vec<Vec<u8>> = receipts.iter().map(|r| r.journal.bytes.clone()).collect();
+    
+    let mut builder = ExecutorEnv::builder();
+    for receipt in receipts {
+        builder.add_assumption(receipt.clone());
+    }
+    let env = builder.write(&(PHYSICS_GUEST_ID, journals))?.build()?;
+    
+    let receipt = default_prover()
+        .prove_with_opts(env, AGGREGATE_GUEST_ELF, &ProverOpts::succinct())?
+        .receipt;
+    let state_hashes: Vec<[u8; 32]> = receipt.journal.decode()?;
+    
+    let aggregate_size_bytes = bincode::serialize(&receipt)?.len();
+    let mut individual_size_bytes = 0;
+    for individual in receipts {
+        individual_size_bytes += bincode::serialize(individual)?.len();
+    }
+    
+    Ok(AggregatedProof {
+        receipt,
+        state_hashes,
+        aggregate_size_bytes,
+        individual_size_bytes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use determinisk_core::scenarios;
+    
+    #[test]
+    #[ignore = "generates three real RISC Zero proofs"]
+    fn test_aggregate_covers_each_run() {
+        let inputs = [scenarios::simple_drop(), scenarios::three_body_collision()];
+        let receipts: Vec<Receipt> = inputs
+            .iter()
+            .map(|input| prove_receipt(input).unwrap())
+            .collect();
+        
+        let aggregated = aggregate(&receipts).unwrap();
+        aggregated.verify().unwrap();
+        
+        // One committed hash per run, matching the native simulation
+        assert_eq!(aggregated.state_hashes.len(), inputs.len());
+        for (input, hash) in inputs.iter().zip(&aggregated.state_hashes) {
+            assert_eq!(*hash, input.execute_journal().state_hash);
+        }
+        
+        println!(
+            "aggregate: {} bytes, individual receipts: {} bytes",
+            aggregated.aggregate_size_bytes, aggregated.individual_size_bytes
+        );
+    }
+}
@@ -0,0 +1,115 @@
+//! Best-effort peak memory sampling around proof generation
+//!
+//! RISC Zero proving can need several GB, and on small machines it tends to
+//! be OOM-killed without a useful message. Sampling resident memory while
+//! proving tells users how close a run came, so they can lower
+//! `segment_po2` before it fails. Only Linux is supported (via `/proc`);
+//! elsewhere the sampler reports `None`.
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// How often the sampler thread reads resident memory
+pub const SAMPLE_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Fraction of the budget at which `budget_warning` starts warning
+const BUDGET_WARNING_FRACTION: f64 = 0.9;
+
+/// Resident set size of this process in bytes, if the platform exposes it
+pub fn current_rss_bytes() -> Option<u64> {
+    #[cfg(target_os = "linux")]
+    {
+        let status = std::fs::read_to_string("/proc/self/status").ok()?;
+        let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+        let kb: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+        Some(kb * 1024)
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        None
+    }
+}
+
+/// Background thread tracking the highest resident memory seen
+///
+/// Process-wide: anything else running in the process (other threads,
+/// parallel tests) counts too, and spikes shorter than `SAMPLE_INTERVAL`
+/// can be missed.
+pub struct PeakRssSampler {
+    peak: Arc<AtomicU64>,
+    stop: Arc<AtomicBool>,
+    handle: JoinHandle<()>,
+}
+
+impl PeakRssSampler {
+    /// Start sampling on a background thread
+    pub fn start() -> Self {
+        let peak = Arc::new(AtomicU64::new(current_rss_bytes().unwrap_or(0)));
+        let stop = Arc::new(AtomicBool::new(false));
+        let handle = {
+            let (peak, stop) = (peak.clone(), stop.clone());
+            thread::spawn(move || {
+                while !stop.load(Ordering::Relaxed) {
+                    if let Some(rss) = current_rss_bytes() {
+                        peak.fetch_max(rss, Ordering::Relaxed);
+                    }
+                    thread::sleep(SAMPLE_INTERVAL);
+                }
+            })
+        };
+        Self { peak, stop, handle }
+    }
+    
+    /// Stop sampling and return the peak in bytes (`None` if unsupported)
+    pub fn finish(self) -> Option<u64> {
+        if let Some(rss) = current_rss_bytes() {
+            self.peak.fetch_max(rss, Ordering::Relaxed);
+        }
+        self.stop.store(true, Ordering::Relaxed);
+        let _ = self.handle.join();
+        Some(self.peak.load(Ordering::Relaxed)).filter(|&peak| peak > 0)
+    }
+}
+
+/// Warning text if `peak` reached 90% of `budget` (both in bytes)
+pub fn budget_warning(peak: u64, budget: u64) -> Option<String> {
+    if (peak as f64) < budget as f64 * BUDGET_WARNING_FRACTION {
+        return None;
+    }
+    let mb = |bytes: u64| bytes / (1024 * 1024);
+    Some(format!(
+        "proving peaked at {} MB of a {} MB memory budget; consider a smaller --segment-po2",
+        mb(peak),
+        mb(budget),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_sampler_sees_known_allocation() {
+        const BUFFER_BYTES: usize = 64 * 1024 * 1024;
+        
+        let sampler = PeakRssSampler::start();
+        // Non-zero fill, so every page is actually touched
+        let buffer = vec![1u8; BUFFER_BYTES];
+        thread::sleep(SAMPLE_INTERVAL * 3);
+        assert_eq!(std::hint::black_box(&buffer)[BUFFER_BYTES - 1], 1);
+        let peak = sampler.finish().unwrap();
+        
+        assert!(peak >= BUFFER_BYTES as u64, "peak {} below the {} byte buffer", peak, BUFFER_BYTES);
+    }
+    
+    #[test]
+    fn test_budget_warning_threshold() {
+        let mb = 1024 * 1024;
+        assert_eq!(budget_warning(800 * mb, 1000 * mb), None);
+        let warning = budget_warning(950 * mb, 1000 * mb).unwrap();
+        assert!(warning.contains("950 MB of a 1000 MB"), "{}", warning);
+    }
+}
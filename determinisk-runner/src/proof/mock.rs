@@ -1,16 +1,55 @@
 //! Mock proof backend for testing
 
-use super::{ProofBackend, ProofMetrics};
+use super::{ProofBackend, ProofMetrics, ProofProgress};
 use determinisk_core::SimulationInput;
 use std::thread;
 use std::time::Duration;
 
-pub struct MockBackend;
+/// How long a mock proof takes, however many segments it is split into
+pub const MOCK_PROVING_TIME: Duration = Duration::from_secs(5);
+
+/// Segments a real prover would split `input` into at `segment_po2`
+///
+/// Based on `SimulationInput::estimated_cycles`; always at least one.
+pub fn estimated_segments(input: &SimulationInput, segment_po2: u32) -> u32 {
+    let segments = input.estimated_cycles().div_ceil(1u64 << segment_po2).max(1);
+    u32::try_from(segments).unwrap_or(u32::MAX)
+}
+
+/// Sleep through a mock proof one segment at a time, reporting each
+///
+/// Takes `MOCK_PROVING_TIME` in total and returns the segment count.
+pub fn prove_segments(
+    input: &SimulationInput,
+    segment_po2: u32,
+    mut on_progress: impl FnMut(ProofProgress),
+) -> u32 {
+    let segments = estimated_segments(input, segment_po2);
+    for segments_done in 1..=segments {
+        thread::sleep(MOCK_PROVING_TIME / segments);
+        on_progress(ProofProgress { segments_done });
+    }
+    segments
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct MockBackend {
+    /// Artificial delay in `verify`, to exercise verification timing
+    pub verify_delay: Duration,
+}
 
 impl ProofBackend for MockBackend {
     fn prove(&self, input: &SimulationInput) -> Result<ProofMetrics, String> {
-        // Simulate proof generation delay
-        thread::sleep(Duration::from_secs(5));
+        self.prove_with_progress(input, &mut |_| {})
+    }
+    
+    fn prove_with_progress(
+        &self,
+        input: &SimulationInput,
+        on_progress: &mut dyn FnMut(ProofProgress),
+    ) -> Result<ProofMetrics, String> {
+        // Simulate proof generation delay, segment by segment
+        let segments = prove_segments(input, input.recommended_segment_po2(), on_progress);
         
         // Generate mock metrics based on input
         let total_cycles = (input.num_steps as u64) * (input.circles.len() as u64) * 1000;
@@ -18,15 +57,18 @@ impl ProofBackend for MockBackend {
         Ok(ProofMetrics {
             total_cycles,
             user_cycles: Some(total_cycles * 8 / 10),
-            segments: 1,
+            segments,
             proof_size_bytes: 4200,
-            proving_time_ms: 5000,
-            verification_time_ms: Some(10),
+            proving_time_ms: MOCK_PROVING_TIME.as_millis(),
+            // Proving doesn't verify; see `ProofBackend::verify_timed`
+            verification_time_ms: None,
             zkvm_backend: "Mock".to_string(),
+            peak_memory_bytes: None,
         })
     }
     
     fn verify(&self, _proof: &[u8]) -> Result<bool, String> {
+        thread::sleep(self.verify_delay);
         Ok(true)
     }
 }
\ No newline at end of file
@@ -3,8 +3,18 @@
 use determinisk_core::SimulationInput;
 use serde::{Deserialize, Serialize};
 
+pub mod memory;
 pub mod mock;
 
+#[cfg(feature = "risc0")]
+pub mod aggregation;
+
+#[cfg(feature = "risc0")]
+pub mod risc0;
+
+#[cfg(feature = "risc0")]
+pub use aggregation::{aggregate, AggregatedProof};
+
 // RISC Zero and SP1 backends are integrated directly in runner.rs
 // They could be refactored into separate modules later
 
@@ -18,6 +28,49 @@ pub struct ProofMetrics {
     pub proving_time_ms: u128,
     pub verification_time_ms: Option<u128>,
     pub zkvm_backend: String,
+    /// Peak resident memory while proving, where the platform reports it
+    #[serde(default)]
+    pub peak_memory_bytes: Option<u64>,
+}
+
+impl ProofMetrics {
+    /// Render as OpenMetrics text exposition, labelled with the backend
+    ///
+    /// Optional metrics are omitted when unknown.
+    pub fn to_openmetrics(&self) -> String {
+        let backend = self.zkvm_backend
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('\n', "\\n");
+        
+        let gauges: [(&str, &str, Option<u128>); 7] = [
+            ("determinisk_proof_total_cycles", "Total zkVM cycles", Some(self.total_cycles as u128)),
+            ("determinisk_proof_user_cycles", "Guest (user) cycles", self.user_cycles.map(u128::from)),
+            ("determinisk_proof_segments", "Number of proof segments", Some(self.segments as u128)),
+            ("determinisk_proof_size_bytes", "Serialized proof size in bytes", Some(self.proof_size_bytes as u128)),
+            ("determinisk_proving_time_ms", "Proof generation time in milliseconds", Some(self.proving_time_ms)),
+            ("determinisk_verification_time_ms", "Proof verification time in milliseconds", self.verification_time_ms),
+            ("determinisk_proof_peak_memory_bytes", "Peak resident memory while proving", self.peak_memory_bytes.map(u128::from)),
+        ];
+        
+        let mut out = String::new();
+        for (name, help, value) in gauges {
+            if let Some(value) = value {
+                out.push_str(&format!("# HELP {} {}\n", name, help));
+                out.push_str(&format!("# TYPE {} gauge\n", name));
+                out.push_str(&format!("{}{{backend=\"{}\"}} {}\n", name, backend, value));
+            }
+        }
+        out.push_str("# EOF\n");
+        out
+    }
+}
+
+/// How far a running proof has got
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProofProgress {
+    /// Segments completed so far; never decreases within one proof
+    pub segments_done: u32,
 }
 
 /// Trait for proof backends
@@ -25,6 +78,94 @@ pub trait ProofBackend {
     /// Generate a proof for the simulation
     fn prove(&self, input: &SimulationInput) -> Result<ProofMetrics, String>;
     
+    /// `prove`, calling `on_progress` as segments complete
+    ///
+    /// Backends that can't observe segments report once, when done.
+    fn prove_with_progress(
+        &self,
+        input: &SimulationInput,
+        on_progress: &mut dyn FnMut(ProofProgress),
+    ) -> Result<ProofMetrics, String> {
+        let metrics = self.prove(input)?;
+        on_progress(ProofProgress { segments_done: metrics.segments });
+        Ok(metrics)
+    }
+    
     /// Verify a proof
     fn verify(&self, proof: &[u8]) -> Result<bool, String>;
-}
\ No newline at end of file
+    
+    /// `verify`, also returning how long it took in milliseconds
+    fn verify_timed(&self, proof: &[u8]) -> (Result<bool, String>, u128) {
+        let start = std::time::Instant::now();
+        let result = self.verify(proof);
+        (result, start.elapsed().as_millis())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_openmetrics_output() {
+        let metrics = ProofMetrics {
+            total_cycles: 100_000,
+            user_cycles: Some(80_000),
+            segments: 2,
+            proof_size_bytes: 4200,
+            proving_time_ms: 5123,
+            verification_time_ms: None,
+            zkvm_backend: "RISC Zero".to_string(),
+            peak_memory_bytes: None,
+        };
+        
+        let text = metrics.to_openmetrics();
+        
+        assert!(text.contains("# TYPE determinisk_proof_total_cycles gauge\n"));
+        assert!(text.contains("determinisk_proof_total_cycles{backend=\"RISC Zero\"} 100000\n"));
+        assert!(text.contains("determinisk_proof_user_cycles{backend=\"RISC Zero\"} 80000\n"));
+        assert!(text.contains("determinisk_proof_segments{backend=\"RISC Zero\"} 2\n"));
+        assert!(text.contains("determinisk_proof_size_bytes{backend=\"RISC Zero\"} 4200\n"));
+        assert!(text.contains("determinisk_proving_time_ms{backend=\"RISC Zero\"} 5123\n"));
+        assert!(!text.contains("determinisk_verification_time_ms"));
+        assert!(text.ends_with("# EOF\n"));
+    }
+    
+    #[test]
+    fn test_openmetrics_escapes_backend_label() {
+        let metrics = ProofMetrics {
+            total_cycles: 1,
+            user_cycles: None,
+            segments: 1,
+            proof_size_bytes: 1,
+            proving_time_ms: 1,
+            verification_time_ms: None,
+            zkvm_backend: "Mock (error: \"oops\")".to_string(),
+            peak_memory_bytes: None,
+        };
+        
+        assert!(metrics.to_openmetrics().contains("{backend=\"Mock (error: \\\"oops\\\")\"}"));
+    }
+    
+    #[test]
+    fn test_mock_verification_time_reflects_delay() {
+        let backend = mock::MockBackend { verify_delay: std::time::Duration::from_millis(40) };
+        
+        let (verified, elapsed_ms) = backend.verify_timed(&[]);
+        
+        assert_eq!(verified, Ok(true));
+        assert!(elapsed_ms >= 40, "verification reported {} ms for a 40 ms delay", elapsed_ms);
+    }
+    
+    #[test]
+    fn test_mock_progress_counts_up_to_segments() {
+        let input = determinisk_core::scenarios::simple_drop();
+        let mut reported = Vec::new();
+        
+        let metrics = mock::MockBackend::default()
+            .prove_with_progress(&input, &mut |progress| reported.push(progress.segments_done))
+            .unwrap();
+        
+        assert_eq!(reported, (1..=metrics.segments).collect::<Vec<_>>());
+    }
+}
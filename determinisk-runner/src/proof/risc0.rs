@@ -0,0 +1,35 @@
+//! RISC Zero proving with live segment progress
+
+use super::ProofProgress;
+use determinisk_core::SimulationInput;
+use methods::PHYSICS_GUEST_ELF;
+use risc0_zkvm::{
+    get_prover_server, ExecutorEnv, ExecutorImpl, ProveInfo, ProverOpts, SimpleSegmentRef,
+    VerifierContext,
+};
+
+/// Prove `input` with the physics guest, reporting segments as they complete
+///
+/// Runs the executor with a session callback, so `on_progress` fires each
+/// time a `2^segment_po2`-cycle segment is sealed, then proves the finished
+/// session. The callback runs on the calling thread.
+pub fn prove_with_progress(
+    input: &SimulationInput,
+    segment_po2: u32,
+    mut on_progress: impl FnMut(ProofProgress),
+) -> Result<ProveInfo, Box<dyn std::error::Error>> {
+    let env = ExecutorEnv::builder()
+        .write(input)?
+        .segment_limit_po2(segment_po2)
+        .build()?;
+
+    let mut segments_done = 0;
+    let session = ExecutorImpl::from_elf(env, PHYSICS_GUEST_ELF)?.run_with_callback(|segment| {
+        segments_done += 1;
+        on_progress(ProofProgress { segments_done });
+        Ok(Box::new(SimpleSegmentRef::new(segment)))
+    })?;
+
+    let prover = get_prover_server(&ProverOpts::default())?;
+    Ok(prover.prove_session(&VerifierContext::default(), &session)?)
+}
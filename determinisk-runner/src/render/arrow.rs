@@ -0,0 +1,91 @@
+//! Velocity arrow geometry in fixed-point
+//!
+//! Arrowheads are traditionally built with `f32` `atan2`/`sin`/`cos`, whose
+//! last bits can vary between libm builds. Rotating the unit direction by a
+//! constant angle needs no trig at all, so this does it in Q16.16 and the
+//! same trace always yields the same vertices (and hence the same pixels).
+
+use determinisk_core::{Scalar, Vec2};
+
+/// Angle between the shaft and each arrowhead edge, as Q16.16 bits of
+/// cos(2.5 rad) ≈ -0.80114 and sin(2.5 rad) ≈ 0.59847
+const HEAD_COS_BITS: i32 = -0xCD18;
+const HEAD_SIN_BITS: i32 = 0x9935;
+
+/// Screen-space vertices of a velocity arrow
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ArrowGeometry {
+    pub tail: [f32; 2],
+    pub tip: [f32; 2],
+    /// The two back corners of the arrowhead triangle (the third is `tip`)
+    pub head: [[f32; 2]; 2],
+}
+
+/// Arrow from `tail` along `shaft` (both in screen pixels), head `head_size` long
+///
+/// Returns `None` for shafts too short to draw (under 0.1 px on both axes).
+pub fn velocity_arrow(tail: [f32; 2], shaft: [f32; 2], head_size: f32) -> Option<ArrowGeometry> {
+    if shaft[0].abs() <= 0.1 && shaft[1].abs() <= 0.1 {
+        return None;
+    }
+    
+    let tail_v = Vec2::new(tail[0], tail[1]);
+    let shaft_v = Vec2::new(shaft[0], shaft[1]);
+    let tip = tail_v + shaft_v;
+    
+    // Scale by the largest component before normalizing so long shafts
+    // can't overflow the squared length
+    let largest = shaft_v.x.abs().max(shaft_v.y.abs());
+    let dir = (shaft_v / largest).normalized();
+    
+    let size = Scalar::from_float(head_size);
+    let head_cos = Scalar::from_bits(HEAD_COS_BITS);
+    let head_sin = Scalar::from_bits(HEAD_SIN_BITS);
+    let corner = |sin: Scalar| {
+        // dir rotated by ±2.5 rad
+        let rotated = Vec2::from_scalars(
+            dir.x * head_cos - dir.y * sin,
+            dir.y * head_cos + dir.x * sin,
+        );
+        let point = tip - rotated * size;
+        [point.x.to_float(), point.y.to_float()]
+    };
+    
+    Some(ArrowGeometry {
+        tail: [tail_v.x.to_float(), tail_v.y.to_float()],
+        tip: [tip.x.to_float(), tip.y.to_float()],
+        head: [corner(head_sin), corner(-head_sin)],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_head_constants_match_trig() {
+        assert!((Scalar::from_bits(HEAD_COS_BITS).to_float() - 2.5f32.cos()).abs() < 1e-4);
+        assert!((Scalar::from_bits(HEAD_SIN_BITS).to_float() - 2.5f32.sin()).abs() < 1e-4);
+    }
+    
+    #[test]
+    fn test_arrow_is_reproducible_and_symmetric() {
+        let a = velocity_arrow([120.0, 80.0], [37.3, -14.9], 5.0).unwrap();
+        let b = velocity_arrow([120.0, 80.0], [37.3, -14.9], 5.0).unwrap();
+        let bits = |g: ArrowGeometry| [g.tip, g.head[0], g.head[1]].map(|p| p.map(f32::to_bits));
+        assert_eq!(bits(a), bits(b));
+        
+        // Pointing along +x, the head trails the tip and mirrors about the shaft
+        let arrow = velocity_arrow([0.0, 0.0], [10.0, 0.0], 5.0).unwrap();
+        assert_eq!(arrow.tip, [10.0, 0.0]);
+        let [upper, lower] = arrow.head;
+        assert_eq!(upper[0], lower[0]);
+        assert_eq!(upper[1], -lower[1]);
+        assert!((upper[0] - (10.0 - 5.0 * 2.5f32.cos())).abs() < 1e-3);
+        assert!((upper[1].abs() - 5.0 * 2.5f32.sin()).abs() < 1e-3);
+        
+        // Long shafts stay finite and too-short ones are skipped
+        assert!(velocity_arrow([0.0, 0.0], [3000.0, 2000.0], 5.0).is_some());
+        assert_eq!(velocity_arrow([0.0, 0.0], [0.05, -0.05], 5.0), None);
+    }
+}
@@ -0,0 +1,119 @@
+//! Headless PNG export of a recorded trace, one image per frame
+//!
+//! For turning runs into videos in CI: no window or GPU, and unlike the
+//! visualizer it can run off the main thread. Frames use the visualizer's
+//! `PIXELS_PER_METER` scale and the thumbnail colors, and draw velocity
+//! arrows with the fixed-point geometry, so the same trace always encodes
+//! to the same PNG bytes.
+
+use std::path::{Path, PathBuf};
+
+use determinisk_core::{DeterminiskError, SimulationTrace};
+use image::RgbaImage;
+
+use super::arrow::velocity_arrow;
+use super::thumbnail::{circle_color, draw_arrow, draw_rect_outline, fill_disk, ARROW, ARROW_HEAD_SIZE, BACKGROUND, BOUNDARY};
+use super::{velocity_to_screen, world_to_screen, PIXELS_PER_METER};
+
+/// Write every `every_n`th state of `trace` to `out_dir` as `frame_NNNNNN.png`
+///
+/// Frames are numbered by step and sized to the world at
+/// `PIXELS_PER_METER`; an `every_n` of 0 is treated as 1. `out_dir` is
+/// created if needed. Returns the written paths in order.
+pub fn export_frames(
+    trace: &SimulationTrace,
+    out_dir: impl AsRef<Path>,
+    every_n: usize,
+) -> Result<Vec<PathBuf>, DeterminiskError> {
+    let out_dir = out_dir.as_ref();
+    std::fs::create_dir_all(out_dir)?;
+    
+    let input = &trace.input;
+    let width = (input.world_width * PIXELS_PER_METER).ceil().max(1.0) as u32;
+    let height = (input.world_height * PIXELS_PER_METER).ceil().max(1.0) as u32;
+    
+    let mut paths = Vec::new();
+    for state in trace.states.iter().step_by(every_n.max(1)) {
+        let mut image = RgbaImage::from_pixel(width, height, BACKGROUND);
+        draw_rect_outline(&mut image, [0.0, 0.0], [(width - 1) as f32, (height - 1) as f32], BOUNDARY);
+        for (index, circle) in state.circles.iter().enumerate() {
+            let (x, y) = world_to_screen(input, circle.position);
+            fill_disk(&mut image, [x, y], circle.radius * PIXELS_PER_METER, circle_color(index));
+        }
+        // Arrows on top, as in the visualizer
+        for circle in &state.circles {
+            let (x, y) = world_to_screen(input, circle.position);
+            if let Some(arrow) = velocity_arrow([x, y], velocity_to_screen(input, circle.velocity), ARROW_HEAD_SIZE) {
+                draw_arrow(&mut image, &arrow, ARROW);
+            }
+        }
+        
+        let path = out_dir.join(format!("frame_{:06}.png", state.step));
+        image.save(&path).map_err(|err| match err {
+            image::ImageError::IoError(err) => DeterminiskError::Io(err),
+            err => DeterminiskError::Unsupported(format!("PNG export failed: {}", err)),
+        })?;
+        paths.push(path);
+    }
+    
+    Ok(paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use determinisk_core::{scenarios, World};
+    
+    #[test]
+    fn test_export_frames_writes_one_png_per_state() {
+        // A small world keeps the frames (and the test) small
+        let mut input = scenarios::simple_drop();
+        input.world_width = 8.0;
+        input.world_height = 6.0;
+        input.circles[0].position = [4.0, 4.0];
+        let trace = World::from_input(&input).run_with_recording(2);
+        assert_eq!(trace.states.len(), 3);
+        
+        let out_dir = std::env::temp_dir().join(format!("determinisk_frames_{}", std::process::id()));
+        let paths = export_frames(&trace, &out_dir, 1).unwrap();
+        
+        let expected = (
+            (input.world_width * PIXELS_PER_METER) as u32,
+            (input.world_height * PIXELS_PER_METER) as u32,
+        );
+        assert_eq!(paths.len(), 3);
+        for path in &paths {
+            assert!(std::fs::metadata(path).unwrap().len() > 0);
+            assert_eq!(image::image_dimensions(path).unwrap(), expected);
+        }
+        
+        // Every other frame: steps 0 and 2
+        let sparse = export_frames(&trace, &out_dir, 2).unwrap();
+        assert_eq!(sparse, vec![out_dir.join("frame_000000.png"), out_dir.join("frame_000002.png")]);
+        
+        std::fs::remove_dir_all(&out_dir).unwrap();
+    }
+    
+    #[test]
+    fn test_export_frames_is_byte_identical() {
+        let mut input = scenarios::simple_drop();
+        input.world_width = 8.0;
+        input.world_height = 6.0;
+        input.circles[0].position = [2.0, 4.0];
+        input.circles[0].velocity = [3.0, 1.5];
+        let trace = World::from_input(&input).run_with_recording(3);
+        
+        let base = std::env::temp_dir().join(format!("determinisk_frames_repro_{}", std::process::id()));
+        let first = export_frames(&trace, base.join("a"), 1).unwrap();
+        let second = export_frames(&trace, base.join("b"), 1).unwrap();
+        
+        for (a, b) in first.iter().zip(&second) {
+            assert_eq!(std::fs::read(a).unwrap(), std::fs::read(b).unwrap(), "{:?} differs", a.file_name());
+        }
+        // The moving ball gets an arrow
+        let frame = image::open(&first[0]).unwrap().to_rgba8();
+        assert!(frame.pixels().any(|pixel| *pixel == ARROW));
+        
+        std::fs::remove_dir_all(&base).unwrap();
+    }
+}
@@ -1,10 +1,53 @@
 //! Visualization module for physics simulations
 //! 
 //! This module provides optional visualization using Macroquad.
-//! It's only compiled when the "visual" feature is enabled.
+//! It's only compiled when the "visual" or "image" feature is enabled;
+//! "image" adds headless thumbnails and PNG frame export.
+
+use determinisk_core::SimulationInput;
+
+pub mod arrow;
+
+#[cfg(feature = "image")]
+pub mod frames;
 
 #[cfg(feature = "visual")]
 pub mod visualizer;
 
+#[cfg(feature = "image")]
+pub mod thumbnail;
+
+#[cfg(feature = "image")]
+pub use thumbnail::render_initial_state;
+
+#[cfg(feature = "image")]
+pub use frames::export_frames;
+
 #[cfg(feature = "visual")]
-pub use visualizer::{visualize_trace, visualize_trace_with_updates, ProofMetrics};
\ No newline at end of file
+pub use visualizer::{visualize_trace, visualize_trace_with_updates, ProofMetrics};
+
+/// Scale of the visualizer's world view and of exported frames
+pub const PIXELS_PER_METER: f32 = 50.0;
+
+/// Velocity arrow length in pixels per m/s, at `PIXELS_PER_METER`
+pub const VELOCITY_ARROW_SCALE: f32 = 20.0;
+
+/// Pixel position of world point `pos` at `PIXELS_PER_METER`, before any camera
+pub fn world_to_screen(input: &SimulationInput, pos: [f32; 2]) -> (f32, f32) {
+    let x = pos[0] * PIXELS_PER_METER;
+    // y-down traces are already in screen orientation
+    let y = if input.y_down {
+        pos[1] * PIXELS_PER_METER
+    } else {
+        (input.world_height - pos[1]) * PIXELS_PER_METER
+    };
+    (x, y)
+}
+
+/// Screen-space arrow shaft for `velocity` at `VELOCITY_ARROW_SCALE`
+pub fn velocity_to_screen(input: &SimulationInput, velocity: [f32; 2]) -> [f32; 2] {
+    let vx = velocity[0] * VELOCITY_ARROW_SCALE;
+    // Screen y grows downward unless the trace already does
+    let vy = if input.y_down { velocity[1] } else { -velocity[1] } * VELOCITY_ARROW_SCALE;
+    [vx, vy]
+}
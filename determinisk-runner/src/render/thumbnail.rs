@@ -0,0 +1,200 @@
+//! Headless thumbnails of a scenario's starting layout
+//!
+//! For scenario galleries: draws the world boundary, the initial circles and
+//! their velocity arrows into an `RgbaImage` without opening a window, so it
+//! works in CI and on servers. Colors follow the visualizer's trail palette.
+
+use determinisk_core::SimulationInput;
+use image::{Rgba, RgbaImage};
+
+use super::arrow::{velocity_arrow, ArrowGeometry};
+use super::{velocity_to_screen, PIXELS_PER_METER};
+
+pub(crate) const BACKGROUND: Rgba<u8> = Rgba([26, 26, 26, 255]);
+pub(crate) const BOUNDARY: Rgba<u8> = Rgba([230, 41, 55, 255]);
+/// Velocity arrows (the visualizer's green)
+pub(crate) const ARROW: Rgba<u8> = Rgba([0, 228, 48, 255]);
+
+/// Arrowhead length in pixels, at `PIXELS_PER_METER`
+pub(crate) const ARROW_HEAD_SIZE: f32 = 5.0;
+
+/// Circle colors, cycled by body index (the visualizer's trail palette)
+const PALETTE: [Rgba<u8>; 6] = [
+    Rgba([128, 179, 255, 255]),
+    Rgba([255, 153, 77, 255]),
+    Rgba([128, 255, 128, 255]),
+    Rgba([255, 128, 204, 255]),
+    Rgba([255, 230, 102, 255]),
+    Rgba([153, 230, 230, 255]),
+];
+
+/// Color `render_initial_state` uses for circle `index`
+pub fn circle_color(index: usize) -> Rgba<u8> {
+    PALETTE[index % PALETTE.len()]
+}
+
+/// Draw `input`'s initial circles and world boundary into a `size` image
+///
+/// The world is scaled uniformly to fit and centered. Every circle covers
+/// at least the pixel under its center, so tiny bodies stay visible.
+/// Velocity arrows use fixed-point geometry (see `arrow`), so the same
+/// input always renders the same pixels. Unlike in exported frames they sit
+/// beneath the bodies, so every body keeps its palette color at its center.
+pub fn render_initial_state(input: &SimulationInput, size: (u32, u32)) -> RgbaImage {
+    let (width, height) = size;
+    let mut image = RgbaImage::from_pixel(width, height, BACKGROUND);
+    if width < 2 || height < 2 || input.world_width <= 0.0 || input.world_height <= 0.0 {
+        return image;
+    }
+    
+    let scale = ((width - 1) as f32 / input.world_width).min((height - 1) as f32 / input.world_height);
+    let origin = [
+        (width as f32 - input.world_width * scale) / 2.0,
+        (height as f32 - input.world_height * scale) / 2.0,
+    ];
+    let to_pixel = |[x, y]: [f32; 2]| {
+        // y-down inputs are already in image orientation
+        let y = if input.y_down { y } else { input.world_height - y };
+        [origin[0] + x * scale, origin[1] + y * scale]
+    };
+    
+    let far = [origin[0] + input.world_width * scale, origin[1] + input.world_height * scale];
+    draw_rect_outline(&mut image, origin, far, BOUNDARY);
+    
+    let zoom = scale / PIXELS_PER_METER;
+    for circle in &input.circles {
+        let shaft = velocity_to_screen(input, circle.velocity).map(|v| v * zoom);
+        if let Some(arrow) = velocity_arrow(to_pixel(circle.position), shaft, ARROW_HEAD_SIZE * zoom) {
+            draw_arrow(&mut image, &arrow, ARROW);
+        }
+    }
+    for (index, circle) in input.circles.iter().enumerate() {
+        fill_disk(&mut image, to_pixel(circle.position), circle.radius * scale, circle_color(index));
+    }
+    
+    image
+}
+
+fn put_clamped(image: &mut RgbaImage, x: f32, y: f32, color: Rgba<u8>) {
+    let x = (x.max(0.0) as u32).min(image.width() - 1);
+    let y = (y.max(0.0) as u32).min(image.height() - 1);
+    image.put_pixel(x, y, color);
+}
+
+fn put_inside(image: &mut RgbaImage, x: f32, y: f32, color: Rgba<u8>) {
+    if (0.0..image.width() as f32).contains(&x) && (0.0..image.height() as f32).contains(&y) {
+        image.put_pixel(x as u32, y as u32, color);
+    }
+}
+
+/// Draw `arrow`'s shaft and filled head, skipping pixels outside the image
+///
+/// Only plain `f32` arithmetic on the fixed-point vertices, so it is as
+/// reproducible as the geometry itself.
+pub(crate) fn draw_arrow(image: &mut RgbaImage, arrow: &ArrowGeometry, color: Rgba<u8>) {
+    let [tail, tip] = [arrow.tail, arrow.tip];
+    let steps = (tip[0] - tail[0]).abs().max((tip[1] - tail[1]).abs()).ceil().max(1.0);
+    for i in 0..=steps as u32 {
+        let t = i as f32 / steps;
+        put_inside(image, tail[0] + (tip[0] - tail[0]) * t, tail[1] + (tip[1] - tail[1]) * t, color);
+    }
+    
+    // Head: pixel centers on the same side of all three edges
+    let [a, b, c] = [tip, arrow.head[0], arrow.head[1]];
+    let edge = |p: [f32; 2], q: [f32; 2], x: f32, y: f32| (q[0] - p[0]) * (y - p[1]) - (q[1] - p[1]) * (x - p[0]);
+    let min_x = a[0].min(b[0]).min(c[0]).max(0.0) as u32;
+    let min_y = a[1].min(b[1]).min(c[1]).max(0.0) as u32;
+    let max_x = a[0].max(b[0]).max(c[0]).min(image.width() as f32 - 1.0);
+    let max_y = a[1].max(b[1]).max(c[1]).min(image.height() as f32 - 1.0);
+    if max_x < 0.0 || max_y < 0.0 {
+        return;
+    }
+    for y in min_y..=max_y as u32 {
+        for x in min_x..=max_x as u32 {
+            let (px, py) = (x as f32 + 0.5, y as f32 + 0.5);
+            let sides = [edge(a, b, px, py), edge(b, c, px, py), edge(c, a, px, py)];
+            if sides.iter().all(|&s| s >= 0.0) || sides.iter().all(|&s| s <= 0.0) {
+                image.put_pixel(x, y, color);
+            }
+        }
+    }
+}
+
+pub(crate) fn draw_rect_outline(image: &mut RgbaImage, min: [f32; 2], max: [f32; 2], color: Rgba<u8>) {
+    let mut x = min[0];
+    while x <= max[0] {
+        put_clamped(image, x, min[1], color);
+        put_clamped(image, x, max[1], color);
+        x += 1.0;
+    }
+    let mut y = min[1];
+    while y <= max[1] {
+        put_clamped(image, min[0], y, color);
+        put_clamped(image, max[0], y, color);
+        y += 1.0;
+    }
+}
+
+pub(crate) fn fill_disk(image: &mut RgbaImage, center: [f32; 2], radius: f32, color: Rgba<u8>) {
+    let (width, height) = (image.width() as f32, image.height() as f32);
+    if !(0.0..width).contains(&center[0]) || !(0.0..height).contains(&center[1]) {
+        return;
+    }
+    
+    let x_range = (center[0] - radius).max(0.0) as u32..=((center[0] + radius).min(width - 1.0) as u32);
+    let y_range = (center[1] - radius).max(0.0) as u32..=((center[1] + radius).min(height - 1.0) as u32);
+    for y in y_range {
+        for x in x_range.clone() {
+            let dx = x as f32 + 0.5 - center[0];
+            let dy = y as f32 + 0.5 - center[1];
+            if dx * dx + dy * dy <= radius * radius {
+                image.put_pixel(x, y, color);
+            }
+        }
+    }
+    image.put_pixel(center[0] as u32, center[1] as u32, color);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use determinisk_core::scenarios;
+    
+    #[test]
+    fn test_thumbnail_draws_every_pool_ball() {
+        let input = scenarios::pool_break_15();
+        let image = render_initial_state(&input, (320, 180));
+        
+        assert_eq!(image.dimensions(), (320, 180));
+        
+        let scale = (319.0 / input.world_width).min(179.0 / input.world_height);
+        let origin = [(320.0 - input.world_width * scale) / 2.0, (180.0 - input.world_height * scale) / 2.0];
+        let drawn = input.circles.iter().enumerate().filter(|(index, circle)| {
+            let [x, y] = circle.position;
+            let y = if input.y_down { y } else { input.world_height - y };
+            let pixel = image.get_pixel((origin[0] + x * scale) as u32, (origin[1] + y * scale) as u32);
+            *pixel == circle_color(*index)
+        }).count();
+        assert_eq!(drawn, input.circles.len());
+        assert_eq!(drawn, 16);
+        
+        assert!(image.pixels().any(|pixel| *pixel == BOUNDARY));
+        // The cue ball is moving
+        assert!(image.pixels().any(|pixel| *pixel == ARROW));
+    }
+    
+    #[test]
+    fn test_thumbnail_is_reproducible() {
+        let input = scenarios::pool_break_15();
+        let first = render_initial_state(&input, (320, 180));
+        let second = render_initial_state(&input, (320, 180));
+        assert_eq!(first.as_raw(), second.as_raw());
+    }
+    
+    #[test]
+    fn test_thumbnail_of_degenerate_size_is_blank() {
+        let image = render_initial_state(&scenarios::pool_break_15(), (1, 1));
+        assert_eq!(image.dimensions(), (1, 1));
+        assert_eq!(*image.get_pixel(0, 0), BACKGROUND);
+    }
+}
@@ -1,22 +1,26 @@
 //! Macroquad-based visualizer for simulation traces
 
-use determinisk_core::{SimulationTrace, CircleState};
+use determinisk_core::{SimulationTrace, CircleState, Circle, Scalar, SpatialGrid};
 use macroquad::prelude::*;
-use serde::{Serialize, Deserialize};
 
-const PIXELS_PER_METER: f32 = 50.0;
+use super::PIXELS_PER_METER;
 
-/// Proof metrics from zkVM execution
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ProofMetrics {
-    pub total_cycles: u64,
-    pub user_cycles: Option<u64>,
-    pub segments: u32,
-    pub proof_size_bytes: usize,
-    pub proving_time_ms: u128,
-    pub verification_time_ms: Option<u128>,
-    pub zkvm_backend: String,
-}
+/// Trail length bounds and `+`/`-` step, in frames
+const MIN_TRAIL_LENGTH: usize = 5;
+const MAX_TRAIL_LENGTH: usize = 600;
+const TRAIL_LENGTH_STEP: usize = 5;
+
+/// Trail colors, cycled by body index
+const TRAIL_PALETTE: [Color; 6] = [
+    Color::new(0.5, 0.7, 1.0, 1.0),
+    Color::new(1.0, 0.6, 0.3, 1.0),
+    Color::new(0.5, 1.0, 0.5, 1.0),
+    Color::new(1.0, 0.5, 0.8, 1.0),
+    Color::new(1.0, 0.9, 0.4, 1.0),
+    Color::new(0.6, 0.9, 0.9, 1.0),
+];
+
+pub use crate::proof::ProofMetrics;
 
 pub struct Visualizer {
     trace: SimulationTrace,
@@ -27,8 +31,13 @@ pub struct Visualizer {
     show_velocities: bool,
     show_metrics: bool,
     show_grid: bool,
+    show_broadphase: bool,
     trail_length: usize,
+    trail_width: f32,
+    /// Draw trails as one joined polyline instead of separate segments
+    trail_polyline: bool,
     proof_metrics: Option<ProofMetrics>,
+    selected: Option<usize>,
 }
 
 impl Visualizer {
@@ -42,8 +51,12 @@ impl Visualizer {
             show_velocities: true,
             show_metrics: true,
             show_grid: true,
+            show_broadphase: false,
             trail_length: 30,
+            trail_width: 2.0,
+            trail_polyline: false,
             proof_metrics: None,
+            selected: None,
         }
     }
     
@@ -57,8 +70,12 @@ impl Visualizer {
             show_velocities: true,
             show_metrics: true,
             show_grid: true,
+            show_broadphase: false,
             trail_length: 30,
+            trail_width: 2.0,
+            trail_polyline: false,
             proof_metrics: Some(proof_metrics),
+            selected: None,
         }
     }
     
@@ -86,6 +103,18 @@ impl Visualizer {
             self.show_trails = !self.show_trails;
         }
         
+        if is_key_pressed(KeyCode::L) {
+            self.trail_polyline = !self.trail_polyline;
+        }
+        
+        if is_key_pressed(KeyCode::Equal) || is_key_pressed(KeyCode::KpAdd) {
+            self.set_trail_length(self.trail_length + TRAIL_LENGTH_STEP);
+        }
+        
+        if is_key_pressed(KeyCode::Minus) || is_key_pressed(KeyCode::KpSubtract) {
+            self.set_trail_length(self.trail_length.saturating_sub(TRAIL_LENGTH_STEP));
+        }
+        
         if is_key_pressed(KeyCode::V) {
             self.show_velocities = !self.show_velocities;
         }
@@ -97,12 +126,62 @@ impl Visualizer {
         if is_key_pressed(KeyCode::M) {
             self.show_metrics = !self.show_metrics;
         }
+        
+        if is_key_pressed(KeyCode::B) {
+            self.show_broadphase = !self.show_broadphase;
+        }
+        
+        // Click a body to inspect it; clicking empty space clears the selection
+        if is_mouse_button_pressed(MouseButton::Left) {
+            let (mx, my) = mouse_position();
+            let point = self.screen_to_world([mx, my], [screen_width(), screen_height()]);
+            self.selected = self.trace.states[self.current_frame].circle_at_point(point);
+        }
     }
     
     fn world_to_screen(&self, pos: [f32; 2]) -> (f32, f32) {
-        let x = pos[0] * PIXELS_PER_METER;
-        let y = (self.trace.input.world_height - pos[1]) * PIXELS_PER_METER;
-        (x, y)
+        super::world_to_screen(&self.trace.input, pos)
+    }
+    
+    /// Zoom that fits the world (in pixels) on screen with some padding
+    fn fit_zoom(&self, screen_size: [f32; 2]) -> f32 {
+        let world_width = self.trace.input.world_width * PIXELS_PER_METER;
+        let world_height = self.trace.input.world_height * PIXELS_PER_METER;
+        let zoom_x = screen_size[0] / world_width;
+        let zoom_y = screen_size[1] / world_height;
+        zoom_x.min(zoom_y) * 0.9 // 0.9 to add some padding
+    }
+    
+    /// Camera centered on the world, scaled to fit the screen
+    fn camera(&self) -> Camera2D {
+        let screen_size = [screen_width(), screen_height()];
+        let zoom = self.fit_zoom(screen_size);
+        let world_width = self.trace.input.world_width * PIXELS_PER_METER;
+        let world_height = self.trace.input.world_height * PIXELS_PER_METER;
+        Camera2D {
+            target: vec2(world_width / 2.0, world_height / 2.0),
+            zoom: vec2(zoom / screen_size[0] * 2.0, zoom / screen_size[1] * 2.0),
+            ..Default::default()
+        }
+    }
+    
+    /// Inverse of `camera` followed by `world_to_screen`, for mouse picking
+    ///
+    /// Takes the screen size explicitly so it can be used without a window.
+    fn screen_to_world(&self, screen: [f32; 2], screen_size: [f32; 2]) -> [f32; 2] {
+        let zoom = self.fit_zoom(screen_size);
+        let world_width = self.trace.input.world_width * PIXELS_PER_METER;
+        let world_height = self.trace.input.world_height * PIXELS_PER_METER;
+        // Camera space: the screen center shows the world center
+        let px = world_width / 2.0 + (screen[0] - screen_size[0] / 2.0) / zoom;
+        let py = world_height / 2.0 + (screen[1] - screen_size[1] / 2.0) / zoom;
+        let x = px / PIXELS_PER_METER;
+        let y = if self.trace.input.y_down {
+            py / PIXELS_PER_METER
+        } else {
+            self.trace.input.world_height - py / PIXELS_PER_METER
+        };
+        [x, y]
     }
     
     fn draw_grid(&self) {
@@ -123,6 +202,35 @@ impl Visualizer {
         }
     }
     
+    /// Shade spatial grid cells occupied in the current frame
+    fn draw_broadphase(&self) {
+        let state = &self.trace.states[self.current_frame];
+        let circles: Vec<Circle> = state.circles.iter().map(CircleState::to_circle).collect();
+        let grid = SpatialGrid::build(
+            &circles,
+            SpatialGrid::default_cell_size(&circles),
+            Scalar::from_float(self.trace.input.world_width),
+            Scalar::from_float(self.trace.input.world_height),
+        );
+        
+        let (cell_width, cell_height) = (grid.cell_width().to_float(), grid.cell_height().to_float());
+        for (cell, count) in grid.occupancy() {
+            // Cell's top-left corner on screen: the high-y edge when y is up
+            let top = if self.trace.input.y_down { cell.y } else { cell.y + 1 };
+            let (x, y) = self.world_to_screen([
+                cell.x as f32 * cell_width,
+                top as f32 * cell_height,
+            ]);
+            draw_rectangle(
+                x,
+                y,
+                cell_width * PIXELS_PER_METER,
+                cell_height * PIXELS_PER_METER,
+                occupancy_color(count),
+            );
+        }
+    }
+    
     fn draw_boundaries(&self) {
         let width = self.trace.input.world_width * PIXELS_PER_METER;
         let height = self.trace.input.world_height * PIXELS_PER_METER;
@@ -143,60 +251,71 @@ impl Visualizer {
         draw_circle_lines(x, y, radius, 2.0, WHITE);
     }
     
+    fn draw_selection(&self, circle: &CircleState) {
+        let (x, y) = self.world_to_screen(circle.position);
+        let radius = circle.radius * PIXELS_PER_METER;
+        
+        draw_circle_lines(x, y, radius + 4.0, 3.0, YELLOW);
+    }
+    
     fn draw_velocity(&self, circle: &CircleState) {
         let (x, y) = self.world_to_screen(circle.position);
-        let scale = 20.0;
-        let vx = circle.velocity[0] * scale;
-        let vy = -circle.velocity[1] * scale;
+        let shaft = super::velocity_to_screen(&self.trace.input, circle.velocity);
         
-        if vx.abs() > 0.1 || vy.abs() > 0.1 {
-            draw_line(x, y, x + vx, y + vy, 2.0, GREEN);
+        // Fixed-point geometry, so arrows are identical run to run
+        if let Some(arrow) = super::arrow::velocity_arrow([x, y], shaft, 5.0) {
+            let [tail, tip] = [arrow.tail, arrow.tip].map(|p| vec2(p[0], p[1]));
+            draw_line(tail.x, tail.y, tip.x, tip.y, 2.0, GREEN);
             
             // Arrowhead
-            let angle = vy.atan2(vx);
-            let arrow_size = 5.0;
-            draw_triangle(
-                vec2(x + vx, y + vy),
-                vec2(
-                    x + vx - arrow_size * (angle + 2.5).cos(),
-                    y + vy - arrow_size * (angle + 2.5).sin(),
-                ),
-                vec2(
-                    x + vx - arrow_size * (angle - 2.5).cos(),
-                    y + vy - arrow_size * (angle - 2.5).sin(),
-                ),
-                GREEN,
-            );
+            let [left, right] = arrow.head.map(|p| vec2(p[0], p[1]));
+            draw_triangle(tip, left, right, GREEN);
         }
     }
     
-    fn draw_trails(&self) {
+    /// Set how many past frames trails cover, clamped to a sane range
+    pub fn set_trail_length(&mut self, frames: usize) {
+        self.trail_length = frames.clamp(MIN_TRAIL_LENGTH, MAX_TRAIL_LENGTH);
+    }
+    
+    /// Set the trail line width in pixels
+    pub fn set_trail_width(&mut self, width: f32) {
+        self.trail_width = width.max(0.5);
+    }
+    
+    /// Screen positions of `circle_idx` over the trail window, oldest first
+    ///
+    /// Covers the last `trail_length` frames up to and including the current
+    /// one (fewer near the start of the trace).
+    fn trail_points(&self, circle_idx: usize) -> Vec<Vec2> {
         let start = self.current_frame.saturating_sub(self.trail_length);
-        let end = self.current_frame;
-        
-        for (circle_idx, _circle) in self.trace.states[self.current_frame].circles.iter().enumerate() {
-            let mut trail_points = Vec::new();
-            
-            for frame in start..=end {
-                if frame < self.trace.states.len() {
-                    let pos = self.trace.states[frame].circles[circle_idx].position;
-                    let (x, y) = self.world_to_screen(pos);
-                    trail_points.push(vec2(x, y));
-                }
-            }
+        let end = self.current_frame.min(self.trace.states.len() - 1);
+        
+        self.trace.states[start..=end].iter()
+            .filter_map(|state| state.circles.get(circle_idx))
+            .map(|circle| {
+                let (x, y) = self.world_to_screen(circle.position);
+                vec2(x, y)
+            })
+            .collect()
+    }
+    
+    fn draw_trails(&self) {
+        for circle_idx in 0..self.trace.states[self.current_frame].circles.len() {
+            let trail_points = self.trail_points(circle_idx);
+            let base = trail_color(circle_idx);
             
-            // Draw trail as fading line segments
+            // Fading from transparent (oldest) to half opacity (newest)
             for i in 1..trail_points.len() {
                 let alpha = (i as f32) / (trail_points.len() as f32);
-                let color = Color::new(0.5, 0.7, 1.0, alpha * 0.5);
-                draw_line(
-                    trail_points[i-1].x,
-                    trail_points[i-1].y,
-                    trail_points[i].x,
-                    trail_points[i].y,
-                    2.0,
-                    color,
-                );
+                let color = Color { a: alpha * 0.5, ..base };
+                let (from, to) = (trail_points[i - 1], trail_points[i]);
+                draw_line(from.x, from.y, to.x, to.y, self.trail_width, color);
+                
+                // Round joins close the gaps between segments at high speed
+                if self.trail_polyline {
+                    draw_circle(to.x, to.y, self.trail_width / 2.0, color);
+                }
             }
         }
     }
@@ -205,6 +324,12 @@ impl Visualizer {
         let state = &self.trace.states[self.current_frame];
         let metrics = &self.trace.output.metrics;
         
+        // Scenario title, centered at the top
+        if let Some(name) = &self.trace.input.name {
+            let size = measure_text(name, None, 28, 1.0);
+            draw_text(name, (screen_width() - size.width) / 2.0, 25.0, 28.0, WHITE);
+        }
+        
         // Input parameters
         draw_text("INPUT PARAMETERS", 10.0, 25.0, 24.0, YELLOW);
         draw_text(&format!("World: {}x{} m", 
@@ -280,13 +405,9 @@ impl Visualizer {
                 metrics.boundary_hits), 10.0, proof_y + 210.0, 20.0, WHITE);
             
             // Estimated proof size
-            let proof_overhead = 1024; // Base proof overhead
-            let state_hash_size = 32; // SHA-256 hash
-            let public_outputs = num_circles * 8 + 4; // Final positions + step count
-            let estimated_proof_size = proof_overhead + public_outputs + state_hash_size;
-            
+            let estimate = self.trace.input.estimate_cost();
             draw_text(&format!("Est. proof size: {:.1} KB", 
-                estimated_proof_size as f32 / 1024.0), 10.0, proof_y + 245.0, 20.0, YELLOW);
+                estimate.est_proof_size_kb), 10.0, proof_y + 245.0, 20.0, YELLOW);
         }
         
         // Metrics
@@ -300,13 +421,12 @@ impl Visualizer {
             
             // Computation complexity
             draw_text("COMPUTATION", x, 100.0, 18.0, YELLOW);
-            let num_circles = self.trace.input.circles.len();
-            let num_frames = self.trace.states.len();
-            let collision_checks = (num_circles * (num_circles - 1)) / 2;
+            let estimate = self.trace.input.estimate_cost();
+            let collision_checks = estimate.est_collision_checks / u64::from(estimate.steps.max(1));
             draw_text(&format!("Collision pairs/frame: {}", 
                 collision_checks), x, 120.0, 16.0, WHITE);
             draw_text(&format!("Total checks: {}", 
-                collision_checks * num_frames), x, 140.0, 16.0, WHITE);
+                estimate.est_collision_checks), x, 140.0, 16.0, WHITE);
             
             // Current frame stats
             draw_text("CURRENT FRAME", x, 180.0, 18.0, YELLOW);
@@ -345,8 +465,26 @@ impl Visualizer {
                 (circle_size * state.circles.len()) as f32 / 1024.0), x, 360.0, 16.0, WHITE);
         }
         
+        // Selected body, as the Q16.16 bits the engine works with
+        if let Some(circle) = self.selected.and_then(|i| state.circles.get(i)) {
+            let x = screen_width() - 250.0;
+            let y = screen_height() - 170.0;
+            let bits = |v: f32| Scalar::from_float(v).to_bits();
+            draw_text(&format!("SELECTED BODY #{}", self.selected.unwrap_or_default()), x, y, 18.0, YELLOW);
+            draw_text(&format!("pos: ({:.3}, {:.3})", 
+                circle.position[0], circle.position[1]), x, y + 20.0, 16.0, WHITE);
+            draw_text(&format!("pos bits: ({:#010x}, {:#010x})", 
+                bits(circle.position[0]), bits(circle.position[1])), x, y + 40.0, 16.0, WHITE);
+            draw_text(&format!("vel: ({:.3}, {:.3})", 
+                circle.velocity[0], circle.velocity[1]), x, y + 60.0, 16.0, WHITE);
+            draw_text(&format!("vel bits: ({:#010x}, {:#010x})", 
+                bits(circle.velocity[0]), bits(circle.velocity[1])), x, y + 80.0, 16.0, WHITE);
+            draw_text(&format!("radius bits: {:#010x}", bits(circle.radius)), x, y + 100.0, 16.0, WHITE);
+            draw_text(&format!("mass bits: {:#010x}", bits(circle.mass)), x, y + 120.0, 16.0, WHITE);
+        }
+        
         // Controls
-        let y = screen_height() - 220.0;
+        let y = screen_height() - 295.0;
         draw_text("CONTROLS", 10.0, y, 24.0, YELLOW);
         draw_text("Space: Play/Pause", 10.0, y + 30.0, 20.0, WHITE);
         draw_text("←/→: Previous/Next frame", 10.0, y + 55.0, 20.0, WHITE);
@@ -359,6 +497,11 @@ impl Visualizer {
         draw_text("G: Toggle grid", 10.0, y + 155.0, 20.0, grid_color);
         let metrics_color = if self.show_metrics { GREEN } else { Color::new(0.5, 0.5, 0.5, 1.0) };
         draw_text("M: Toggle metrics", 10.0, y + 180.0, 20.0, metrics_color);
+        let broadphase_color = if self.show_broadphase { GREEN } else { Color::new(0.5, 0.5, 0.5, 1.0) };
+        draw_text("B: Toggle broad-phase cells", 10.0, y + 205.0, 20.0, broadphase_color);
+        let polyline_color = if self.trail_polyline { GREEN } else { Color::new(0.5, 0.5, 0.5, 1.0) };
+        draw_text("L: Toggle polyline trails", 10.0, y + 230.0, 20.0, polyline_color);
+        draw_text(&format!("+/-: Trail length ({} frames)", self.trail_length), 10.0, y + 255.0, 20.0, WHITE);
         
         // Playback status
         let status = if self.playing { "▶ PLAYING" } else { "⏸ PAUSED" };
@@ -367,10 +510,6 @@ impl Visualizer {
     }
     
     pub async fn run(mut self) {
-        // Set up camera to view the entire world
-        let world_width = self.trace.input.world_width * PIXELS_PER_METER;
-        let world_height = self.trace.input.world_height * PIXELS_PER_METER;
-        
         loop {
             // Handle input
             self.handle_input();
@@ -386,21 +525,15 @@ impl Visualizer {
             clear_background(Color::new(0.1, 0.1, 0.15, 1.0));
             
             // Set camera to view the world properly
-            // Calculate zoom to fit the world in the screen
-            let zoom_x = screen_width() / world_width;
-            let zoom_y = screen_height() / world_height;
-            let zoom = zoom_x.min(zoom_y) * 0.9; // 0.9 to add some padding
-            
-            set_camera(&Camera2D {
-                target: vec2(world_width / 2.0, world_height / 2.0),
-                zoom: vec2(zoom / screen_width() * 2.0, zoom / screen_height() * 2.0),
-                ..Default::default()
-            });
+            set_camera(&self.camera());
             
             // Draw world
             if self.show_grid {
                 self.draw_grid();
             }
+            if self.show_broadphase {
+                self.draw_broadphase();
+            }
             self.draw_boundaries();
             
             // Draw trails
@@ -417,6 +550,9 @@ impl Visualizer {
                     self.draw_velocity(circle);
                 }
             }
+            if let Some(circle) = self.selected.and_then(|i| state.circles.get(i)) {
+                self.draw_selection(circle);
+            }
             
             // Reset camera for UI
             set_default_camera();
@@ -429,6 +565,22 @@ impl Visualizer {
     }
 }
 
+/// Shading for a broad-phase cell: more occupants means a hotter, more opaque cell
+fn occupancy_color(count: usize) -> Color {
+    match count {
+        0 => Color::new(0.0, 0.0, 0.0, 0.0),
+        1 => Color::new(0.2, 0.6, 0.2, 0.25),
+        2 => Color::new(0.8, 0.8, 0.2, 0.35),
+        3 => Color::new(0.9, 0.5, 0.1, 0.45),
+        _ => Color::new(0.9, 0.1, 0.1, 0.55),
+    }
+}
+
+/// Trail color for body `index`, cycling through `TRAIL_PALETTE`
+fn trail_color(index: usize) -> Color {
+    TRAIL_PALETTE[index % TRAIL_PALETTE.len()]
+}
+
 /// Visualize a simulation trace
 pub async fn visualize_trace(trace: SimulationTrace) {
     let visualizer = Visualizer::new(trace);
@@ -442,10 +594,6 @@ pub async fn visualize_trace_with_updates(
 ) {
     let mut visualizer = Visualizer::new(trace);
     
-    // Set up camera to view the entire world
-    let world_width = visualizer.trace.input.world_width * PIXELS_PER_METER;
-    let world_height = visualizer.trace.input.world_height * PIXELS_PER_METER;
-    
     loop {
         // Update proof metrics if available
         if let Ok(metrics) = proof_metrics.lock() {
@@ -463,21 +611,16 @@ pub async fn visualize_trace_with_updates(
         // Draw everything
         clear_background(Color::new(0.1, 0.1, 0.15, 1.0));
         
-        // Set camera to view the world properly
-        // Calculate zoom to fit the world in the screen
-        let zoom_x = screen_width() / world_width;
-        let zoom_y = screen_height() / world_height;
-        let zoom = zoom_x.min(zoom_y) * 0.9; // 0.9 to add some padding
-        
-        set_camera(&Camera2D {
-            target: vec2(world_width / 2.0, world_height / 2.0),
-            zoom: vec2(zoom / screen_width() * 2.0, -zoom / screen_height() * 2.0), // Negative Y for correct orientation
-            ..Default::default()
-        });
+        // Set camera to view the world properly; shared with `run` so mouse
+        // picking agrees with what is drawn
+        set_camera(&visualizer.camera());
         
         if visualizer.show_grid {
             visualizer.draw_grid();
         }
+        if visualizer.show_broadphase {
+            visualizer.draw_broadphase();
+        }
         visualizer.draw_boundaries();
         
         if visualizer.show_trails {
@@ -493,6 +636,9 @@ pub async fn visualize_trace_with_updates(
                 visualizer.draw_velocity(circle);
             }
         }
+        if let Some(circle) = visualizer.selected.and_then(|i| state.circles.get(i)) {
+            visualizer.draw_selection(circle);
+        }
         
         // Reset camera for UI
         set_default_camera();
@@ -507,4 +653,97 @@ pub async fn visualize_trace_with_updates(
         
         next_frame().await;
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_occupancy_color_mapping() {
+        // Empty cells are invisible
+        assert_eq!(occupancy_color(0).a, 0.0);
+        
+        // Crowding increases opacity and shifts from green to red
+        let alphas: Vec<f32> = (1..=4).map(|n| occupancy_color(n).a).collect();
+        assert!(alphas.windows(2).all(|w| w[0] < w[1]));
+        assert!(occupancy_color(1).g > occupancy_color(1).r);
+        assert!(occupancy_color(4).r > occupancy_color(4).g);
+        
+        // Anything beyond four occupants saturates
+        assert_eq!(occupancy_color(4), occupancy_color(10));
+    }
+    
+    #[test]
+    fn test_screen_to_world_picking() {
+        use determinisk_core::World;
+        
+        // simple_drop: 100x100 m world, y-up, one ball
+        let input = determinisk_core::scenarios::simple_drop();
+        let trace = World::from_input(&input).run_with_recording(0);
+        let mut visualizer = Visualizer::new(trace);
+        
+        // 100 m = 5000 px; fits 800x600 at 600/5000 * 0.9 = 0.108 screen px per px
+        let screen = [800.0, 600.0];
+        let px_per_meter = 0.108 * PIXELS_PER_METER;
+        let close = |a: [f32; 2], b: [f32; 2]| (a[0] - b[0]).abs() < 1e-3 && (a[1] - b[1]).abs() < 1e-3;
+        
+        // Screen center is world center; screen up is world up
+        assert!(close(visualizer.screen_to_world([400.0, 300.0], screen), [50.0, 50.0]));
+        assert!(close(visualizer.screen_to_world([400.0 + px_per_meter, 300.0 - px_per_meter], screen), [51.0, 51.0]));
+        
+        // y-down traces keep screen orientation
+        visualizer.trace.input.y_down = true;
+        assert!(close(visualizer.screen_to_world([400.0, 300.0 - px_per_meter], screen), [50.0, 49.0]));
+        visualizer.trace.input.y_down = false;
+        
+        // Clicking the ball's center picks it; empty space picks nothing
+        let ball = visualizer.trace.states[0].circles[0].position;
+        let click = [
+            400.0 + (ball[0] - 50.0) * px_per_meter,
+            300.0 - (ball[1] - 50.0) * px_per_meter,
+        ];
+        let state = &visualizer.trace.states[0];
+        assert_eq!(state.circle_at_point(visualizer.screen_to_world(click, screen)), Some(0));
+        assert_eq!(state.circle_at_point(visualizer.screen_to_world([1.0, 1.0], screen)), None);
+    }
+    
+    #[test]
+    fn test_trail_points_follow_trajectory() {
+        use determinisk_core::World;
+        
+        let input = determinisk_core::scenarios::simple_drop();
+        let trace = World::from_input(&input).run_with_recording(60);
+        let mut visualizer = Visualizer::new(trace);
+        let expected = |v: &Visualizer, frames: std::ops::RangeInclusive<usize>| -> Vec<Vec2> {
+            frames.map(|f| {
+                let (x, y) = v.world_to_screen(v.trace.states[f].circles[0].position);
+                vec2(x, y)
+            }).collect()
+        };
+        
+        // Mid-trace: the default 30 frames back plus the current one, oldest first
+        visualizer.current_frame = 40;
+        assert_eq!(visualizer.trail_points(0), expected(&visualizer, 10..=40));
+        // The ball is falling, so screen y grows along the trail
+        let points = visualizer.trail_points(0);
+        assert!(points.windows(2).all(|w| w[1].y >= w[0].y));
+        
+        // Near the start the trail is shorter
+        visualizer.current_frame = 3;
+        assert_eq!(visualizer.trail_points(0), expected(&visualizer, 0..=3));
+        
+        // Length changes are clamped
+        visualizer.set_trail_length(1000);
+        assert_eq!(visualizer.trail_length, MAX_TRAIL_LENGTH);
+        visualizer.set_trail_length(0);
+        assert_eq!(visualizer.trail_length, MIN_TRAIL_LENGTH);
+        visualizer.current_frame = 60;
+        assert_eq!(visualizer.trail_points(0), expected(&visualizer, 55..=60));
+        
+        // Unknown bodies have no trail; bodies get distinct colors
+        assert!(visualizer.trail_points(7).is_empty());
+        assert_ne!(trail_color(0), trail_color(1));
+        assert_eq!(trail_color(0), trail_color(TRAIL_PALETTE.len()));
+    }
+}
@@ -1,12 +1,28 @@
 //! Simulation runner with parallel proof generation and visualization support
 
-use determinisk_core::{SimulationInput, SimulationTrace, World};
+use determinisk_core::determinism::check_journal_checkpoints;
+use determinisk_core::{DeterminiskError, JournalOutput, SimulationInput, SimulationTrace, TooManyCircles, World, MAX_CIRCLES, RECOMMENDED_SEGMENT_PO2};
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::Instant;
 
-#[cfg(feature = "visual")]
-use crate::render::ProofMetrics;
+use crate::proof::memory::{budget_warning, PeakRssSampler};
+use crate::proof::mock::{prove_segments, MockBackend};
+use crate::proof::{ProofBackend, ProofMetrics, ProofProgress};
+
+/// How often `run_with_status` checks the proof thread for status changes
+const STATUS_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(100);
+
+/// Steps between `World::is_sane` checks before proving
+const SANITY_CHECK_INTERVAL: u32 = 50;
+
+/// Why `ZkVmBackend::Sp1` can't prove, and what to use instead
+///
+/// Proving needs the SP1 SDK and the guest ELF built by the SP1 toolchain,
+/// which only the standalone host in `determinisk-sp1/script` links.
+#[cfg(feature = "sp1")]
+pub const SP1_UNAVAILABLE: &str = "SP1 proving is not wired into the runner yet; \
+    prove with the SP1 host instead: cd determinisk-sp1/script && cargo run --release -- --prove";
 
 /// Configuration for simulation runner
 #[derive(Debug, Clone)]
@@ -21,6 +37,80 @@ pub struct RunnerConfig {
     pub verbose: bool,
     /// Segment size for RISC Zero proving (power of 2)
     pub segment_po2: u32,
+    /// Warn when peak proving memory comes within 10% of this many bytes
+    pub memory_budget_bytes: Option<u64>,
+}
+
+impl RunnerConfig {
+    /// Start from the defaults: headless, no proof, mock backend, quiet,
+    /// `segment_po2` 20 and no memory budget
+    pub fn builder() -> RunnerConfigBuilder {
+        RunnerConfigBuilder {
+            config: RunnerConfig {
+                visualize: false,
+                prove: false,
+                backend: ZkVmBackend::Mock,
+                verbose: false,
+                segment_po2: *RECOMMENDED_SEGMENT_PO2.end(),
+                memory_budget_bytes: None,
+            },
+        }
+    }
+}
+
+impl Default for RunnerConfig {
+    fn default() -> Self {
+        RunnerConfig::builder().build()
+    }
+}
+
+/// Chainable construction of a `RunnerConfig`, see `RunnerConfig::builder`
+#[derive(Debug, Clone)]
+pub struct RunnerConfigBuilder {
+    config: RunnerConfig,
+}
+
+impl RunnerConfigBuilder {
+    /// Open the visualizer (requires the visual binary)
+    pub fn visualize(mut self, visualize: bool) -> Self {
+        self.config.visualize = visualize;
+        self
+    }
+    
+    /// Generate a zkVM proof
+    pub fn prove(mut self, prove: bool) -> Self {
+        self.config.prove = prove;
+        self
+    }
+    
+    /// zkVM backend to prove with
+    pub fn backend(mut self, backend: ZkVmBackend) -> Self {
+        self.config.backend = backend;
+        self
+    }
+    
+    /// Print progress
+    pub fn verbose(mut self, verbose: bool) -> Self {
+        self.config.verbose = verbose;
+        self
+    }
+    
+    /// Segment size for RISC Zero proving (power of 2)
+    pub fn segment_po2(mut self, segment_po2: u32) -> Self {
+        self.config.segment_po2 = segment_po2;
+        self
+    }
+    
+    /// Warn when peak proving memory nears this many bytes
+    pub fn memory_budget_bytes(mut self, budget: Option<u64>) -> Self {
+        self.config.memory_budget_bytes = budget;
+        self
+    }
+    
+    /// Finish building
+    pub fn build(self) -> RunnerConfig {
+        self.config
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -41,6 +131,58 @@ pub struct RunnerResult {
     pub proof_metrics: Option<ProofMetrics>,
     /// Total execution time
     pub execution_time_ms: u128,
+    /// Whether the proof's committed state hash matched the native run
+    /// (`None` if no proof was generated or the backend exposes no journal)
+    pub determinism_verified: Option<bool>,
+    /// Journal committed by the guest, if the backend produced one
+    pub journal: Option<JournalOutput>,
+    /// Bincode-serialized receipt, if the backend produced a real one
+    /// (see `SimulationRunner::save_proof`)
+    pub receipt_bytes: Option<Vec<u8>>,
+}
+
+/// Median proof metrics over several runs of the same input
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchSummary {
+    /// Backend that produced the proofs
+    pub zkvm_backend: String,
+    /// Number of proofs generated
+    pub runs: usize,
+    /// Runs left after discarding the fastest and slowest
+    pub kept: usize,
+    pub median_total_cycles: u64,
+    pub median_proving_time_ms: u128,
+    pub median_proof_size_bytes: usize,
+}
+
+/// Output of the background proof thread
+struct ProofOutput {
+    metrics: ProofMetrics,
+    /// Journal committed by the guest, if the backend produced one
+    journal: Option<JournalOutput>,
+    /// Serialized receipt, for backends with real proofs
+    receipt_bytes: Option<Vec<u8>>,
+    /// `false` if a real receipt failed to verify; its journal and bytes
+    /// are then dropped
+    receipt_verified: bool,
+}
+
+/// Whether the proof attests to the native run: `None` without a journal
+/// to check, `Some(false)` if the receipt didn't verify or its committed
+/// state (or any checkpoint) differs
+fn check_determinism(
+    output: Option<&ProofOutput>,
+    input: &SimulationInput,
+    native_state_hash: [u8; 32],
+) -> Option<bool> {
+    let output = output?;
+    if !output.receipt_verified {
+        return Some(false);
+    }
+    output.journal.as_ref().map(|journal| {
+        journal.state_hash == native_state_hash
+            && check_journal_checkpoints(input, journal).is_ok()
+    })
 }
 
 /// Unified simulation runner
@@ -55,16 +197,84 @@ impl SimulationRunner {
     }
     
     /// Run a simulation from input
-    pub fn run(&self, input: SimulationInput) -> Result<RunnerResult, Box<dyn std::error::Error>> {
+    pub fn run(&self, input: SimulationInput) -> Result<RunnerResult, DeterminiskError> {
+        self.run_with_status(input, |_| {})
+    }
+    
+    /// Run a simulation, reporting live proof status while waiting
+    ///
+    /// `on_status` is called from the calling thread each time the proof
+    /// thread publishes a new `ProofMetrics` (e.g. "Generating..." with a
+    /// growing `segments` count, and then the final metrics). It is never
+    /// called when `prove` is off.
+    pub fn run_with_status<F: FnMut(&ProofMetrics)>(
+        &self,
+        input: SimulationInput,
+        on_status: F,
+    ) -> Result<RunnerResult, DeterminiskError> {
+        self.run_in(&mut World::new(0.0, 0.0), input, on_status)
+    }
+    
+    /// Run and prove a simulation, reporting segments as they complete
+    ///
+    /// Proves even if the config has `prove` off. `on_progress` is called
+    /// from the calling thread with a strictly increasing `segments_done`;
+    /// the last call carries the final segment count.
+    pub fn prove_with_progress<F: FnMut(ProofProgress)>(
+        &self,
+        input: SimulationInput,
+        mut on_progress: F,
+    ) -> Result<RunnerResult, DeterminiskError> {
+        let runner = SimulationRunner::new(RunnerConfig { prove: true, ..self.config.clone() });
+        let mut last_done = 0;
+        runner.run_with_status(input, |status| {
+            if status.segments > last_done {
+                last_done = status.segments;
+                on_progress(ProofProgress { segments_done: last_done });
+            }
+        })
+    }
+    
+    /// `run_with_status` on an existing world, rebuilt in place from `input`
+    fn run_in<F: FnMut(&ProofMetrics)>(
+        &self,
+        world: &mut World,
+        input: SimulationInput,
+        mut on_status: F,
+    ) -> Result<RunnerResult, DeterminiskError> {
         let start = Instant::now();
         
         // Create world and run simulation
         if self.config.verbose {
             println!("Creating world from input...");
         }
+        if input.circles.len() > MAX_CIRCLES {
+            return Err(TooManyCircles { count: input.circles.len() }.into());
+        }
+        world.reset_with(&input);
+        if world.tunneling_risk() {
+            eprintln!("Warning: timestep {} lets bodies move further than their radius per step; collisions may tunnel",
+                input.timestep);
+        }
         
-        let mut world = World::from_input(&input);
-        let trace = world.run_with_recording(input.num_steps);
+        // Fail before simulating rather than from inside the proof thread
+        #[cfg(feature = "sp1")]
+        if self.config.prove && matches!(self.config.backend, ZkVmBackend::Sp1) {
+            return Err(DeterminiskError::Unsupported(SP1_UNAVAILABLE.to_string()));
+        }
+        
+        // Don't spend a proof on a simulation that has already blown up
+        if self.config.prove {
+            check_sanity(world.clone(), input.num_steps).map_err(DeterminiskError::validation)?;
+        }
+        
+        // Only the visualizer needs every frame; headless runs keep the final state
+        let trace = if self.config.visualize {
+            world.run_with_recording(input.num_steps)
+        } else {
+            world.simulate(input.num_steps)
+        };
+        let native_state_hash = world.state_hash();
         
         // Setup proof metrics channel for live updates
         let proof_metrics = Arc::new(Mutex::new(None));
@@ -76,9 +286,10 @@ impl SimulationRunner {
             let input_clone = input.clone();
             let verbose = self.config.verbose;
             let segment_po2 = self.config.segment_po2;
+            let memory_budget = self.config.memory_budget_bytes;
             
             Some(thread::spawn(move || {
-                generate_proof(backend, input_clone, proof_metrics_clone, verbose, segment_po2)
+                generate_proof(backend, input_clone, proof_metrics_clone, verbose, segment_po2, memory_budget)
             }))
         } else {
             None
@@ -92,72 +303,228 @@ impl SimulationRunner {
             );
             println!("\nNote: The standard runner cannot display visualizations due to");
             println!("macroquad requiring control of the main thread.");
-            return Err("Use the visual binary for visualization".into());
+            return Err(DeterminiskError::Unsupported("Use the visual binary for visualization".to_string()));
         }
         
-        // Wait for proof generation to complete
-        let final_proof_metrics = if let Some(handle) = proof_handle {
-            handle.join().map_err(|_| "Proof generation thread panicked")?
+        // Wait for proof generation to complete, relaying status changes
+        let proof_output = if let Some(handle) = proof_handle {
+            let mut last_status: Option<(String, u32)> = None;
+            let mut report = |last_status: &mut Option<(String, u32)>| {
+                if let Some(current) = proof_metrics.lock().unwrap().as_ref() {
+                    let status = (current.zkvm_backend.clone(), current.segments);
+                    if last_status.as_ref() != Some(&status) {
+                        *last_status = Some(status);
+                        on_status(current);
+                    }
+                }
+            };
+            while !handle.is_finished() {
+                report(&mut last_status);
+                thread::sleep(STATUS_POLL_INTERVAL);
+            }
+            report(&mut last_status);
+            handle.join().map_err(|_| DeterminiskError::Proof("proof generation thread panicked".to_string()))?
         } else {
             None
         };
         
+        // Cross-check the guest's committed state (and any intermediate
+        // checkpoints) against the native run
+        let determinism_verified = check_determinism(proof_output.as_ref(), &input, native_state_hash);
+        
+        if determinism_verified == Some(false) {
+            eprintln!("✗ Determinism check failed: proof did not verify or its state hash differs from native run");
+        } else if determinism_verified == Some(true) && self.config.verbose {
+            println!("✓ Proof state hash matches native run");
+        }
+        
+        let (proof_metrics, journal, receipt_bytes) = match proof_output {
+            Some(output) => (Some(output.metrics), output.journal, output.receipt_bytes),
+            None => (None, None, None),
+        };
+        
         let execution_time_ms = start.elapsed().as_millis();
         
         Ok(RunnerResult {
             trace,
-            proof_metrics: final_proof_metrics,
+            proof_metrics,
             execution_time_ms,
+            determinism_verified,
+            journal,
+            receipt_bytes,
+        })
+    }
+    
+    /// Write `result`'s receipt to `path`, to verify later without re-proving
+    ///
+    /// Fails if the run produced no receipt (no proof, or the mock backend).
+    pub fn save_proof(&self, result: &RunnerResult, path: impl AsRef<std::path::Path>) -> Result<(), DeterminiskError> {
+        let bytes = result.receipt_bytes.as_ref().ok_or_else(|| {
+            DeterminiskError::Proof("no receipt to save; prove with a real backend first".to_string())
+        })?;
+        std::fs::write(path, bytes)?;
+        Ok(())
+    }
+    
+    /// Verify a receipt written by `save_proof`, returning its journal
+    #[cfg(feature = "risc0")]
+    pub fn verify_proof_file(path: impl AsRef<std::path::Path>) -> Result<JournalOutput, DeterminiskError> {
+        use methods::PHYSICS_GUEST_ID;
+        use risc0_zkvm::Receipt;
+        
+        let bytes = std::fs::read(path)?;
+        let receipt: Receipt = bincode::deserialize(&bytes)
+            .map_err(|e| DeterminiskError::Proof(format!("malformed receipt: {}", e)))?;
+        receipt.verify(PHYSICS_GUEST_ID)
+            .map_err(|e| DeterminiskError::Proof(format!("receipt did not verify: {}", e)))?;
+        receipt.journal.decode()
+            .map_err(|e| DeterminiskError::Proof(format!("malformed journal: {}", e)))
+    }
+    
+    /// Prove the same input `runs` times and report median metrics
+    ///
+    /// With three or more runs the fastest and slowest (by proving time) are
+    /// discarded before taking medians, since single proofs are noisy.
+    pub fn bench(&self, input: &SimulationInput, runs: usize) -> Result<BenchSummary, DeterminiskError> {
+        if runs == 0 {
+            return Err(DeterminiskError::validation("bench needs at least one run"));
+        }
+        
+        let runner = SimulationRunner::new(RunnerConfig {
+            visualize: false,
+            prove: true,
+            ..self.config.clone()
+        });
+        
+        let mut samples = Vec::with_capacity(runs);
+        for run in 0..runs {
+            if self.config.verbose {
+                println!("Bench run {}/{}", run + 1, runs);
+            }
+            let metrics = runner.run(input.clone())?
+                .proof_metrics
+                .ok_or_else(|| DeterminiskError::Proof("backend produced no proof metrics".to_string()))?;
+            samples.push(metrics);
+        }
+        
+        // Drop the outliers by proving time
+        samples.sort_by_key(|m| m.proving_time_ms);
+        let kept: &[ProofMetrics] = if samples.len() >= 3 {
+            &samples[1..samples.len() - 1]
+        } else {
+            &samples
+        };
+        
+        Ok(BenchSummary {
+            zkvm_backend: kept[0].zkvm_backend.clone(),
+            runs,
+            kept: kept.len(),
+            median_total_cycles: median(kept.iter().map(|m| m.total_cycles)),
+            median_proving_time_ms: median(kept.iter().map(|m| m.proving_time_ms)),
+            median_proof_size_bytes: median(kept.iter().map(|m| m.proof_size_bytes)),
         })
     }
     
-    /// Run multiple simulations in parallel
+    /// Run multiple simulations, returning results in input order
+    ///
+    /// With the `rayon` feature the inputs run in parallel; they share no
+    /// state, so each result is the same as a lone `run`. A failed input
+    /// yields an empty result rather than aborting the batch.
     pub fn run_batch(&self, inputs: Vec<SimulationInput>) -> Vec<RunnerResult> {
-        // For now, run sequentially (async parallel would require tokio runtime)
-        let mut results = Vec::new();
-        for input in inputs {
-            let result = self.run(input).unwrap_or_else(|e| {
-                eprintln!("Simulation failed: {}", e);
-                RunnerResult {
-                    trace: SimulationTrace {
-                        input: SimulationInput {
-                            world_width: 100.0,
-                            world_height: 100.0,
-                            gravity: [0.0, -9.81],
-                            timestep: 0.016,
-                            restitution: 0.8,
-                            position_correction: 0.8,
+        // Reuse a world's circle buffer across inputs (one per worker)
+        #[cfg(feature = "rayon")]
+        {
+            use rayon::prelude::*;
+            inputs
+                .into_par_iter()
+                .map_init(|| World::new(0.0, 0.0), |world, input| self.run_or_placeholder(world, input))
+                .collect()
+        }
+        #[cfg(not(feature = "rayon"))]
+        {
+            let mut world = World::new(0.0, 0.0);
+            inputs
+                .into_iter()
+                .map(|input| self.run_or_placeholder(&mut world, input))
+                .collect()
+        }
+    }
+    
+    /// `run_in`, logging a failure and standing in an empty result for it
+    fn run_or_placeholder(&self, world: &mut World, input: SimulationInput) -> RunnerResult {
+        self.run_in(world, input, |_| {}).unwrap_or_else(|e| {
+            eprintln!("Simulation failed: {}", e);
+            RunnerResult {
+                trace: SimulationTrace {
+                    input: SimulationInput {
+                        name: None,
+                        description: None,
+                        author: None,
+                        world_width: 100.0,
+                        world_height: 100.0,
+                        gravity: [0.0, -9.81],
+                        y_down: false,
+                        timestep: 0.016,
+                        restitution: 0.8,
+                        position_correction: 0.8,
+                        collision_preset: None,
+                        circles: vec![],
+                        num_steps: 0,
+                        record_trajectory: false,
+                        seed: 0,
+                        checkpoint_interval: 0,
+                    },
+                    states: vec![],
+                    output: determinisk_core::SimulationOutput {
+                        final_state: determinisk_core::SimulationState {
+                            step: 0,
+                            time: 0.0,
                             circles: vec![],
-                            num_steps: 0,
-                            record_trajectory: false,
-                            seed: 0,
+                            frame_collisions: 0,
+                            frame_boundary_hits: 0,
+                            kinetic_energy: 0.0,
+                            potential_energy: 0.0,
                         },
-                        states: vec![],
-                        output: determinisk_core::SimulationOutput {
-                            final_state: determinisk_core::SimulationState {
-                                step: 0,
-                                time: 0.0,
-                                circles: vec![],
-                                frame_collisions: 0,
-                                frame_boundary_hits: 0,
-                            },
-                            steps_executed: 0,
-                            metrics: determinisk_core::SimulationMetrics {
-                                total_energy: 0.0,
-                                max_velocity: 0.0,
-                                collision_count: 0,
-                                boundary_hits: 0,
-                            },
+                        steps_executed: 0,
+                        metrics: determinisk_core::SimulationMetrics {
+                            total_energy: 0.0,
+                            max_velocity: 0.0,
+                            collision_count: 0,
+                            boundary_hits: 0,
+                            gravity: [0.0, -9.81],
+                            world_size: [100.0, 100.0],
                         },
                     },
-                    proof_metrics: None,
-                    execution_time_ms: 0,
-                }
-            });
-            results.push(result);
+                },
+                proof_metrics: None,
+                execution_time_ms: 0,
+                determinism_verified: None,
+                journal: None,
+                receipt_bytes: None,
+            }
+        })
+    }
+}
+
+/// Median of a non-empty sequence (lower middle for even counts)
+fn median<T: Ord + Copy>(values: impl Iterator<Item = T>) -> T {
+    let mut values: Vec<T> = values.collect();
+    values.sort();
+    values[(values.len() - 1) / 2]
+}
+
+/// Step a copy of the world through the run, checking `is_sane` periodically
+fn check_sanity(mut world: World, num_steps: u32) -> Result<(), String> {
+    for step in 1..=num_steps {
+        world.step();
+        if (step.is_multiple_of(SANITY_CHECK_INTERVAL) || step == num_steps) && !world.is_sane() {
+            return Err(format!(
+                "simulation exploded by step {} (bodies far outside the world or moving too fast); not proving",
+                step,
+            ));
         }
-        results
     }
+    Ok(())
 }
 
 /// Generate proof for a simulation
@@ -166,8 +533,9 @@ fn generate_proof(
     input: SimulationInput,
     metrics: Arc<Mutex<Option<ProofMetrics>>>,
     verbose: bool,
-    #[allow(unused_variables)] segment_po2: u32,
-) -> Option<ProofMetrics> {
+    segment_po2: u32,
+    memory_budget: Option<u64>,
+) -> Option<ProofOutput> {
     if verbose {
         println!("Generating proof with backend: {:?}", backend);
     }
@@ -183,31 +551,45 @@ fn generate_proof(
         proving_time_ms: 0,
         verification_time_ms: None,
         zkvm_backend: "Mock (Generating...)".to_string(),
+        peak_memory_bytes: None,
     });
     
+    let memory_sampler = PeakRssSampler::start();
+    
+    // Show segments so far on the "generating" status
+    let publish_progress = |progress: ProofProgress| {
+        if let Some(current) = metrics.lock().unwrap().as_mut() {
+            current.segments = progress.segments_done;
+        }
+    };
+    
     // Simulate proof generation based on backend
-    let proof_metrics = match backend {
+    let (proof_metrics, journal, receipt_bytes, receipt_verified) = match backend {
         ZkVmBackend::Mock => {
-            // Mock proof generation with longer delay
-            thread::sleep(std::time::Duration::from_secs(5));
+            // Mock proof generation, one segment at a time
+            let segments = prove_segments(&input, segment_po2, publish_progress);
+            
+            // Commit the natively computed journal, as an honest guest would
+            let journal = input.execute_journal();
             
-            // Use the input to avoid unused warning
-            let _num_steps = input.num_steps;
+            // Time a real verify call rather than reporting a fixed figure
+            let (_, verification_time) = MockBackend::default().verify_timed(&[]);
             
-            ProofMetrics {
+            (ProofMetrics {
                 total_cycles: 100_000,
                 user_cycles: Some(80_000),
-                segments: 1,
+                segments,
                 proof_size_bytes: 1024,
                 proving_time_ms: 2000,
-                verification_time_ms: Some(10),
+                verification_time_ms: Some(verification_time),
                 zkvm_backend: "Mock".to_string(),
-            }
+                peak_memory_bytes: None,
+            }, Some(journal), None, true)
         }
         #[cfg(feature = "risc0")]
         ZkVmBackend::Risc0 => {
-            use methods::{PHYSICS_GUEST_ELF, PHYSICS_GUEST_ID};
-            use risc0_zkvm::{default_prover, ExecutorEnv};
+            use crate::proof::risc0::prove_with_progress;
+            use methods::PHYSICS_GUEST_ID;
             
             // Update status
             *metrics.lock().unwrap() = Some(ProofMetrics {
@@ -218,21 +600,13 @@ fn generate_proof(
                 proving_time_ms: 0,
                 verification_time_ms: None,
                 zkvm_backend: "RISC Zero (Generating...)".to_string(),
+                peak_memory_bytes: None,
             });
             
-            // Create executor environment with simulation input and segment configuration
-            let env = ExecutorEnv::builder()
-                .write(&input)
-                .unwrap()
-                .segment_limit_po2(segment_po2)
-                .build()
-                .unwrap();
-            
-            // Generate proof
-            let prover = default_prover();
+            // Generate proof, counting segments as the executor seals them
             let prove_start = Instant::now();
             
-            match prover.prove(env, PHYSICS_GUEST_ELF) {
+            match prove_with_progress(&input, segment_po2, publish_progress) {
                 Ok(prove_info) => {
                     let proving_time = prove_start.elapsed().as_millis();
                     
@@ -241,32 +615,44 @@ fn generate_proof(
                     
                     // Verify the proof!
                     let verify_start = Instant::now();
-                    match receipt.verify(PHYSICS_GUEST_ID) {
+                    let receipt_verified = match receipt.verify(PHYSICS_GUEST_ID) {
                         Ok(_) => {
                             if verbose {
                                 println!("✓ Proof verified successfully!");
                             }
+                            true
                         }
                         Err(e) => {
                             eprintln!("✗ Proof verification failed: {}", e);
+                            false
                         }
-                    }
+                    };
                     let verification_time = verify_start.elapsed().as_millis();
                     
+                    // Decode the committed journal for the determinism
+                    // cross-check; an unverified one attests to nothing
+                    let journal: Option<JournalOutput> = if receipt_verified {
+                        receipt.journal.decode().ok()
+                    } else {
+                        None
+                    };
+                    
                     // Get actual proof size (serialized receipt)
                     let proof_bytes = bincode::serialize(&receipt).unwrap_or_default();
                     let proof_size = proof_bytes.len();
                     
-                    // Save proof to file
-                    let timestamp = std::time::SystemTime::now()
-                        .duration_since(std::time::UNIX_EPOCH)
-                        .unwrap_or_default()
-                        .as_secs();
-                    let proof_filename = format!("proof_risc0_{}.bin", timestamp);
-                    if let Err(e) = std::fs::write(&proof_filename, &proof_bytes) {
-                        eprintln!("Failed to save proof to file: {}", e);
-                    } else if verbose {
-                        println!("Proof saved to: {}", proof_filename);
+                    // Save proof to file, unless it failed to verify
+                    if receipt_verified {
+                        let timestamp = std::time::SystemTime::now()
+                            .duration_since(std::time::UNIX_EPOCH)
+                            .unwrap_or_default()
+                            .as_secs();
+                        let proof_filename = format!("proof_risc0_{}.bin", timestamp);
+                        if let Err(e) = std::fs::write(&proof_filename, &proof_bytes) {
+                            eprintln!("Failed to save proof to file: {}", e);
+                        } else if verbose {
+                            println!("Proof saved to: {}", proof_filename);
+                        }
                     }
                     
                     // Get cycle count from stats
@@ -281,7 +667,7 @@ fn generate_proof(
                         println!("Segments: {}", segments);
                     }
                     
-                    ProofMetrics {
+                    (ProofMetrics {
                         total_cycles,
                         user_cycles: Some(user_cycles),
                         segments: segments as u32,
@@ -289,34 +675,45 @@ fn generate_proof(
                         proving_time_ms: proving_time,
                         verification_time_ms: Some(verification_time),
                         zkvm_backend: "RISC Zero".to_string(),
-                    }
+                        peak_memory_bytes: None,
+                    }, journal, receipt_verified.then_some(proof_bytes), receipt_verified)
                 }
                 Err(e) => {
                     eprintln!("RISC Zero proof generation failed: {}", e);
                     // Fallback to mock
                     thread::sleep(std::time::Duration::from_secs(2));
-                    ProofMetrics {
+                    (ProofMetrics {
                         total_cycles: 100_000,
                         user_cycles: Some(80_000),
                         segments: 1,
                         proof_size_bytes: 1024,
                         proving_time_ms: 2000,
-                        verification_time_ms: Some(10),
+                        verification_time_ms: None,
                         zkvm_backend: format!("Mock (RISC Zero error: {})", e),
-                    }
+                        peak_memory_bytes: None,
+                    }, None, None, true)
                 }
             }
         }
         #[cfg(feature = "sp1")]
         ZkVmBackend::Sp1 => {
-            // Real SP1 proof generation would go here
-            todo!("SP1 proof generation")
+            // `run_in` rejects this first; don't leave a stale status behind
+            eprintln!("{}", SP1_UNAVAILABLE);
+            *metrics.lock().unwrap() = None;
+            return None;
         }
     };
     
     let proving_time = start.elapsed().as_millis();
     let mut final_metrics = proof_metrics;
     final_metrics.proving_time_ms = proving_time;
+    final_metrics.peak_memory_bytes = memory_sampler.finish();
+    
+    if let (Some(peak), Some(budget)) = (final_metrics.peak_memory_bytes, memory_budget) {
+        if let Some(warning) = budget_warning(peak, budget) {
+            eprintln!("Warning: {}", warning);
+        }
+    }
     
     // Update shared metrics for live visualization
     *metrics.lock().unwrap() = Some(final_metrics.clone());
@@ -325,6 +722,289 @@ fn generate_proof(
         println!("Proof generated in {:.2}s", proving_time as f32 / 1000.0);
     }
     
-    Some(final_metrics)
+    Some(ProofOutput {
+        metrics: final_metrics,
+        journal,
+        receipt_bytes,
+        receipt_verified,
+    })
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_builder_defaults_match_documentation() {
+        let config = RunnerConfig::builder().build();
+        
+        assert!(!config.visualize);
+        assert!(!config.prove);
+        assert!(matches!(config.backend, ZkVmBackend::Mock));
+        assert!(!config.verbose);
+        assert_eq!(config.segment_po2, 20);
+        assert_eq!(config.memory_budget_bytes, None);
+        
+        let custom = RunnerConfig::builder().prove(true).segment_po2(18).build();
+        assert!(custom.prove && custom.segment_po2 == 18 && !custom.visualize);
+    }
+    
+    #[test]
+    fn test_unverified_receipt_fails_determinism_check() {
+        let input = determinisk_core::scenarios::simple_drop();
+        let journal = input.execute_journal();
+        let native_state_hash = journal.state_hash;
+        let output = |receipt_verified| ProofOutput {
+            metrics: ProofMetrics {
+                total_cycles: 1,
+                user_cycles: None,
+                segments: 1,
+                proof_size_bytes: 1,
+                proving_time_ms: 1,
+                verification_time_ms: None,
+                zkvm_backend: "test".to_string(),
+                peak_memory_bytes: None,
+            },
+            journal: Some(journal.clone()),
+            receipt_bytes: None,
+            receipt_verified,
+        };
+        
+        assert_eq!(check_determinism(Some(&output(true)), &input, native_state_hash), Some(true));
+        // A matching journal proves nothing if the receipt didn't verify
+        assert_eq!(check_determinism(Some(&output(false)), &input, native_state_hash), Some(false));
+        assert_eq!(check_determinism(None, &input, native_state_hash), None);
+    }
+    
+    #[test]
+    fn test_mock_proof_determinism_verified() {
+        let input = determinisk_core::scenarios::simple_drop();
+        let runner = SimulationRunner::new(RunnerConfig::builder().prove(true).build());
+        
+        let result = runner.run(input).unwrap();
+        
+        assert!(result.proof_metrics.unwrap().verification_time_ms.is_some());
+        assert_eq!(result.determinism_verified, Some(true));
+    }
+    
+    #[test]
+    fn test_prove_with_progress_counts_segments_up() {
+        let input = determinisk_core::scenarios::simple_drop();
+        // Small segments so the mock splits its proof several ways
+        let runner = SimulationRunner::new(RunnerConfig::builder().segment_po2(16).build());
+        let mut reported = Vec::new();
+        
+        let result = runner.prove_with_progress(input, |progress| reported.push(progress.segments_done)).unwrap();
+        
+        assert!(reported.len() > 1, "only saw {:?}", reported);
+        assert!(reported.windows(2).all(|pair| pair[0] < pair[1]), "not increasing: {:?}", reported);
+        assert_eq!(reported.last().copied(), Some(result.proof_metrics.unwrap().segments));
+    }
+    
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_mock_proof_records_peak_memory() {
+        let input = determinisk_core::scenarios::simple_drop();
+        let runner = SimulationRunner::new(RunnerConfig::builder().prove(true).memory_budget_bytes(Some(1)).build());
+        
+        let metrics = runner.run(input).unwrap().proof_metrics.unwrap();
+        
+        assert!(metrics.peak_memory_bytes.unwrap() > 0);
+    }
+    
+    #[test]
+    fn test_mock_proof_commits_checked_checkpoints() {
+        let mut input = determinisk_core::scenarios::simple_drop();
+        input.checkpoint_interval = 10;
+        let runner = SimulationRunner::new(RunnerConfig::builder().prove(true).build());
+        
+        let expected = (input.num_steps / 10) as usize;
+        let result = runner.run(input).unwrap();
+        
+        assert_eq!(result.journal.unwrap().checkpoints.len(), expected);
+        assert_eq!(result.determinism_verified, Some(true));
+    }
+    
+    #[test]
+    fn test_no_proof_leaves_determinism_unchecked() {
+        let input = determinisk_core::scenarios::simple_drop();
+        let runner = SimulationRunner::new(RunnerConfig::builder().build());
+        
+        let result = runner.run(input).unwrap();
+        
+        assert_eq!(result.determinism_verified, None);
+    }
+    
+    #[test]
+    fn test_headless_run_keeps_only_final_state() {
+        let input = determinisk_core::scenarios::three_body_collision();
+        let runner = SimulationRunner::new(RunnerConfig::builder().build());
+        
+        let result = runner.run(input.clone()).unwrap();
+        let recorded = World::from_input(&input).run_with_recording(input.num_steps);
+        
+        assert_eq!(result.trace.states.len(), 1);
+        assert_eq!(result.trace.output.final_state, recorded.output.final_state);
+        assert_eq!(result.trace.states[0], *recorded.states.last().unwrap());
+        assert_eq!(
+            result.trace.output.metrics.collision_count,
+            recorded.output.metrics.collision_count
+        );
+    }
+    
+    #[test]
+    fn test_mock_journal_conservation_matches_native() {
+        // Zero-gravity pool break: the committed values must match a native run
+        let input = determinisk_core::scenarios::pool_break();
+        let runner = SimulationRunner::new(RunnerConfig::builder().prove(true).build());
+        
+        let result = runner.run(input.clone()).unwrap();
+        let journal = result.journal.expect("mock backend commits a journal");
+        
+        let mut world = World::from_input(&input);
+        assert_eq!(journal.initial_conservation, world.conservation_bits());
+        for _ in 0..input.num_steps {
+            world.step();
+        }
+        assert_eq!(journal.final_conservation, world.conservation_bits());
+        
+        // Nothing moves before the break except the cue ball
+        assert_ne!(journal.initial_conservation.momentum, (0, 0));
+    }
+    
+    #[test]
+    fn test_mock_bench_median_cycles_deterministic() {
+        let input = determinisk_core::scenarios::simple_drop();
+        let runner = SimulationRunner::new(RunnerConfig::builder().build());
+        
+        let summary = runner.bench(&input, 3).unwrap();
+        
+        assert_eq!(summary.runs, 3);
+        assert_eq!(summary.kept, 1);
+        assert_eq!(summary.zkvm_backend, "Mock");
+        assert_eq!(summary.median_total_cycles, 100_000);
+        assert_eq!(summary.median_proof_size_bytes, 1024);
+        assert!(summary.median_proving_time_ms >= 5000);
+    }
+    
+    #[test]
+    fn test_median_takes_lower_middle() {
+        assert_eq!(median([3u64, 1, 2].into_iter()), 2);
+        assert_eq!(median([4u64, 1, 3, 2].into_iter()), 2);
+        assert_eq!(median([7u64].into_iter()), 7);
+    }
+    
+    #[test]
+    fn test_run_rejects_too_many_circles() {
+        let mut input = determinisk_core::scenarios::simple_drop();
+        let circle = input.circles[0].clone();
+        input.circles = vec![circle; determinisk_core::MAX_CIRCLES + 1];
+        let runner = SimulationRunner::new(RunnerConfig::builder().prove(true).build());
+        
+        // Rejected up front, before simulating or spawning the proof thread
+        let err = runner.run(input).unwrap_err();
+        assert!(err.to_string().contains("at most"), "{}", err);
+        let DeterminiskError::Validation(inner) = err else { panic!("expected a validation error") };
+        assert!(inner.downcast_ref::<TooManyCircles>().is_some());
+    }
+    
+    #[test]
+    fn test_status_transitions_generating_to_final() {
+        let input = determinisk_core::scenarios::simple_drop();
+        let runner = SimulationRunner::new(RunnerConfig::builder().prove(true).build());
+        
+        let mut statuses = Vec::new();
+        runner.run_with_status(input, |m| statuses.push(m.zkvm_backend.clone())).unwrap();
+        
+        // Segment progress can repeat the generating status
+        statuses.dedup();
+        assert_eq!(statuses, vec!["Mock (Generating...)".to_string(), "Mock".to_string()]);
+    }
+    
+    #[test]
+    fn test_exploding_simulation_is_not_proved() {
+        use determinisk_core::CircleConfig;
+        
+        // Big circles spawned on top of each other launch apart
+        let mut input = determinisk_core::scenarios::simple_drop();
+        input.world_width = 1000.0;
+        input.world_height = 1000.0;
+        input.gravity = [0.0, 0.0];
+        input.circles = (0..4)
+            .map(|i| CircleConfig {
+                position: [500.0 + 0.05 * i as f32, 500.0],
+                velocity: [0.0, 0.0],
+                radius: 20.0,
+                mass: 1.0,
+                angular_velocity: 0.0,
+            })
+            .collect();
+        let runner = SimulationRunner::new(RunnerConfig::builder().prove(true).build());
+        
+        let err = runner.run(input).unwrap_err();
+        assert!(err.to_string().contains("exploded"), "{}", err);
+    }
+    
+    #[test]
+    #[cfg(feature = "sp1")]
+    fn test_sp1_backend_reports_unsupported() {
+        let input = determinisk_core::scenarios::simple_drop();
+        let runner = SimulationRunner::new(RunnerConfig::builder().prove(true).backend(ZkVmBackend::Sp1).build());
+        
+        match runner.run(input) {
+            Err(DeterminiskError::Unsupported(message)) => assert_eq!(message, SP1_UNAVAILABLE),
+            other => panic!("expected an unsupported-backend error, got {:?}", other.map(|r| r.proof_metrics)),
+        }
+    }
+    
+    #[test]
+    fn test_save_proof_needs_a_receipt() {
+        let runner = SimulationRunner::new(RunnerConfig::builder().prove(true).build());
+        let result = runner.run(determinisk_core::scenarios::simple_drop()).unwrap();
+        assert!(result.receipt_bytes.is_none());
+        
+        let path = std::env::temp_dir().join(format!("determinisk_no_receipt_{}.bin", std::process::id()));
+        assert!(matches!(runner.save_proof(&result, &path), Err(DeterminiskError::Proof(_))));
+        assert!(!path.exists());
+    }
+    
+    #[test]
+    #[cfg(feature = "risc0")]
+    fn test_risc0_receipt_round_trip() {
+        let input = determinisk_core::scenarios::simple_drop();
+        let runner = SimulationRunner::new(RunnerConfig::builder().prove(true).backend(ZkVmBackend::Risc0).build());
+        let result = runner.run(input).unwrap();
+        
+        let path = std::env::temp_dir().join(format!("determinisk_receipt_{}.bin", std::process::id()));
+        runner.save_proof(&result, &path).unwrap();
+        let journal = SimulationRunner::verify_proof_file(&path);
+        std::fs::remove_file(&path).unwrap();
+        
+        assert_eq!(journal.unwrap(), result.journal.unwrap());
+    }
+    
+    #[test]
+    fn test_run_batch_matches_sequential_runs() {
+        let mut too_big = determinisk_core::scenarios::simple_drop();
+        too_big.circles = vec![too_big.circles[0].clone(); MAX_CIRCLES + 1];
+        let inputs = vec![
+            determinisk_core::scenarios::pool_break(),
+            determinisk_core::scenarios::simple_drop(),
+            too_big,
+            determinisk_core::scenarios::three_body_collision(),
+            determinisk_core::scenarios::pool_break_15(),
+        ];
+        let runner = SimulationRunner::new(RunnerConfig::default());
+        
+        let batch = runner.run_batch(inputs.clone());
+        
+        assert_eq!(batch.len(), inputs.len());
+        for (i, (result, input)) in batch.iter().zip(inputs).enumerate() {
+            match runner.run(input) {
+                Ok(sequential) => assert!(result.trace.bit_eq(&sequential.trace), "input {}", i),
+                // Failures keep their slot, as an empty placeholder
+                Err(_) => assert_eq!(result.trace.output.steps_executed, 0, "input {}", i),
+            }
+        }
+    }
+}
@@ -1,7 +1,7 @@
 //! SP1 host orchestration for physics proof generation
 
 use sp1_sdk::{include_elf, ProverClient, SP1Stdin};
-use determinisk_core::{Scalar, Vec2};
+use determinisk_core::{CircleConfig, JournalOutput, Scalar, SimulationInput, Vec2};
 use clap::Parser;
 
 /// SP1 program ELF will be generated by build script  
@@ -80,13 +80,33 @@ fn run_simulation<P: sp1_prover::components::SP1ProverComponents>(
     initial_vel: Vec2,
     prove: bool,
 ) {
-    // Prepare input
+    // Prepare input: a single ball in a 200x200 world
+    let input = SimulationInput {
+        name: None,
+        description: None,
+        author: None,
+        world_width: 200.0,
+        world_height: 200.0,
+        gravity: [0.0, -9.81],
+        y_down: false,
+        timestep: 1.0 / 60.0,
+        restitution: 0.8,
+        position_correction: 0.8,
+        collision_preset: None,
+        circles: vec![CircleConfig {
+            position: [initial_pos.x.to_float(), initial_pos.y.to_float()],
+            velocity: [initial_vel.x.to_float(), initial_vel.y.to_float()],
+            radius: 3.0,
+            mass: 1.0,
+            angular_velocity: 0.0,
+        }],
+        num_steps,
+        record_trajectory: false,
+        seed: 0,
+        checkpoint_interval: 0,
+    };
     let mut stdin = SP1Stdin::new();
-    stdin.write(&num_steps);
-    stdin.write(&Scalar::from_float(initial_pos.x.to_float()).to_bits());
-    stdin.write(&Scalar::from_float(initial_pos.y.to_float()).to_bits());
-    stdin.write(&Scalar::from_float(initial_vel.x.to_float()).to_bits());
-    stdin.write(&Scalar::from_float(initial_vel.y.to_float()).to_bits());
+    stdin.write(&input);
 
     let start = std::time::Instant::now();
     
@@ -105,21 +125,8 @@ fn run_simulation<P: sp1_prover::components::SP1ProverComponents>(
 
         // Extract public outputs from the proof
         let mut public_values = proof.public_values.clone();
-        let final_x = public_values.read::<i32>();
-        let final_y = public_values.read::<i32>();
-        let final_vx = public_values.read::<i32>();
-        let final_vy = public_values.read::<i32>();
-        let steps = public_values.read::<u32>();
-
-        println!("  Final position: ({:.2}, {:.2})",
-            Scalar::from_bits(final_x).to_float(),
-            Scalar::from_bits(final_y).to_float()
-        );
-        println!("  Final velocity: ({:.2}, {:.2})",
-            Scalar::from_bits(final_vx).to_float(),
-            Scalar::from_bits(final_vy).to_float()
-        );
-        println!("  Steps: {}", steps);
+        let journal = public_values.read::<JournalOutput>();
+        print_journal(&journal);
         
         // Serialize proof to get actual size
         let proof_bytes = bincode::serialize(&proof).expect("Failed to serialize proof");
@@ -149,21 +156,8 @@ fn run_simulation<P: sp1_prover::components::SP1ProverComponents>(
         let elapsed = start.elapsed();
 
         // Extract public outputs
-        let final_x = public_values.read::<i32>();
-        let final_y = public_values.read::<i32>();
-        let final_vx = public_values.read::<i32>();
-        let final_vy = public_values.read::<i32>();
-        let steps = public_values.read::<u32>();
-
-        println!("  Final position: ({:.2}, {:.2})",
-            Scalar::from_bits(final_x).to_float(),
-            Scalar::from_bits(final_y).to_float()
-        );
-        println!("  Final velocity: ({:.2}, {:.2})",
-            Scalar::from_bits(final_vx).to_float(),
-            Scalar::from_bits(final_vy).to_float()
-        );
-        println!("  Steps: {}", steps);
+        let journal = public_values.read::<JournalOutput>();
+        print_journal(&journal);
         println!("  Execution time: {:.2}s", elapsed.as_secs_f32());
         
         // Display execution metrics
@@ -171,4 +165,16 @@ fn run_simulation<P: sp1_prover::components::SP1ProverComponents>(
         println!("  Total cycles: {}", report.total_instruction_count());
         println!("  (Run with --prove to generate actual proof and see proof metrics)");
     }
-}
\ No newline at end of file
+}
+
+fn print_journal(journal: &JournalOutput) {
+    let (final_x, final_y) = journal.final_positions[0];
+    println!("  Final position: ({:.2}, {:.2})",
+        Scalar::from_bits(final_x).to_float(),
+        Scalar::from_bits(final_y).to_float()
+    );
+    println!("  Steps: {}", journal.steps_executed);
+    if !journal.checkpoints.is_empty() {
+        println!("  Checkpoints: {}", journal.checkpoints.len());
+    }
+}
@@ -0,0 +1,36 @@
+//! End-to-end SP1 proof of the single-ball scenario
+//!
+//! Slow with a real prover, so ignored by default:
+//! `SP1_PROVER=cpu cargo test --release -- --ignored`
+
+use determinisk_core::{scenarios, JournalOutput, World};
+use sp1_sdk::{include_elf, ProverClient, SP1Stdin};
+
+const ELF: &[u8] = include_elf!("determinisk-sp1-program");
+
+#[test]
+#[ignore = "generates a full SP1 proof"]
+fn test_simple_drop_proof_matches_native_run() {
+    let input = scenarios::simple_drop();
+    let client = ProverClient::from_env();
+    let mut stdin = SP1Stdin::new();
+    stdin.write(&input);
+
+    let (pk, vk) = client.setup(ELF);
+    let proof = client.prove(&pk, &stdin, sp1_sdk::SP1ProofMode::Core)
+        .expect("proving failed");
+    client.verify(&proof, &vk).expect("proof did not verify");
+
+    let mut public_values = proof.public_values.clone();
+    let journal = public_values.read::<JournalOutput>();
+
+    // The committed final position must be the native run's, bit for bit
+    let mut world = World::from_input(&input);
+    world.simulate(input.num_steps);
+    let native: Vec<(i32, i32)> = world.circles.iter()
+        .map(|c| (c.position.x.to_bits(), c.position.y.to_bits()))
+        .collect();
+    assert_eq!(journal.final_positions, native);
+    assert_eq!(journal.steps_executed, input.num_steps);
+    assert_eq!(journal.state_hash, world.state_hash());
+}